@@ -19,14 +19,17 @@ TIMEOUT=0
 
 usage() {
     cat <<EOF
-Uso: $0 [blargg|mooneye|all]
+Uso: $0 [blargg|mooneye|acid|all]
 
   blargg   Executa os testes Blargg versionados em gb-test-roms/ (padrão)
   mooneye  Executa os testes Mooneye automatizáveis em mooneye-roms/
-  all      Executa Blargg e Mooneye
+  acid     Executa dmg-acid2 e Mealybug Tearoom (FIFO) em acid-roms/
+  all      Executa Blargg, Mooneye e acid
 
 Antes de rodar Mooneye pela primeira vez:
   ./scripts/fetch_mooneye_roms.sh
+Antes de rodar acid pela primeira vez:
+  ./scripts/fetch_acid_roms.sh
 EOF
 }
 
@@ -39,7 +42,7 @@ cleanup() {
 trap cleanup INT TERM
 
 case "$SUITE" in
-    blargg|mooneye|all) ;;
+    blargg|mooneye|acid|all) ;;
     -h|--help|help)
         usage
         exit 0
@@ -63,7 +66,11 @@ run_rom() {
     name="${rom#*/}"
     name="${name%.gb}"
 
-    timeout 90 "$BINARY" --headless "$rom" >/dev/null 2>&1
+    local extra=()
+    # Testes de tela: renderizador FIFO comparado com a referência ao lado da ROM
+    [ -f "${rom%.gb}.pgm" ] && extra=(--ppu-fifo --screenshot-ref "${rom%.gb}.pgm")
+
+    timeout 90 "$BINARY" --headless "${extra[@]}" "$rom" >/dev/null 2>&1
     local code=$?
 
     case $code in
@@ -150,6 +157,21 @@ run_mooneye() {
     run_section_recursive "misc" "mooneye-roms/misc"
 }
 
+run_acid() {
+    if [ ! -d "acid-roms" ]; then
+        echo "Diretório acid-roms/ não encontrado." >&2
+        echo "Rode: ./scripts/fetch_acid_roms.sh" >&2
+        exit 2
+    fi
+    echo ""
+    echo "=========================================="
+    echo -e "${BOLD}Testes de tela (FIFO) — $(date '+%H:%M:%S')${NC}"
+    echo "=========================================="
+
+    run_section_flat "dmg-acid2" "acid-roms"
+    run_section_flat "mealybug" "acid-roms/mealybug"
+}
+
 case "$SUITE" in
     blargg)
         run_blargg
@@ -157,9 +179,13 @@ case "$SUITE" in
     mooneye)
         run_mooneye
         ;;
+    acid)
+        run_acid
+        ;;
     all)
         run_blargg
         run_mooneye
+        run_acid
         ;;
 esac
 
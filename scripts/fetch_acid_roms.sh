@@ -0,0 +1,96 @@
+#!/usr/bin/env bash
+# Baixa dmg-acid2 e Mealybug Tearoom com as telas de referência DMG.
+# As referências PNG viram PGM (o test runner lê PGM) com o python3 do sistema.
+set -euo pipefail
+
+repo_root="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
+out_dir="$repo_root/acid-roms"
+tmp_out_dir="$repo_root/acid-roms.tmp"
+
+acid2_rom="https://github.com/mattcurrie/dmg-acid2/releases/download/v1.0/dmg-acid2.gb"
+acid2_ref="https://raw.githubusercontent.com/mattcurrie/dmg-acid2/master/img/reference-dmg.png"
+mealybug_repo="https://github.com/mattcurrie/mealybug-tearoom-tests.git"
+
+tmp_dir="$(mktemp -d)"
+trap 'rm -rf "$tmp_dir"' EXIT
+
+# PNG (8 bits, cinza/RGB/RGBA/paleta, sem entrelaçamento) -> PGM P5
+png_to_pgm() {
+    python3 - "$1" "$2" <<'EOF'
+import struct, sys, zlib
+
+data = open(sys.argv[1], "rb").read()
+assert data[:8] == b"\x89PNG\r\n\x1a\n", "não é PNG"
+pos, idat, palette = 8, b"", None
+while pos < len(data):
+    length, kind = struct.unpack(">I4s", data[pos:pos + 8])
+    body = data[pos + 8:pos + 8 + length]
+    if kind == b"IHDR":
+        width, height, depth, color, _, _, interlace = struct.unpack(">IIBBBBB", body)
+    elif kind == b"PLTE":
+        palette = [body[i:i + 3] for i in range(0, len(body), 3)]
+    elif kind == b"IDAT":
+        idat += body
+    pos += 12 + length
+assert depth == 8 and interlace == 0, "PNG não suportado"
+channels = {0: 1, 2: 3, 3: 1, 4: 2, 6: 4}[color]
+raw = zlib.decompress(idat)
+stride = width * channels
+rows, prev, pos = [], bytearray(stride), 0
+for _ in range(height):
+    kind, line = raw[pos], bytearray(raw[pos + 1:pos + 1 + stride])
+    pos += 1 + stride
+    for i in range(stride):
+        a = line[i - channels] if i >= channels else 0
+        b = prev[i]
+        c = prev[i - channels] if i >= channels else 0
+        if kind == 1:
+            line[i] = (line[i] + a) & 0xFF
+        elif kind == 2:
+            line[i] = (line[i] + b) & 0xFF
+        elif kind == 3:
+            line[i] = (line[i] + (a + b) // 2) & 0xFF
+        elif kind == 4:
+            p = a + b - c
+            pa, pb, pc = abs(p - a), abs(p - b), abs(p - c)
+            pred = a if pa <= pb and pa <= pc else (b if pb <= pc else c)
+            line[i] = (line[i] + pred) & 0xFF
+    rows.append(line)
+    prev = line
+gray = bytearray()
+for line in rows:
+    for x in range(width):
+        px = line[x * channels:(x + 1) * channels]
+        if color == 3:
+            px = palette[px[0]]
+        gray.append(sum(px[:3]) // len(px[:3]))
+with open(sys.argv[2], "wb") as f:
+    f.write(b"P5\n%d %d\n255\n" % (width, height) + bytes(gray))
+EOF
+}
+
+rm -rf "$tmp_out_dir"
+mkdir -p "$tmp_out_dir/mealybug"
+
+echo "Downloading dmg-acid2"
+curl -fL -o "$tmp_out_dir/dmg-acid2.gb" "$acid2_rom"
+curl -fL -o "$tmp_dir/dmg-acid2.png" "$acid2_ref"
+png_to_pgm "$tmp_dir/dmg-acid2.png" "$tmp_out_dir/dmg-acid2.pgm"
+
+echo "Cloning $mealybug_repo"
+git clone --depth 1 "$mealybug_repo" "$tmp_dir/mealybug"
+unzip -q -o "$tmp_dir/mealybug/mealybug-tearoom-tests.zip" -d "$tmp_dir/mealybug-roms"
+while IFS= read -r rom; do
+    name="$(basename "$rom" .gb)"
+    ref="$tmp_dir/mealybug/expected/DMG-blob/$name.png"
+    # Só os testes com tela de referência DMG
+    [ -f "$ref" ] || continue
+    cp "$rom" "$tmp_out_dir/mealybug/"
+    png_to_pgm "$ref" "$tmp_out_dir/mealybug/$name.pgm"
+done < <(find "$tmp_dir/mealybug-roms" -name '*.gb' -type f | sort)
+
+rm -rf "$out_dir"
+mv "$tmp_out_dir" "$out_dir"
+
+rom_count="$(find "$out_dir" -type f -name '*.pgm' | wc -l | tr -d ' ')"
+echo "Extracted $rom_count screenshot tests into $out_dir"
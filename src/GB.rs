@@ -5,14 +5,24 @@ pub mod CPU;
 pub mod PPU;
 pub mod RAM;
 pub mod bus;
+pub mod cartdb;
 pub mod cartridge;
+pub mod compat_palettes;
 pub mod debugger;
+pub mod four_player;
+pub mod infrared;
 pub mod instructions;
 pub mod joypad;
 pub mod mbc;
 pub mod microcode;
+pub mod netlink;
+pub mod printer;
 pub mod registers;
+pub mod rewind;
+pub mod savestate;
 pub mod sdl_runner;
+pub mod serial;
+pub mod sgb;
 pub mod test_runner;
 pub mod timer;
 pub mod trace;
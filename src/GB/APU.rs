@@ -1,5 +1,7 @@
 #![allow(non_snake_case)]
 
+use crate::GB::savestate::{StateReader, StateWriter};
+
 // ============================================================================
 // ESTRUTURAS DE PRECISÃO DE HARDWARE
 // Organizadas em seções para facilitar manutenção
@@ -44,6 +46,15 @@ impl FrameSequencer {
     pub fn is_length_clock_next(&self) -> bool {
         self.step % 2 == 0
     }
+
+    pub fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.step);
+    }
+
+    pub fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.step = r.read_u8()?;
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -134,6 +145,23 @@ impl Envelope {
         self.timer = 0;
         self.stopped = false;
     }
+
+    pub fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.volume);
+        w.write_bool(self.direction);
+        w.write_u8(self.period);
+        w.write_u8(self.timer);
+        w.write_bool(self.stopped);
+    }
+
+    pub fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.volume = r.read_u8()?;
+        self.direction = r.read_bool()?;
+        self.period = r.read_u8()?;
+        self.timer = r.read_u8()?;
+        self.stopped = r.read_bool()?;
+        Ok(())
+    }
 }
 
 /// Sweep Unit com quirks de hardware (overflow e negate-to-add)
@@ -229,6 +257,25 @@ impl SweepUnit {
     pub fn reset_negate_flag(&mut self) {
         self.negate_used = false;
     }
+
+    pub fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.period);
+        w.write_bool(self.direction);
+        w.write_u8(self.shift);
+        w.write_u8(self.timer);
+        w.write_bool(self.enabled);
+        w.write_bool(self.negate_used);
+    }
+
+    pub fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.period = r.read_u8()?;
+        self.direction = r.read_bool()?;
+        self.shift = r.read_u8()?;
+        self.timer = r.read_u8()?;
+        self.enabled = r.read_bool()?;
+        self.negate_used = r.read_bool()?;
+        Ok(())
+    }
 }
 
 /// Length Counter com extra clocking quirk
@@ -319,6 +366,19 @@ impl LengthCounter {
     pub fn is_enabled(&self) -> bool {
         self.enable
     }
+
+    pub fn save_state(&self, w: &mut StateWriter) {
+        w.write_u16(self.counter);
+        w.write_bool(self.enable);
+        w.write_u16(self.max_length);
+    }
+
+    pub fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.counter = r.read_u16()?;
+        self.enable = r.read_bool()?;
+        self.max_length = r.read_u16()?;
+        Ok(())
+    }
 }
 
 // ============================================================================
@@ -1668,4 +1728,171 @@ impl APU {
         // Canal 4
         self.ch4_envelope.step();
     }
+
+    // ========== SAVE STATE ==========
+
+    pub fn save_state(&self, w: &mut StateWriter) {
+        w.write_bool(self.ch1_enabled);
+        w.write_u8(self.ch1_sweep_period);
+        w.write_bool(self.ch1_sweep_direction);
+        w.write_u8(self.ch1_sweep_shift);
+        w.write_u8(self.ch1_wave_duty);
+        w.write_u8(self.ch1_length_timer);
+        w.write_u8(self.ch1_envelope_initial);
+        w.write_bool(self.ch1_envelope_direction);
+        w.write_u8(self.ch1_envelope_period);
+        w.write_u16(self.ch1_frequency);
+        w.write_bool(self.ch1_length_enable);
+        w.write_bool(self.ch2_enabled);
+        w.write_u8(self.ch2_wave_duty);
+        w.write_u8(self.ch2_length_timer);
+        w.write_u8(self.ch2_envelope_initial);
+        w.write_bool(self.ch2_envelope_direction);
+        w.write_u8(self.ch2_envelope_period);
+        w.write_u16(self.ch2_frequency);
+        w.write_bool(self.ch2_length_enable);
+        w.write_bool(self.ch3_enabled);
+        w.write_bool(self.ch3_dac_enable);
+        w.write_u8(self.ch3_length_timer);
+        w.write_u8(self.ch3_output_level);
+        w.write_u16(self.ch3_frequency);
+        w.write_bool(self.ch3_length_enable);
+        w.write_bytes(&self.ch3_wave_ram);
+        w.write_u8(self.ch3_current_sample_byte);
+        w.write_u8(self.ch3_sample_index);
+        w.write_bool(self.ch3_wave_accessible);
+        w.write_u32(self.ch3_wave_accessible_idx as u32);
+        w.write_u8(self.ch3_wave_access_window);
+        w.write_u8(self.ch3_trigger_corrupt_window);
+        w.write_u32(self.ch3_buffer_byte_idx as u32);
+        w.write_u32(self.ch3_last_fetched_idx as u32);
+        w.write_bool(self.ch3_fetch_phase == Ch3FetchPhase::Opening);
+        w.write_u32(self.ch3_fetch_pending_idx as u32);
+        w.write_bool(self.ch4_enabled);
+        w.write_u8(self.ch4_length_timer);
+        w.write_u8(self.ch4_envelope_initial);
+        w.write_bool(self.ch4_envelope_direction);
+        w.write_u8(self.ch4_envelope_period);
+        w.write_u8(self.ch4_clock_shift);
+        w.write_bool(self.ch4_width_mode);
+        w.write_u8(self.ch4_divisor_code);
+        w.write_bool(self.ch4_length_enable);
+        w.write_u8(self.left_volume);
+        w.write_u8(self.right_volume);
+        w.write_bool(self.vin_left_enable);
+        w.write_bool(self.vin_right_enable);
+        w.write_bool(self.ch1_left);
+        w.write_bool(self.ch1_right);
+        w.write_bool(self.ch2_left);
+        w.write_bool(self.ch2_right);
+        w.write_bool(self.ch3_left);
+        w.write_bool(self.ch3_right);
+        w.write_bool(self.ch4_left);
+        w.write_bool(self.ch4_right);
+        w.write_bool(self.sound_enable);
+        w.write_bool(self.is_cgb);
+        self.frame_sequencer.save_state(w);
+        self.ch1_envelope.save_state(w);
+        self.ch1_sweep.save_state(w);
+        self.ch1_length.save_state(w);
+        self.ch2_envelope.save_state(w);
+        self.ch2_length.save_state(w);
+        self.ch3_length.save_state(w);
+        self.ch4_envelope.save_state(w);
+        self.ch4_length.save_state(w);
+        w.write_u16(self.ch1_frequency_shadow);
+        w.write_u8(self.ch1_wave_position);
+        w.write_u8(self.ch2_wave_position);
+        w.write_u8(self.ch3_wave_position);
+        w.write_u16(self.ch4_lfsr);
+        w.write_u32(self.ch1_frequency_timer);
+        w.write_u32(self.ch2_frequency_timer);
+        w.write_u32(self.ch3_frequency_timer);
+        w.write_u32(self.ch4_frequency_timer);
+    }
+
+    pub fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.ch1_enabled = r.read_bool()?;
+        self.ch1_sweep_period = r.read_u8()?;
+        self.ch1_sweep_direction = r.read_bool()?;
+        self.ch1_sweep_shift = r.read_u8()?;
+        self.ch1_wave_duty = r.read_u8()?;
+        self.ch1_length_timer = r.read_u8()?;
+        self.ch1_envelope_initial = r.read_u8()?;
+        self.ch1_envelope_direction = r.read_bool()?;
+        self.ch1_envelope_period = r.read_u8()?;
+        self.ch1_frequency = r.read_u16()?;
+        self.ch1_length_enable = r.read_bool()?;
+        self.ch2_enabled = r.read_bool()?;
+        self.ch2_wave_duty = r.read_u8()?;
+        self.ch2_length_timer = r.read_u8()?;
+        self.ch2_envelope_initial = r.read_u8()?;
+        self.ch2_envelope_direction = r.read_bool()?;
+        self.ch2_envelope_period = r.read_u8()?;
+        self.ch2_frequency = r.read_u16()?;
+        self.ch2_length_enable = r.read_bool()?;
+        self.ch3_enabled = r.read_bool()?;
+        self.ch3_dac_enable = r.read_bool()?;
+        self.ch3_length_timer = r.read_u8()?;
+        self.ch3_output_level = r.read_u8()?;
+        self.ch3_frequency = r.read_u16()?;
+        self.ch3_length_enable = r.read_bool()?;
+        r.read_bytes(&mut self.ch3_wave_ram)?;
+        self.ch3_current_sample_byte = r.read_u8()?;
+        self.ch3_sample_index = r.read_u8()?;
+        self.ch3_wave_accessible = r.read_bool()?;
+        self.ch3_wave_accessible_idx = r.read_u32()? as usize;
+        self.ch3_wave_access_window = r.read_u8()?;
+        self.ch3_trigger_corrupt_window = r.read_u8()?;
+        self.ch3_buffer_byte_idx = r.read_u32()? as usize;
+        self.ch3_last_fetched_idx = r.read_u32()? as usize;
+        self.ch3_fetch_phase = if r.read_bool()? {
+            Ch3FetchPhase::Opening
+        } else {
+            Ch3FetchPhase::Idle
+        };
+        self.ch3_fetch_pending_idx = r.read_u32()? as usize;
+        self.ch4_enabled = r.read_bool()?;
+        self.ch4_length_timer = r.read_u8()?;
+        self.ch4_envelope_initial = r.read_u8()?;
+        self.ch4_envelope_direction = r.read_bool()?;
+        self.ch4_envelope_period = r.read_u8()?;
+        self.ch4_clock_shift = r.read_u8()?;
+        self.ch4_width_mode = r.read_bool()?;
+        self.ch4_divisor_code = r.read_u8()?;
+        self.ch4_length_enable = r.read_bool()?;
+        self.left_volume = r.read_u8()?;
+        self.right_volume = r.read_u8()?;
+        self.vin_left_enable = r.read_bool()?;
+        self.vin_right_enable = r.read_bool()?;
+        self.ch1_left = r.read_bool()?;
+        self.ch1_right = r.read_bool()?;
+        self.ch2_left = r.read_bool()?;
+        self.ch2_right = r.read_bool()?;
+        self.ch3_left = r.read_bool()?;
+        self.ch3_right = r.read_bool()?;
+        self.ch4_left = r.read_bool()?;
+        self.ch4_right = r.read_bool()?;
+        self.sound_enable = r.read_bool()?;
+        self.is_cgb = r.read_bool()?;
+        self.frame_sequencer.load_state(r)?;
+        self.ch1_envelope.load_state(r)?;
+        self.ch1_sweep.load_state(r)?;
+        self.ch1_length.load_state(r)?;
+        self.ch2_envelope.load_state(r)?;
+        self.ch2_length.load_state(r)?;
+        self.ch3_length.load_state(r)?;
+        self.ch4_envelope.load_state(r)?;
+        self.ch4_length.load_state(r)?;
+        self.ch1_frequency_shadow = r.read_u16()?;
+        self.ch1_wave_position = r.read_u8()?;
+        self.ch2_wave_position = r.read_u8()?;
+        self.ch3_wave_position = r.read_u8()?;
+        self.ch4_lfsr = r.read_u16()?;
+        self.ch1_frequency_timer = r.read_u32()?;
+        self.ch2_frequency_timer = r.read_u32()?;
+        self.ch3_frequency_timer = r.read_u32()?;
+        self.ch4_frequency_timer = r.read_u32()?;
+        Ok(())
+    }
 }
@@ -1,6 +1,7 @@
 use crate::GB::instructions;
 use crate::GB::microcode;
 use crate::GB::registers;
+use crate::GB::savestate::{SAVE_STATE_MAGIC, SAVE_STATE_VERSION, StateReader, StateWriter};
 
 pub struct CPU {
     pub registers: registers::Registers,
@@ -26,10 +27,115 @@ pub enum BootModel {
     Agb,    // Game Boy Advance running in Game Boy Color mode (AGB/AGS)
 }
 
+/// CRC32 das boot ROMs conhecidas. Sem hash do CGB0 conferido: ela cai na
+/// detecção por tamanho (CGB)
+const BOOT_ROM_CRC32: [(u32, BootModel); 8] = [
+    (0xC2F5CC97, BootModel::Dmg0),
+    (0x59C8598E, BootModel::DmgAbc),
+    (0xE6920754, BootModel::Mgb),
+    (0xEC8A83B9, BootModel::Sgb),
+    (0x53D0DD63, BootModel::Sgb2),
+    (0x41884E46, BootModel::Cgb),
+    (0xE8EF5318, BootModel::Cgb), // CGB-E
+    (0xFFD6B0F1, BootModel::Agb),
+];
+
+impl BootModel {
+    /// Modelo de uma boot ROM conhecida pelo CRC32 da imagem
+    pub fn from_boot_rom_crc32(crc: u32) -> Option<BootModel> {
+        BOOT_ROM_CRC32
+            .iter()
+            .find(|&&(known, _)| known == crc)
+            .map(|&(_, model)| model)
+    }
+
+    /// Identifica o modelo pela imagem da boot ROM: primeiro pelo hash das
+    /// imagens conhecidas; as desconhecidas vão pelo tamanho e conteúdo.
+    /// 0x900 bytes é CGB. Nas imagens de 0x100 bytes, o valor escrito em FF50
+    /// (`LD A,n / LDH (50),A`) separa DMG/SGB (0x01) de MGB/SGB2 (0xFF), e só
+    /// a boot ROM do SGB escreve em P1 (`LDH (00),A`) para enviar o header.
+    pub fn from_boot_rom(data: &[u8]) -> Option<BootModel> {
+        if let Some(model) = BootModel::from_boot_rom_crc32(crate::GB::cartridge::crc32(data)) {
+            return Some(model);
+        }
+        match data.len() {
+            0x900 => return Some(BootModel::Cgb),
+            0x100 => {}
+            _ => return None,
+        }
+
+        let is_sgb = data.windows(2).any(|w| w == [0xE0, 0x00]);
+        let ff50_value = data
+            .windows(4)
+            .find(|w| w[0] == 0x3E && w[2] == 0xE0 && w[3] == 0x50)
+            .map(|w| w[1]);
+        let pocket = ff50_value == Some(0xFF);
+
+        Some(match (is_sgb, pocket) {
+            (false, false) => BootModel::DmgAbc,
+            (false, true) => BootModel::Mgb,
+            (true, false) => BootModel::Sgb,
+            (true, true) => BootModel::Sgb2,
+        })
+    }
+
+    /// Modelos que executam o modo CGB da ROM
+    pub fn is_cgb(self) -> bool {
+        matches!(self, BootModel::Cgb | BootModel::Cgb0 | BootModel::Agb)
+    }
+
+    /// Modelos com o Super Game Boy (pacotes de comando, cores e borda)
+    pub fn is_sgb(self) -> bool {
+        matches!(self, BootModel::Sgb | BootModel::Sgb2)
+    }
+
+    /// Nome usado por `--model` (dmg0, dmg, mgb, sgb, sgb2, cgb0, cgb, agb)
+    pub fn from_name(name: &str) -> Option<BootModel> {
+        match name.to_ascii_lowercase().as_str() {
+            "dmg0" => Some(BootModel::Dmg0),
+            "dmg" => Some(BootModel::DmgAbc),
+            "mgb" => Some(BootModel::Mgb),
+            "sgb" => Some(BootModel::Sgb),
+            "sgb2" => Some(BootModel::Sgb2),
+            "cgb0" => Some(BootModel::Cgb0),
+            "cgb" => Some(BootModel::Cgb),
+            "agb" => Some(BootModel::Agb),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BootModel::Dmg0 => "dmg0",
+            BootModel::DmgAbc => "dmg",
+            BootModel::Mgb => "mgb",
+            BootModel::Sgb => "sgb",
+            BootModel::Sgb2 => "sgb2",
+            BootModel::Cgb0 => "cgb0",
+            BootModel::Cgb => "cgb",
+            BootModel::Agb => "agb",
+        }
+    }
+
+    /// Modelo padrão para uma ROM: CGB se o header suportar cor, senão DMG
+    pub fn for_rom(rom: &[u8]) -> BootModel {
+        if crate::GB::cartridge::is_cgb_rom(rom) {
+            BootModel::Cgb
+        } else {
+            BootModel::DmgAbc
+        }
+    }
+}
+
 impl CPU {
     pub fn new(rom: Vec<u8>) -> Self {
-        let is_cgb = crate::GB::cartridge::is_cgb_only_rom(&rom);
-        let mbc = crate::GB::mbc::create_mbc(rom);
+        let mbc = crate::GB::mbc::create_mbc(rom.clone());
+        CPU::with_mbc(&rom, mbc)
+    }
+
+    /// Como `new`, com o mapper já criado (banco de cartuchos, --mapper)
+    pub fn with_mbc(rom: &[u8], mbc: Box<dyn crate::GB::mbc::MBC + Send>) -> Self {
+        let is_cgb = crate::GB::cartridge::is_cgb_rom(rom);
         let mut cpu = CPU {
             registers: registers::Registers::new(),
             bus: crate::GB::bus::MemoryBus::new(mbc),
@@ -42,10 +148,17 @@ impl CPU {
             cycles: 0,
         };
 
-        // O core gráfico ainda é DMG-only. ROMs CGB-compatible (flag 0x80)
-        // devem iniciar no caminho DMG; caso contrário elas tentam usar VRAM
-        // banks, tile attributes e paletas CGB que ainda não existem aqui.
+        // ROMs CGB-compatible (0x80) e CGB-only (0xC0) rodam em modo CGB;
+        // init_post_boot_model volta para o caminho DMG em modelos DMG/SGB.
         cpu.bus.set_cgb_mode(is_cgb);
+        cpu.bus.apu.set_cgb_mode(is_cgb);
+        cpu
+    }
+
+    /// Cria a CPU já no estado pós-boot do modelo indicado
+    pub fn with_model(rom: Vec<u8>, model: BootModel) -> Self {
+        let mut cpu = CPU::new(rom);
+        cpu.init_post_boot_model(model);
         cpu
     }
 
@@ -71,6 +184,36 @@ impl CPU {
         (hi << 8) | lo
     }
 
+    /// Mapeia uma boot ROM real e começa a execução em 0x0000.
+    /// No CGB o hardware sobe em modo CGB; a própria boot ROM decide (via KEY0)
+    /// se o cartucho roda em modo DMG.
+    pub fn load_boot_rom(&mut self, data: Vec<u8>) -> Result<BootModel, String> {
+        let model = BootModel::from_boot_rom(&data)
+            .ok_or_else(|| format!("Boot ROM com tamanho desconhecido: {} bytes", data.len()))?;
+        self.load_boot_rom_as(data, model)?;
+        Ok(model)
+    }
+
+    /// Como `load_boot_rom`, mas com o modelo escolhido pelo usuário
+    pub fn load_boot_rom_as(&mut self, data: Vec<u8>, model: BootModel) -> Result<(), String> {
+        if model.is_cgb() != (data.len() == 0x900) {
+            return Err(format!(
+                "Boot ROM de {} bytes não serve para o modelo {}",
+                data.len(),
+                model.name()
+            ));
+        }
+        self.bus.load_boot_rom(data)?;
+        self.init_power_on_ram(model);
+        self.bus.set_cgb_mode(model.is_cgb());
+        // VBK, BCPS/OCPS e FF72-FF75 existem desde o reset no hardware CGB
+        self.bus.set_cgb_compat_hwio(model.is_cgb());
+        self.bus.apu.set_cgb_mode(model.is_cgb());
+        self.bus.set_sgb_enabled(model.is_sgb());
+        self.registers.set_pc(0x0000);
+        Ok(())
+    }
+
     pub fn init_post_boot(&mut self) {
         let model = if self.bus.cgb_mode {
             BootModel::Cgb
@@ -80,7 +223,20 @@ impl CPU {
         self.init_post_boot_model(model);
     }
 
+    /// Estado pós-boot de cada modelo: registradores, IO, quirks de IO do CGB,
+    /// comportamento CGB do APU e o conteúdo da VRAM deixado pela boot ROM.
     pub fn init_post_boot_model(&mut self, model: BootModel) {
+        // Só um hardware CGB executa o modo CGB da ROM
+        if !model.is_cgb() {
+            self.bus.set_cgb_mode(false);
+        }
+        // O APU segue o hardware, mesmo com cartucho DMG num CGB
+        self.bus.apu.set_cgb_mode(model.is_cgb());
+        self.bus.set_sgb_enabled(model.is_sgb());
+        self.init_power_on_ram(model);
+        self.init_post_boot_vram(model);
+        self.bus.skip_cart_boot();
+
         match model {
             BootModel::Dmg0 => {
                 self.registers.set_af(0x0100);
@@ -221,24 +377,144 @@ impl CPU {
                 self.bus.set_div_counter(0xABCC);
             }
             BootModel::Cgb => {
-                self.bus.set_cgb_compat_hwio(true);
                 self.bus.write(0xFF00, 0x30);
                 // CGB-ABCDE: div_counter = 0x2678
                 self.bus.set_div_counter(0x2678);
             }
             BootModel::Cgb0 => {
-                self.bus.set_cgb_compat_hwio(true);
                 self.bus.write(0xFF00, 0x30);
                 // CGB-0 (first revision): div_counter = 0x2884
                 self.bus.set_div_counter(0x2884);
             }
             BootModel::Agb => {
-                self.bus.set_cgb_compat_hwio(true);
                 self.bus.write(0xFF00, 0x30);
                 // AGB/AGS: div_counter = 0x267C
                 self.bus.set_div_counter(0x267C);
             }
         }
+
+        // Registradores de IO do CGB (VBK, BCPS/OCPS, FF72-FF75) e quirks do STAT
+        if model.is_cgb() {
+            self.bus.set_cgb_compat_hwio(true);
+            // Cartucho DMG: paletas escolhidas pelo título, como na boot ROM
+            if !self.bus.cgb_mode {
+                let header: Vec<u8> = (0..0x0150).map(|addr| self.bus.read(addr)).collect();
+                let palettes = crate::GB::compat_palettes::palettes_for_rom(&header);
+                self.bus.ppu.load_compat_palettes(&palettes);
+            }
+        }
+    }
+
+    /// WRAM, HRAM e OAM ao ligar: lixo com semente fixa por modelo, o mesmo a
+    /// cada execução. VRAM fica com `init_post_boot_vram` (ou com a boot ROM)
+    fn init_power_on_ram(&mut self, model: BootModel) {
+        self.bus.fill_power_on_ram(0x4742_0000 + model as u64);
+    }
+
+    /// VRAM como a boot ROM deixa: zerada (os dois bancos no CGB) e, no
+    /// DMG/MGB, com o logo do header e o ® em 0x8010-0x819F / 0x9904-0x992F.
+    fn init_post_boot_vram(&mut self, model: BootModel) {
+        self.bus.ppu.vram.fill(0);
+        self.bus.ppu.vram1.fill(0);
+        if !matches!(model, BootModel::Dmg0 | BootModel::DmgAbc | BootModel::Mgb) {
+            return;
+        }
+
+        // Cada nibble do logo vira uma linha com os bits duplicados, escrita duas vezes
+        let mut addr = 0x0010;
+        for i in 0..48 {
+            let byte = self.bus.read(0x0104 + i);
+            for nibble in [byte >> 4, byte & 0x0F] {
+                let mut row = 0u8;
+                for bit in (0..4).rev() {
+                    row = (row << 2) | if nibble & (1 << bit) != 0 { 0b11 } else { 0 };
+                }
+                self.bus.ppu.vram[addr] = row;
+                self.bus.ppu.vram[addr + 2] = row;
+                addr += 4;
+            }
+        }
+        const REGISTERED: [u8; 8] = [0x3C, 0x42, 0xB9, 0xA5, 0xB9, 0xA5, 0x42, 0x3C];
+        for (i, row) in REGISTERED.iter().enumerate() {
+            self.bus.ppu.vram[0x0190 + i * 2] = *row;
+        }
+
+        for tile in 0..12u8 {
+            self.bus.ppu.vram[0x1904 + tile as usize] = tile + 1;
+            self.bus.ppu.vram[0x1924 + tile as usize] = tile + 13;
+        }
+        self.bus.ppu.vram[0x1910] = 0x19;
+    }
+
+    /// Serializa a máquina inteira (CPU, barramento, PPU, APU, timer e MBC)
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut w = StateWriter::new();
+        w.write_bytes(&SAVE_STATE_MAGIC);
+        w.write_u16(SAVE_STATE_VERSION);
+
+        w.write_u16(self.registers.get_af());
+        w.write_u16(self.registers.get_bc());
+        w.write_u16(self.registers.get_de());
+        w.write_u16(self.registers.get_hl());
+        w.write_u16(self.registers.get_sp());
+        w.write_u16(self.registers.get_pc());
+        w.write_bool(self.ime);
+        w.write_bool(self.ime_enable_next);
+        w.write_bool(self.halted);
+        w.write_bool(self.halt_bug);
+        w.write_bool(self.stopped);
+        w.write_u8(self.opcode);
+        w.write_u64(self.cycles);
+
+        self.bus.save_state(&mut w);
+        w.into_inner()
+    }
+
+    /// Restaura um save state gerado por `save_state` para a mesma ROM.
+    /// Em caso de erro a máquina volta ao estado anterior à chamada.
+    pub fn load_state(&mut self, data: &[u8]) -> Result<(), String> {
+        let backup = self.save_state();
+        let result = self.load_state_unchecked(data);
+        if result.is_err() {
+            let _ = self.load_state_unchecked(&backup);
+        }
+        result
+    }
+
+    fn load_state_unchecked(&mut self, data: &[u8]) -> Result<(), String> {
+        let mut r = StateReader::new(data);
+        let mut magic = [0u8; 4];
+        r.read_bytes(&mut magic)?;
+        if magic != SAVE_STATE_MAGIC {
+            return Err("Arquivo não é um save state".to_string());
+        }
+        let version = r.read_u16()?;
+        if version != SAVE_STATE_VERSION {
+            return Err(format!(
+                "Versão de save state não suportada: {} (esperado {})",
+                version, SAVE_STATE_VERSION
+            ));
+        }
+
+        self.registers.set_af(r.read_u16()?);
+        self.registers.set_bc(r.read_u16()?);
+        self.registers.set_de(r.read_u16()?);
+        self.registers.set_hl(r.read_u16()?);
+        self.registers.set_sp(r.read_u16()?);
+        self.registers.set_pc(r.read_u16()?);
+        self.ime = r.read_bool()?;
+        self.ime_enable_next = r.read_bool()?;
+        self.halted = r.read_bool()?;
+        self.halt_bug = r.read_bool()?;
+        self.stopped = r.read_bool()?;
+        self.opcode = r.read_u8()?;
+        self.cycles = r.read_u64()?;
+
+        self.bus.load_state(&mut r)?;
+        if r.remaining() != 0 {
+            return Err(format!("Save state com {} bytes excedentes", r.remaining()));
+        }
+        Ok(())
     }
 
     pub fn fetch_next(&mut self) -> u8 {
@@ -261,6 +537,13 @@ impl CPU {
     }
 
     pub fn execute_next(&mut self) -> (u64, bool) {
+        // GDMA/HDMA ou troca de velocidade: CPU parada, o resto do hardware segue
+        let stall = self.bus.take_cpu_stall_cycles();
+        if stall > 0 {
+            self.bus.tick(stall);
+            return (stall as u64, false);
+        }
+
         // Se CPU está em STOP, só acorda com Joypad
         if self.stopped {
             if self.bus.joypad_should_wake_from_stop() {
@@ -366,8 +649,7 @@ impl CPU {
             0x10 => {
                 // Em modo CGB com KEY1 bit 0 setado, STOP troca a velocidade da CPU
                 if self.bus.cgb_mode && (self.bus.key1 & 0x01) != 0 {
-                    self.bus.cgb_speed = !self.bus.cgb_speed;
-                    self.bus.key1 = 0; // limpa solicitação de troca
+                    self.bus.switch_speed();
                 } else {
                     self.stopped = true;
                 }
@@ -9,20 +9,34 @@ struct Sprite {
     y: u8,          // Posição Y (linha + 16)
     x: u8,          // Posição X (coluna + 8)
     tile_index: u8, // Índice do tile (0-255)
-    attributes: u8, // Bit 7=prioridade, 6=flip Y, 5=flip X, 4=paleta, 3-0=unused
+    attributes: u8, // Bit 7=prioridade, 6=flip Y, 5=flip X, 4=paleta DMG, 3=banco VRAM (CGB), 2-0=paleta CGB
 }
 
-use rand::Rng;
+/// Tons de cinza do DMG em RGB555 (shade 0 = branco ... 3 = preto)
+pub const DMG_SHADES_RGB555: [u16; 4] = [0x7FFF, 0x56B5, 0x294A, 0x0000];
+
+use crate::GB::compat_palettes::CompatPalettes;
+use crate::GB::savestate::{StateReader, StateWriter};
+
+mod fifo;
+
 pub struct PPU {
     // VRAM (Video RAM) - 8KB (0x8000-0x9FFF)
     // 0x8000-0x97FF: Tile data (384 tiles × 16 bytes = 6KB)
     // 0x9800-0x9BFF: Tile map 0 (32×32 = 1KB)
     // 0x9C00-0x9FFF: Tile map 1 (32×32 = 1KB)
     pub vram: [u8; 0x2000],
+    // VRAM banco 1 (apenas CGB): tiles extras + atributos do tile map
+    pub vram1: [u8; 0x2000],
+    pub vram_bank: u8, // 0xFF4F - VBK (bit 0)
 
     // Framebuffer - 160×144 pixels, cada pixel = 0-3 (2 bits por cor)
+    // Em modo CGB guarda o índice de cor antes da paleta
     pub framebuffer: [u8; 160 * 144],
 
+    // Framebuffer colorido em RGB555 (bits 0-4 R, 5-9 G, 10-14 B)
+    pub rgb_framebuffer: [u16; 160 * 144],
+
     /// Per-pixel BG priority buffer (true = BG/window pixel is opaque)
     pub bg_priority: [bool; 160 * 144],
 
@@ -42,6 +56,19 @@ pub struct PPU {
     // OAM (Object Attribute Memory) - 160 bytes (40 sprites × 4 bytes)
     pub oam: [u8; 160],
 
+    // ===== CGB =====
+    pub cgb_mode: bool, // Renderização CGB (atributos, bancos e paletas coloridas)
+    // PPU de um CGB: em modo DMG os shades passam pela paleta BG 0 e OBJ 0/1
+    pub cgb_hardware: bool,
+    pub bcps: u8,                 // 0xFF68 - BG palette index (bit 7 = auto-incremento)
+    pub ocps: u8,                 // 0xFF6A - OBJ palette index
+    pub bg_palette_ram: [u8; 64], // 8 paletas × 4 cores × 2 bytes (RGB555 little-endian)
+    pub obj_palette_ram: [u8; 64],
+    // Bit 7 dos atributos do BG na linha atual (BG sobre sprites)
+    bg_attr_priority: [bool; 160],
+    // Entrada no modo 0 de uma linha visível (dispara o HBlank DMA no barramento)
+    hblank_event: bool,
+
     // Controle de window: início e linha da window
     pub wy_trigger: bool,
     pub wy_pos: i32,
@@ -68,6 +95,10 @@ pub struct PPU {
     // do STAT após LCDC=0x80 retornem modo=0 (comportamento do hardware).
     lcd_on_stat_delay: bool,
     lcd_on_timing_quirk: bool,
+
+    // Renderizador alternativo com fetcher + FIFO de pixels (efeitos no meio da linha)
+    pub fifo_renderer: bool,
+    fifo: fifo::PixelFifo,
 }
 
 impl PPU {
@@ -122,20 +153,14 @@ impl PPU {
             }
         }
     }
+    /// VRAM e OAM zeradas: o conteúdo de power-on é do modelo (CPU)
     pub fn new() -> Self {
-        let mut rng = rand::thread_rng();
-
-        // VRAM com lixo de power-on
-        let mut vram = [0u8; 0x2000];
-        rng.fill(&mut vram[..]);
-
-        // OAM com lixo de power-on
-        let mut oam = [0u8; 160];
-        rng.fill(&mut oam[..]);
-
         PPU {
-            vram,
+            vram: [0; 0x2000],
+            vram1: [0; 0x2000],
+            vram_bank: 0,
             framebuffer: [0; 160 * 144],
+            rgb_framebuffer: [DMG_SHADES_RGB555[0]; 160 * 144],
             bg_priority: [false; 160 * 144],
             lcdc: 0x91, // Default pós-boot: LCD on, BG on, 8x8 sprites
             stat: 0x00,
@@ -148,7 +173,15 @@ impl PPU {
             obp1: 0xFF,
             wy: 0,
             wx: 0,
-            oam,
+            oam: [0; 160],
+            cgb_mode: false,
+            cgb_hardware: false,
+            bcps: 0,
+            ocps: 0,
+            bg_palette_ram: [0xFF; 64],
+            obj_palette_ram: [0xFF; 64],
+            bg_attr_priority: [false; 160],
+            hblank_event: false,
             frame_ready: false,
             headless: false,
             mode: 2, // Começa em OAM Search
@@ -160,6 +193,8 @@ impl PPU {
             cgb_mode2_vblank_stat_quirk: false,
             lcd_on_stat_delay: false,
             lcd_on_timing_quirk: false,
+            fifo_renderer: false,
+            fifo: fifo::PixelFifo::default(),
         }
     }
 
@@ -180,6 +215,14 @@ impl PPU {
     }
 
     fn mode3_end_clock(&self) -> u32 {
+        // FIFO: o modo 3 dura o que o fetcher levar (fim visível 1 M-cycle depois)
+        if self.fifo_renderer {
+            return if self.fifo.done {
+                self.fifo.end_clock + 4
+            } else {
+                456
+            };
+        }
         let scx_penalty = match self.scx & 0x07 {
             0 => 0,
             1..=4 => 4,
@@ -290,7 +333,8 @@ impl PPU {
         }
 
         // LCDC bit 0: BG/Window enable (ambos precisam estar on)
-        if (self.lcdc & 0x01) == 0 {
+        // No CGB o bit 0 só tira a prioridade do BG/window sobre os sprites
+        if (self.lcdc & 0x01) == 0 && !self.cgb_mode {
             return;
         }
 
@@ -324,8 +368,6 @@ impl PPU {
         let tile_y = (window_y as usize) / 8;
         let pixel_y = (window_y as usize) % 8;
 
-        let line_start = self.ly as usize * 160;
-
         // WX é offset por 7, então WX=7 significa coluna 0 na tela
         // WX < 7 significa que a window está fora da área visível (já tratado acima)
         let window_start_x = self.wx - 7;
@@ -343,6 +385,8 @@ impl PPU {
                 continue;
             }
             let tile_index = self.vram[tile_map_addr];
+            // CGB: atributos do tile no banco 1, mesma posição do tile map
+            let attr = self.bg_map_attributes(tile_map_addr);
 
             // Calcular endereço do tile
             let tile_addr = if tile_data_mode {
@@ -354,25 +398,33 @@ impl PPU {
                 (0x1000 + (signed as i16) * 16) as u16
             };
 
-            if tile_addr + (pixel_y as u16) * 2 + 1 >= 0x2000 {
+            // Flip vertical (atributo CGB bit 6)
+            let row = if (attr & 0x40) != 0 {
+                7 - pixel_y
+            } else {
+                pixel_y
+            };
+
+            if tile_addr + (row as u16) * 2 + 1 >= 0x2000 {
                 continue;
             }
 
             // Ler linha do tile
-            let byte1 = self.vram[(tile_addr + (pixel_y as u16) * 2) as usize];
-            let byte2 = self.vram[(tile_addr + (pixel_y as u16) * 2 + 1) as usize];
+            let byte1 = self.tile_byte(attr, (tile_addr + (row as u16) * 2) as usize);
+            let byte2 = self.tile_byte(attr, (tile_addr + (row as u16) * 2 + 1) as usize);
 
-            // Extrair cor do pixel
-            let bit_pos = 7 - pixel_x;
+            // Extrair cor do pixel (flip horizontal: atributo CGB bit 5)
+            let bit_pos = if (attr & 0x20) != 0 {
+                pixel_x
+            } else {
+                7 - pixel_x
+            };
             let bit1 = (byte1 >> bit_pos) & 1;
             let bit2 = (byte2 >> bit_pos) & 1;
             let color = (bit2 << 1) | bit1;
 
-            // Aplicar paleta BGP (window usa mesma paleta que BG)
-            let final_color = self.apply_palette(color);
-            self.framebuffer[line_start + screen_x as usize] = final_color;
-            // Window priority: true if window pixel is opaque (color != 0)
-            self.bg_priority[line_start + screen_x as usize] = color != 0;
+            // Window usa a mesma paleta que o BG
+            self.put_bg_pixel(screen_x as usize, color, attr);
         }
     }
 
@@ -398,12 +450,18 @@ impl PPU {
         if visible_sprites.len() > 10 {
             visible_sprites.truncate(10);
         }
-        // Ordena por prioridade DMG: x menor primeiro, depois OAM menor
-        visible_sprites.sort_by(|a, b| {
-            let ax = a.0.x;
-            let bx = b.0.x;
-            if ax != bx { ax.cmp(&bx) } else { a.1.cmp(&b.1) }
-        });
+        if self.cgb_mode {
+            // CGB: prioridade só pelo índice na OAM; desenha do maior para o
+            // menor para que o sprite de menor índice fique por cima
+            visible_sprites.sort_by_key(|&(_, index)| std::cmp::Reverse(index));
+        } else {
+            // Ordena por prioridade DMG: x menor primeiro, depois OAM menor
+            visible_sprites.sort_by(|a, b| {
+                let ax = a.0.x;
+                let bx = b.0.x;
+                if ax != bx { ax.cmp(&bx) } else { a.1.cmp(&b.1) }
+            });
+        }
         // Renderiza na ordem
         for &(sprite, _sprite_index) in visible_sprites.iter() {
             self.render_single_sprite_with_priority(sprite, line, sprite_height);
@@ -438,8 +496,8 @@ impl PPU {
             return;
         } // Bounds check
 
-        let byte1 = self.vram[tile_addr as usize];
-        let byte2 = self.vram[(tile_addr + 1) as usize];
+        let byte1 = self.tile_byte(sprite.attributes, tile_addr as usize);
+        let byte2 = self.tile_byte(sprite.attributes, (tile_addr + 1) as usize);
 
         // Renderizar 8 pixels da linha do sprite
         for pixel_x in 0..8 {
@@ -471,6 +529,22 @@ impl PPU {
             let bg_priority = (sprite.attributes & 0x80) != 0;
             let framebuffer_pos = (line as usize) * 160 + (screen_x as usize);
 
+            if self.cgb_mode {
+                // CGB: com LCDC bit 0 ligado, BG opaco vence se o sprite ou o
+                // tile do BG pedirem prioridade
+                let bg_wins = (self.lcdc & 0x01) != 0
+                    && self.bg_priority[framebuffer_pos]
+                    && (bg_priority || self.bg_attr_priority[screen_x as usize]);
+                if bg_wins {
+                    continue;
+                }
+                self.framebuffer[framebuffer_pos] = color;
+                self.rgb_framebuffer[framebuffer_pos] =
+                    cgb_color(&self.obj_palette_ram, sprite.attributes & 0x07, color);
+                self.bg_priority[framebuffer_pos] = false;
+                continue;
+            }
+
             // Se sprite tem prioridade baixa, só desenha sobre BG/window "opaque" pixel
             if bg_priority && self.bg_priority[framebuffer_pos] {
                 continue;
@@ -481,6 +555,10 @@ impl PPU {
             let final_color = self.apply_sprite_palette(color, use_obp1);
 
             self.framebuffer[framebuffer_pos] = final_color;
+            if self.cgb_hardware {
+                self.rgb_framebuffer[framebuffer_pos] =
+                    cgb_color(&self.obj_palette_ram, use_obp1 as u8, final_color);
+            }
             // Sprites overwrite BG priority for this pixel
             self.bg_priority[framebuffer_pos] = false;
         }
@@ -635,13 +713,17 @@ impl PPU {
     // ly = linha atual (0-143)
     // Escreve 160 pixels no framebuffer na posição correta
     pub fn render_bg_scanline(&mut self) {
-        // LCDC bit 0: BG/Window enable
-        if (self.lcdc & 0x01) == 0 {
+        // LCDC bit 0: BG/Window enable (no CGB o BG continua visível)
+        if (self.lcdc & 0x01) == 0 && !self.cgb_mode {
             // BG desabilitado, preencher com branco (cor 0)
             let line_start = self.ly as usize * 160;
             for x in 0..160 {
                 self.framebuffer[line_start + x] = 0;
             }
+            if self.cgb_hardware {
+                let white = cgb_color(&self.bg_palette_ram, 0, 0);
+                self.rgb_framebuffer[line_start..line_start + 160].fill(white);
+            }
             return;
         }
 
@@ -669,6 +751,7 @@ impl PPU {
         for x in 0..160 {
             self.bg_priority[line_start + x] = false;
         }
+        self.bg_attr_priority = [false; 160];
         for screen_x in 0..160 {
             // Calcular posição X no tile map (com scroll)
             let x = (screen_x as u8).wrapping_add(self.scx);
@@ -678,6 +761,7 @@ impl PPU {
             // Ler tile number do tile map
             let tile_map_addr = tile_map_base + tile_y * 32 + tile_x;
             let tile_number = self.vram[tile_map_addr];
+            let attr = self.bg_map_attributes(tile_map_addr);
 
             // Converter tile number para endereço em VRAM
             let tile_addr = if tile_data_mode {
@@ -689,21 +773,73 @@ impl PPU {
                 (0x1000u16 as i16 + (signed as i16) * 16) as u16
             };
 
+            // Flip vertical/horizontal (atributos CGB bits 6 e 5)
+            let row = if (attr & 0x40) != 0 {
+                7 - pixel_y
+            } else {
+                pixel_y
+            };
+
             // Ler 2 bytes da linha do tile
-            let byte1 = self.vram[(tile_addr + pixel_y as u16 * 2) as usize];
-            let byte2 = self.vram[(tile_addr + pixel_y as u16 * 2 + 1) as usize];
+            let byte1 = self.tile_byte(attr, (tile_addr + row as u16 * 2) as usize);
+            let byte2 = self.tile_byte(attr, (tile_addr + row as u16 * 2 + 1) as usize);
 
             // Extrair pixel
-            let bit_index = 7 - pixel_x;
+            let bit_index = if (attr & 0x20) != 0 {
+                pixel_x
+            } else {
+                7 - pixel_x
+            };
             let lsb = (byte1 >> bit_index) & 1;
             let msb = (byte2 >> bit_index) & 1;
             let color = (msb << 1) | lsb;
 
             // Aplicar paleta e escrever no framebuffer
-            let final_color = self.apply_palette(color);
-            self.framebuffer[line_start + screen_x] = final_color;
-            // BG priority: true if BG pixel is opaque (color != 0)
-            self.bg_priority[line_start + screen_x] = color != 0;
+            self.put_bg_pixel(screen_x, color, attr);
+        }
+    }
+
+    // Atributos CGB do tile map (banco 1); no DMG sempre 0
+    fn bg_map_attributes(&self, tile_map_addr: usize) -> u8 {
+        if self.cgb_mode {
+            self.vram1[tile_map_addr]
+        } else {
+            0
+        }
+    }
+
+    // Byte de tile data; no CGB o bit 3 dos atributos escolhe o banco de VRAM
+    fn tile_byte(&self, attr: u8, addr: usize) -> u8 {
+        if self.cgb_mode && (attr & 0x08) != 0 {
+            self.vram1[addr]
+        } else {
+            self.vram[addr]
+        }
+    }
+
+    // Escreve um pixel de BG/window na linha atual (paleta DMG ou CGB)
+    fn put_bg_pixel(&mut self, screen_x: usize, color: u8, attr: u8) {
+        let pos = self.ly as usize * 160 + screen_x;
+        if self.cgb_mode {
+            self.framebuffer[pos] = color;
+            self.rgb_framebuffer[pos] = cgb_color(&self.bg_palette_ram, attr & 0x07, color);
+            self.bg_attr_priority[screen_x] = (attr & 0x80) != 0;
+        } else {
+            let shade = self.apply_palette(color);
+            self.framebuffer[pos] = shade;
+            if self.cgb_hardware {
+                self.rgb_framebuffer[pos] = cgb_color(&self.bg_palette_ram, 0, shade);
+            }
+        }
+        // BG priority: true if BG pixel is opaque (color != 0)
+        self.bg_priority[pos] = color != 0;
+    }
+
+    // DMG: converte os shades da linha atual para RGB555
+    fn update_dmg_rgb_line(&mut self) {
+        let line_start = self.ly as usize * 160;
+        for pos in line_start..line_start + 160 {
+            self.rgb_framebuffer[pos] = DMG_SHADES_RGB555[(self.framebuffer[pos] & 0x03) as usize];
         }
     }
 
@@ -715,24 +851,69 @@ impl PPU {
         }
     }
 
-    // Lê byte da VRAM (endereço 0x8000-0x9FFF)
+    // Lê byte da VRAM (endereço 0x8000-0x9FFF) no banco selecionado por VBK
     pub fn read_vram(&self, addr: u16) -> u8 {
         let offset = (addr - 0x8000) as usize;
-        if offset < 0x2000 {
-            self.vram[offset]
-        } else {
+        if offset >= 0x2000 {
             0xFF
+        } else if self.cgb_mode && self.vram_bank != 0 {
+            self.vram1[offset]
+        } else {
+            self.vram[offset]
         }
     }
 
     // Escreve byte na VRAM
     pub fn write_vram(&mut self, addr: u16, val: u8) {
         let offset = (addr - 0x8000) as usize;
-        if offset < 0x2000 {
+        if offset >= 0x2000 {
+            return;
+        }
+        if self.cgb_mode && self.vram_bank != 0 {
+            self.vram1[offset] = val;
+        } else {
             self.vram[offset] = val;
         }
     }
 
+    /// Grava as paletas de compatibilidade (BG 0, OBJ 0 e OBJ 1) como a boot ROM do CGB
+    pub fn load_compat_palettes(&mut self, palettes: &CompatPalettes) {
+        for (i, color) in palettes.bg.iter().enumerate() {
+            self.bg_palette_ram[i * 2..i * 2 + 2].copy_from_slice(&color.to_le_bytes());
+        }
+        for (i, color) in palettes.obj0.iter().chain(palettes.obj1.iter()).enumerate() {
+            self.obj_palette_ram[i * 2..i * 2 + 2].copy_from_slice(&color.to_le_bytes());
+        }
+    }
+
+    // Lê BCPD/OCPD (0xFF69/0xFF6B); inacessível enquanto a VRAM estiver bloqueada (modo 3)
+    pub fn read_palette_data(&self, obj: bool) -> u8 {
+        if self.cpu_vram_blocked() {
+            return 0xFF;
+        }
+        if obj {
+            self.obj_palette_ram[(self.ocps & 0x3F) as usize]
+        } else {
+            self.bg_palette_ram[(self.bcps & 0x3F) as usize]
+        }
+    }
+
+    // Escreve BCPD/OCPD; o auto-incremento acontece mesmo com a escrita bloqueada
+    pub fn write_palette_data(&mut self, obj: bool, val: u8) {
+        let blocked = self.cpu_vram_write_blocked();
+        let (spec, ram) = if obj {
+            (&mut self.ocps, &mut self.obj_palette_ram)
+        } else {
+            (&mut self.bcps, &mut self.bg_palette_ram)
+        };
+        if !blocked {
+            ram[(*spec & 0x3F) as usize] = val;
+        }
+        if (*spec & 0x80) != 0 {
+            *spec = (*spec & 0xC0) | (spec.wrapping_add(1) & 0x3F);
+        }
+    }
+
     // Lê byte da OAM (endereço 0xFE00-0xFE9F)
     pub fn read_oam(&self, addr: u16) -> u8 {
         let offset = (addr - 0xFE00) as usize;
@@ -812,6 +993,9 @@ impl PPU {
 
         let prev_mode_clock = self.mode_clock;
         self.mode_clock += cycles;
+        if self.fifo_renderer && self.ly < 144 {
+            self.fifo_run(prev_mode_clock, self.mode_clock);
+        }
 
         // CGB quirk: com STAT bit 5 habilitado, uma IRQ STAT extra é
         // sinalizada no início de VBlank. Modelamos como 1 M-cycle antes da
@@ -848,7 +1032,8 @@ impl PPU {
                     self.change_mode(0, iflags);
                 }
                 // Se window estiver desabilitada, garante reset do estado
-                if (self.lcdc & 0x20) == 0 {
+                // (no FIFO o contador de linhas da window sobrevive)
+                if (self.lcdc & 0x20) == 0 && !self.fifo_renderer {
                     self.wy_trigger = false;
                     self.wy_pos = -1;
                 }
@@ -906,11 +1091,18 @@ impl PPU {
 
         match new_mode {
             0 => {
+                self.hblank_event = true;
                 // HBlank: renderiza scanline (apenas em modo não-headless)
+                // No FIFO os pixels já saíram durante o modo 3
                 if !self.headless {
-                    self.render_bg_scanline();
-                    self.render_window_scanline();
-                    self.render_sprites_scanline(self.ly);
+                    if !self.fifo_renderer {
+                        self.render_bg_scanline();
+                        self.render_window_scanline();
+                        self.render_sprites_scanline(self.ly);
+                    }
+                    if !self.cgb_mode && !self.cgb_hardware {
+                        self.update_dmg_rgb_line();
+                    }
                 }
             }
             1 => {
@@ -920,19 +1112,26 @@ impl PPU {
                 self.wy_pos = -1;
             }
             2 => {}
-            3 => {
-                // Window trigger: ativa ao entrar em modo 3 na linha wy
-                if (self.lcdc & 0x20) != 0 && !self.wy_trigger && self.ly == self.wy {
-                    self.wy_trigger = true;
-                    self.wy_pos = -1;
-                }
-            }
+            3 => self.check_window_trigger(),
             _ => {}
         }
 
         self.update_stat_irq_line(iflags);
     }
 
+    // Window trigger: ativa ao entrar em modo 3 na linha wy
+    fn check_window_trigger(&mut self) {
+        if (self.lcdc & 0x20) != 0 && !self.wy_trigger && self.ly == self.wy {
+            self.wy_trigger = true;
+            self.wy_pos = -1;
+        }
+    }
+
+    /// Retorna true uma vez por entrada no HBlank (modo 0)
+    pub fn take_hblank_event(&mut self) -> bool {
+        std::mem::take(&mut self.hblank_event)
+    }
+
     /// Dispara STAT IRQ se lyc_inte estiver setado e ly == lyc
     pub fn check_lyc_interrupt(&mut self, iflags: &mut u8) {
         // Bit 6: LYC=LY coincidence interrupt enable
@@ -1059,4 +1258,99 @@ impl PPU {
             self.apply_read_inc_dec_corruption(row);
         }
     }
+
+    // ========== SAVE STATE ==========
+
+    pub fn save_state(&self, w: &mut StateWriter) {
+        w.write_bytes(&self.vram);
+        w.write_bytes(&self.vram1);
+        w.write_u8(self.vram_bank);
+        w.write_bytes(&self.oam);
+        w.write_bytes(&self.framebuffer);
+        for &color in self.rgb_framebuffer.iter() {
+            w.write_u16(color);
+        }
+        for &opaque in self.bg_priority.iter() {
+            w.write_bool(opaque);
+        }
+        w.write_u8(self.lcdc);
+        w.write_u8(self.stat);
+        w.write_u8(self.scy);
+        w.write_u8(self.scx);
+        w.write_u8(self.ly);
+        w.write_u8(self.lyc);
+        w.write_u8(self.bgp);
+        w.write_u8(self.obp0);
+        w.write_u8(self.obp1);
+        w.write_u8(self.wy);
+        w.write_u8(self.wx);
+        w.write_bool(self.wy_trigger);
+        w.write_i32(self.wy_pos);
+        w.write_bool(self.frame_ready);
+        w.write_u8(self.mode);
+        w.write_u32(self.mode_clock);
+        w.write_bool(self.ly_eq_lyc_prev);
+        w.write_bool(self.stat_irq_line_prev);
+        w.write_bool(self.cgb_mode2_vblank_stat_quirk);
+        w.write_bool(self.lcd_on_stat_delay);
+        w.write_bool(self.lcd_on_timing_quirk);
+        w.write_bool(self.cgb_mode);
+        w.write_bool(self.cgb_hardware);
+        w.write_u8(self.bcps);
+        w.write_u8(self.ocps);
+        w.write_bytes(&self.bg_palette_ram);
+        w.write_bytes(&self.obj_palette_ram);
+        w.write_bool(self.fifo_renderer);
+        self.fifo.save_state(w);
+    }
+
+    pub fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        r.read_bytes(&mut self.vram)?;
+        r.read_bytes(&mut self.vram1)?;
+        self.vram_bank = r.read_u8()?;
+        r.read_bytes(&mut self.oam)?;
+        r.read_bytes(&mut self.framebuffer)?;
+        for color in self.rgb_framebuffer.iter_mut() {
+            *color = r.read_u16()?;
+        }
+        for opaque in self.bg_priority.iter_mut() {
+            *opaque = r.read_bool()?;
+        }
+        self.lcdc = r.read_u8()?;
+        self.stat = r.read_u8()?;
+        self.scy = r.read_u8()?;
+        self.scx = r.read_u8()?;
+        self.ly = r.read_u8()?;
+        self.lyc = r.read_u8()?;
+        self.bgp = r.read_u8()?;
+        self.obp0 = r.read_u8()?;
+        self.obp1 = r.read_u8()?;
+        self.wy = r.read_u8()?;
+        self.wx = r.read_u8()?;
+        self.wy_trigger = r.read_bool()?;
+        self.wy_pos = r.read_i32()?;
+        self.frame_ready = r.read_bool()?;
+        self.mode = r.read_u8()?;
+        self.mode_clock = r.read_u32()?;
+        self.ly_eq_lyc_prev = r.read_bool()?;
+        self.stat_irq_line_prev = r.read_bool()?;
+        self.cgb_mode2_vblank_stat_quirk = r.read_bool()?;
+        self.lcd_on_stat_delay = r.read_bool()?;
+        self.lcd_on_timing_quirk = r.read_bool()?;
+        self.cgb_mode = r.read_bool()?;
+        self.cgb_hardware = r.read_bool()?;
+        self.bcps = r.read_u8()?;
+        self.ocps = r.read_u8()?;
+        r.read_bytes(&mut self.bg_palette_ram)?;
+        r.read_bytes(&mut self.obj_palette_ram)?;
+        self.fifo_renderer = r.read_bool()?;
+        self.fifo.load_state(r)?;
+        Ok(())
+    }
+}
+
+/// Cor RGB555 de uma paleta CGB (8 paletas × 4 cores, little-endian)
+fn cgb_color(palette_ram: &[u8; 64], palette: u8, color: u8) -> u16 {
+    let idx = (palette as usize & 0x07) * 8 + (color as usize) * 2;
+    u16::from_le_bytes([palette_ram[idx], palette_ram[idx + 1]]) & 0x7FFF
 }
@@ -0,0 +1,497 @@
+// Renderizador alternativo: fetcher de BG/window/sprites + FIFO de pixels
+//
+// Roda um dot por vez durante o modo 3, então escritas em SCX, BGP, LCDC
+// ou WX no meio da linha afetam só os pixels seguintes. A duração do modo 3
+// sai do próprio fetcher (descarte de SCX, window, pausas de sprite).
+// Referência: https://gbdev.io/pandocs/pixel_fifo.html
+
+use super::{PPU, cgb_color};
+use crate::GB::savestate::{StateReader, StateWriter};
+use std::collections::VecDeque;
+
+/// Dot da linha em que o modo 3 começa internamente
+pub(super) const MODE3_START: u32 = 80;
+
+/// O primeiro fetch de tile da linha é feito duas vezes (6 dots perdidos)
+const STARTUP_DOTS: u8 = 6;
+
+/// Busca de um sprite depois que o fetcher do BG termina o tile atual
+const SPRITE_FETCH_DOTS: u8 = 6;
+
+#[derive(Clone, Copy, Default)]
+struct BgPixel {
+    color: u8,
+    attr: u8, // atributos CGB do tile (paleta, banco, flip, prioridade)
+}
+
+#[derive(Clone, Copy, Default)]
+struct ObjPixel {
+    color: u8,
+    attr: u8,
+    oam_index: u8,
+}
+
+/// Estado do fetcher e das FIFOs na linha atual
+#[derive(Default)]
+pub(super) struct PixelFifo {
+    active: bool,
+    pub(super) done: bool,
+    pub(super) end_clock: u32, // mode_clock em que o último pixel saiu
+    bg: VecDeque<BgPixel>,
+    obj: VecDeque<ObjPixel>,
+
+    // Fetcher: 0 = tile, 1 = byte baixo, 2 = byte alto, 3 = push (2 dots cada)
+    step: u8,
+    sub: u8,
+    fetcher_x: u8,
+    tile_no: u8,
+    tile_attr: u8,
+    tile_lo: u8,
+    tile_hi: u8,
+
+    startup: u8,
+    discard: u8,        // SCX & 7 pixels jogados fora no início da linha
+    window_discard: u8, // WX < 7: pixels da window à esquerda da tela
+    window: bool,
+    lx: u8, // próximo pixel da tela
+
+    // Sprites da linha ainda não buscados: (X, índice na OAM)
+    sprites: Vec<(u8, u8)>,
+    sprite_pending: bool,
+    sprite_dots: u8,
+}
+
+impl PixelFifo {
+    pub(super) fn save_state(&self, w: &mut StateWriter) {
+        w.write_bool(self.active);
+        w.write_bool(self.done);
+        w.write_u32(self.end_clock);
+        w.write_u8(self.bg.len() as u8);
+        for px in &self.bg {
+            w.write_u8(px.color);
+            w.write_u8(px.attr);
+        }
+        w.write_u8(self.obj.len() as u8);
+        for px in &self.obj {
+            w.write_u8(px.color);
+            w.write_u8(px.attr);
+            w.write_u8(px.oam_index);
+        }
+        for value in [
+            self.step,
+            self.sub,
+            self.fetcher_x,
+            self.tile_no,
+            self.tile_attr,
+            self.tile_lo,
+            self.tile_hi,
+            self.startup,
+            self.discard,
+            self.window_discard,
+        ] {
+            w.write_u8(value);
+        }
+        w.write_bool(self.window);
+        w.write_u8(self.lx);
+        w.write_u8(self.sprites.len() as u8);
+        for &(x, index) in &self.sprites {
+            w.write_u8(x);
+            w.write_u8(index);
+        }
+        w.write_bool(self.sprite_pending);
+        w.write_u8(self.sprite_dots);
+    }
+
+    pub(super) fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.active = r.read_bool()?;
+        self.done = r.read_bool()?;
+        self.end_clock = r.read_u32()?;
+        self.bg.clear();
+        for _ in 0..r.read_u8()? {
+            let color = r.read_u8()?;
+            let attr = r.read_u8()?;
+            self.bg.push_back(BgPixel { color, attr });
+        }
+        self.obj.clear();
+        for _ in 0..r.read_u8()? {
+            let color = r.read_u8()?;
+            let attr = r.read_u8()?;
+            let oam_index = r.read_u8()?;
+            self.obj.push_back(ObjPixel {
+                color,
+                attr,
+                oam_index,
+            });
+        }
+        for value in [
+            &mut self.step,
+            &mut self.sub,
+            &mut self.fetcher_x,
+            &mut self.tile_no,
+            &mut self.tile_attr,
+            &mut self.tile_lo,
+            &mut self.tile_hi,
+            &mut self.startup,
+            &mut self.discard,
+            &mut self.window_discard,
+        ] {
+            *value = r.read_u8()?;
+        }
+        self.window = r.read_bool()?;
+        self.lx = r.read_u8()?;
+        self.sprites.clear();
+        for _ in 0..r.read_u8()? {
+            let x = r.read_u8()?;
+            let index = r.read_u8()?;
+            self.sprites.push((x, index));
+        }
+        self.sprite_pending = r.read_bool()?;
+        self.sprite_dots = r.read_u8()?;
+        Ok(())
+    }
+}
+
+impl PPU {
+    /// Avança o modo 3 da linha atual pelos dots [from, to)
+    pub(super) fn fifo_run(&mut self, from: u32, to: u32) {
+        for dot in from..to.min(456) {
+            if dot == MODE3_START {
+                self.fifo_start_line();
+            }
+            if self.fifo.active {
+                self.fifo_dot(dot);
+            }
+        }
+    }
+
+    /// Início do modo 3: OAM scan (até 10 sprites) e reset do fetcher
+    fn fifo_start_line(&mut self) {
+        self.check_window_trigger();
+        let height = if (self.lcdc & 0x04) != 0 { 16 } else { 8 };
+        let line = self.ly as i16;
+        let sprites: Vec<(u8, u8)> = (0..40u8)
+            .map(|index| (self.get_sprite(index), index))
+            .filter(|(sprite, _)| {
+                let top = sprite.y as i16 - 16;
+                line >= top && line < top + height
+            })
+            .take(10)
+            .map(|(sprite, index)| (sprite.x, index))
+            .collect();
+
+        self.fifo = PixelFifo {
+            active: true,
+            startup: STARTUP_DOTS,
+            discard: self.scx & 0x07,
+            sprites,
+            ..PixelFifo::default()
+        };
+    }
+
+    fn fifo_dot(&mut self, dot: u32) {
+        if self.fifo.startup > 0 {
+            self.fifo.startup -= 1;
+            return;
+        }
+
+        if self.fifo.sprite_pending {
+            self.fifo_sprite_wait(true);
+            return;
+        }
+
+        if self.fifo_window_start() {
+            self.fifo_fetcher_dot();
+            return;
+        }
+
+        self.fifo_fetcher_dot();
+        if self.fifo.bg.is_empty() {
+            return;
+        }
+
+        if self.fifo.discard == 0 && self.fifo_sprite_trigger() {
+            self.fifo.sprite_pending = true;
+            self.fifo.sprite_dots = SPRITE_FETCH_DOTS;
+            self.fifo_sprite_wait(false);
+            return;
+        }
+
+        self.fifo_shift_pixel();
+        if self.fifo.lx == 160 {
+            self.fifo.active = false;
+            self.fifo.done = true;
+            self.fifo.end_clock = dot + 1;
+        }
+    }
+
+    // ========== FETCHER ==========
+
+    fn fifo_fetcher_dot(&mut self) {
+        if self.fifo.step == 3 {
+            // Push só acontece com a FIFO do BG vazia
+            if self.fifo.bg.is_empty() {
+                let (lo, hi, attr) = (self.fifo.tile_lo, self.fifo.tile_hi, self.fifo.tile_attr);
+                for i in 0..8 {
+                    let bit = if (attr & 0x20) != 0 { i } else { 7 - i };
+                    let color = (((hi >> bit) & 1) << 1) | ((lo >> bit) & 1);
+                    self.fifo.bg.push_back(BgPixel { color, attr });
+                }
+                self.fifo.step = 0;
+                self.fifo.fetcher_x = self.fifo.fetcher_x.wrapping_add(1);
+            }
+            return;
+        }
+
+        self.fifo.sub += 1;
+        if self.fifo.sub < 2 {
+            return;
+        }
+        self.fifo.sub = 0;
+        match self.fifo.step {
+            0 => {
+                let addr = self.fifo_tile_map_addr();
+                self.fifo.tile_no = self.vram[addr];
+                self.fifo.tile_attr = self.bg_map_attributes(addr);
+            }
+            1 => {
+                let addr = self.fifo_tile_data_addr();
+                self.fifo.tile_lo = self.tile_byte(self.fifo.tile_attr, addr);
+            }
+            _ => {
+                let addr = self.fifo_tile_data_addr() + 1;
+                self.fifo.tile_hi = self.tile_byte(self.fifo.tile_attr, addr);
+            }
+        }
+        self.fifo.step += 1;
+    }
+
+    fn fifo_tile_map_addr(&self) -> usize {
+        if self.fifo.window {
+            let base = if (self.lcdc & 0x40) != 0 {
+                0x1C00
+            } else {
+                0x1800
+            };
+            let row = (self.wy_pos.max(0) as usize / 8) & 0x1F;
+            base + row * 32 + (self.fifo.fetcher_x as usize & 0x1F)
+        } else {
+            let base = if (self.lcdc & 0x08) != 0 {
+                0x1C00
+            } else {
+                0x1800
+            };
+            let row = self.ly.wrapping_add(self.scy) as usize / 8;
+            let col = ((self.scx >> 3) as usize + self.fifo.fetcher_x as usize) & 0x1F;
+            base + row * 32 + col
+        }
+    }
+
+    fn fifo_tile_data_addr(&self) -> usize {
+        let y = if self.fifo.window {
+            self.wy_pos.max(0) as u8
+        } else {
+            self.ly.wrapping_add(self.scy)
+        };
+        let row = if (self.fifo.tile_attr & 0x40) != 0 {
+            7 - (y & 7)
+        } else {
+            y & 7
+        } as usize;
+        let tile = self.fifo.tile_no;
+        let base = if (self.lcdc & 0x10) != 0 {
+            tile as usize * 16
+        } else {
+            (0x1000 + (tile as i8 as i32) * 16) as usize
+        };
+        base + row * 2
+    }
+
+    // ========== WINDOW ==========
+
+    /// Troca o fetcher para a window quando o pixel atual chega em WX - 7
+    fn fifo_window_start(&mut self) -> bool {
+        if self.fifo.window
+            || (self.lcdc & 0x20) == 0
+            || !self.wy_trigger
+            || self.wx > 166
+            || self.fifo.discard > 0
+        {
+            return false;
+        }
+        let start = self.wx.saturating_sub(7);
+        if self.fifo.lx != start {
+            return false;
+        }
+        self.fifo.window = true;
+        self.fifo.window_discard = 7u8.saturating_sub(self.wx);
+        self.fifo.bg.clear();
+        self.fifo.step = 0;
+        self.fifo.sub = 0;
+        self.fifo.fetcher_x = 0;
+        self.wy_pos += 1;
+        true
+    }
+
+    // ========== SPRITES ==========
+
+    fn fifo_sprite_trigger(&self) -> bool {
+        (self.lcdc & 0x02) != 0
+            && self
+                .fifo
+                .sprites
+                .iter()
+                .any(|&(x, _)| x <= self.fifo.lx.saturating_add(8))
+    }
+
+    /// O fetcher do BG termina o tile atual e fica parado durante a busca
+    fn fifo_fetcher_ready(&self) -> bool {
+        self.fifo.step >= 2
+    }
+
+    fn fifo_sprite_wait(&mut self, step_fetcher: bool) {
+        if self.fifo_fetcher_ready() {
+            self.fifo.sprite_dots -= 1;
+            if self.fifo.sprite_dots == 0 {
+                self.fifo_fetch_sprite();
+                self.fifo.sprite_pending = false;
+            }
+        } else if step_fetcher {
+            self.fifo_fetcher_dot();
+        }
+    }
+
+    /// Busca o sprite de menor X que já alcançou o pixel atual e mistura na
+    /// FIFO de sprites (pixels já ocupados só são trocados no CGB, por índice)
+    fn fifo_fetch_sprite(&mut self) {
+        let limit = self.fifo.lx.saturating_add(8);
+        let Some(slot) = self
+            .fifo
+            .sprites
+            .iter()
+            .enumerate()
+            .filter(|(_, (x, _))| *x <= limit)
+            .min_by_key(|(_, (x, _))| *x)
+            .map(|(slot, _)| slot)
+        else {
+            return;
+        };
+        let (x, index) = self.fifo.sprites.remove(slot);
+        let sprite = self.get_sprite(index);
+
+        let height: u8 = if (self.lcdc & 0x04) != 0 { 16 } else { 8 };
+        let mut line = self.ly.wrapping_sub(sprite.y.wrapping_sub(16)) & (height - 1);
+        if (sprite.attributes & 0x40) != 0 {
+            line = height - 1 - line;
+        }
+        let tile = if height == 16 {
+            sprite.tile_index & 0xFE
+        } else {
+            sprite.tile_index
+        };
+        let addr = tile as usize * 16 + line as usize * 2;
+        let lo = self.tile_byte(sprite.attributes, addr);
+        let hi = self.tile_byte(sprite.attributes, addr + 1);
+
+        while self.fifo.obj.len() < 8 {
+            self.fifo.obj.push_back(ObjPixel::default());
+        }
+        for i in 0..8u8 {
+            let screen_x = x as i16 - 8 + i as i16;
+            let pos = screen_x - self.fifo.lx as i16;
+            if pos < 0 {
+                continue;
+            }
+            let bit = if (sprite.attributes & 0x20) != 0 {
+                i
+            } else {
+                7 - i
+            };
+            let color = (((hi >> bit) & 1) << 1) | ((lo >> bit) & 1);
+            if color == 0 {
+                continue;
+            }
+            let current = &mut self.fifo.obj[pos as usize];
+            if current.color == 0 || (self.cgb_mode && index < current.oam_index) {
+                *current = ObjPixel {
+                    color,
+                    attr: sprite.attributes,
+                    oam_index: index,
+                };
+            }
+        }
+    }
+
+    // ========== SAÍDA ==========
+
+    fn fifo_shift_pixel(&mut self) {
+        let Some(bg) = self.fifo.bg.pop_front() else {
+            return;
+        };
+        let obj = self.fifo.obj.pop_front();
+        if self.fifo.discard > 0 {
+            self.fifo.discard -= 1;
+            return;
+        }
+        if self.fifo.window && self.fifo.window_discard > 0 {
+            self.fifo.window_discard -= 1;
+            return;
+        }
+        let x = self.fifo.lx as usize;
+        self.fifo_put_pixel(x, bg, obj.unwrap_or_default());
+        self.fifo.lx += 1;
+    }
+
+    /// Mistura BG e sprite com as paletas/LCDC do momento em que o pixel sai
+    fn fifo_put_pixel(&mut self, x: usize, bg: BgPixel, obj: ObjPixel) {
+        let pos = self.ly as usize * 160 + x;
+        let bg_enabled = (self.lcdc & 0x01) != 0;
+        let bg_color = if bg_enabled || self.cgb_mode {
+            bg.color
+        } else {
+            0
+        };
+
+        let obj_visible = obj.color != 0 && (self.lcdc & 0x02) != 0;
+        let obj_wins = obj_visible
+            && if self.cgb_mode {
+                !(bg_enabled && bg_color != 0 && ((obj.attr | bg.attr) & 0x80) != 0)
+            } else {
+                !((obj.attr & 0x80) != 0 && bg_color != 0)
+            };
+
+        if obj_wins {
+            if self.cgb_mode {
+                self.framebuffer[pos] = obj.color;
+                self.rgb_framebuffer[pos] =
+                    cgb_color(&self.obj_palette_ram, obj.attr & 0x07, obj.color);
+            } else {
+                let use_obp1 = (obj.attr & 0x10) != 0;
+                let shade = self.apply_sprite_palette(obj.color, use_obp1);
+                self.framebuffer[pos] = shade;
+                if self.cgb_hardware {
+                    self.rgb_framebuffer[pos] =
+                        cgb_color(&self.obj_palette_ram, use_obp1 as u8, shade);
+                }
+            }
+            self.bg_priority[pos] = false;
+            return;
+        }
+
+        if self.cgb_mode {
+            self.framebuffer[pos] = bg_color;
+            self.rgb_framebuffer[pos] = cgb_color(&self.bg_palette_ram, bg.attr & 0x07, bg_color);
+        } else {
+            // BG desligado no DMG: branco, sem passar pela BGP
+            let shade = if bg_enabled {
+                self.apply_palette(bg_color)
+            } else {
+                0
+            };
+            self.framebuffer[pos] = shade;
+            if self.cgb_hardware {
+                self.rgb_framebuffer[pos] = cgb_color(&self.bg_palette_ram, 0, shade);
+            }
+        }
+        self.bg_priority[pos] = bg_color != 0;
+    }
+}
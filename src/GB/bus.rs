@@ -1,25 +1,36 @@
 use crate::GB::APU;
 use crate::GB::PPU;
+use crate::GB::infrared::{InfraredPort, IrPeer};
 use crate::GB::joypad::Joypad;
 use crate::GB::mbc::MBC;
+use crate::GB::mbc::camera::CameraSource;
+use crate::GB::savestate::{StateReader, StateWriter};
+use crate::GB::serial::{LinkEndpoint, SerialPort};
+use crate::GB::sgb::Sgb;
 use crate::GB::timer::Timer;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Pausa da troca de velocidade do CGB: 2050 M-cycles
+const SPEED_SWITCH_STALL_CYCLES: u32 = 2050 * 4;
 
 pub struct MemoryBus {
     mbc: Box<dyn MBC + Send>,
-    wram: [u8; 0x2000], // Work RAM (8KB)
+    wram: [u8; 0x8000], // Work RAM (32KB: 8 bancos de 4KB, DMG usa só 0 e 1)
     hram: [u8; 0x7F],   // High RAM (127 bytes)
     timer: Timer,
     pub joypad: Joypad,
     pub ppu: PPU::PPU,
     pub apu: APU::APU,
+    pub sgb: Option<Box<Sgb>>, // Super Game Boy (só nos modelos SGB/SGB2)
     tima: u8,                  // FF05
     tma: u8,                   // FF06
     tac: u8,                   // FF07
     ie: u8,                    // 0xFFFF
     if_: u8,                   // 0xFF0F
-    boot_rom: Option<Vec<u8>>, // Boot ROM (0x100 bytes)
+    boot_rom: Option<Vec<u8>>, // Boot ROM (0x100 bytes DMG/SGB, 0x900 bytes CGB)
     boot_rom_enabled: bool,    // FF50 controle
+    key0: u8,                  // FF4C: modo DMG/CGB escolhido pela boot ROM CGB
 
     // ===== OAM DMA =====
     oam_dma_active: bool,
@@ -34,13 +45,7 @@ pub struct MemoryBus {
     oam_dma_pending_cycles: u32,
 
     // ===== Serial =====
-    serial_sb: u8,                     // FF01 - Serial Transfer Data
-    serial_sc: u8,                     // FF02 - Serial Transfer Control
-    serial_transfer_active: bool,      // Transferência em andamento
-    serial_transfer_cycles: u32,       // Ciclos acumulados da transferência
-    serial_clock_source: bool, // true = internal clock (master), false = external clock (slave)
-    serial_last_transmitted: u8, // Último byte transmitido (para debug/testes)
-    pub serial_output_buffer: Vec<u8>, // Bytes capturados ao completar transferências seriais
+    pub serial: SerialPort,
 
     // Contagem de ciclos consumidos pela CPU nesta instrução
     cpu_cycle_log: u32,
@@ -51,12 +56,23 @@ pub struct MemoryBus {
     pub key1: u8,        // 0xFF4D: bit 0 = solicitação de troca de velocidade
     // HWIO específicos de CGB em modo de compatibilidade
     cgb_hwio_enabled: bool,
-    vbk: u8,  // 0xFF4F (bit 0)
-    bcps: u8, // 0xFF68
-    ocps: u8, // 0xFF6A
-    ff72: u8, // 0xFF72
-    ff73: u8, // 0xFF73
-    ff75: u8, // 0xFF75
+    // VBK (0xFF4F), BCPS (0xFF68) e OCPS (0xFF6A) ficam no PPU
+    ff72: u8,         // 0xFF72
+    ff73: u8,         // 0xFF73
+    ff75: u8,         // 0xFF75
+    ir: InfraredPort, // 0xFF56
+    svbk: u8,         // 0xFF70: banco de WRAM em 0xD000-0xDFFF (0 = banco 1)
+
+    // ===== HDMA/GDMA (0xFF51-0xFF55) =====
+    hdma_src: u16,
+    hdma_dst: u16,         // Offset dentro da VRAM (0x0000-0x1FF0)
+    hdma_remaining: u8,    // Blocos de 16 bytes restantes - 1 (bits 0-6 de FF55)
+    hdma_active: bool,     // HBlank DMA em andamento
+    cpu_stall_cycles: u32, // Ciclos em que a CPU fica parada (DMA, troca de velocidade)
+
+    // Velocidade dupla: ciclos de CPU ainda não repassados a PPU/APU
+    double_speed_dot_carry: u32,
+    double_speed_apu_carry: u32,
 }
 
 impl MemoryBus {
@@ -68,13 +84,40 @@ impl MemoryBus {
     fn lcd_on(&self) -> bool {
         (self.ppu.lcdc & 0x80) != 0
     }
-    /// Carrega a boot ROM (256 bytes) e ativa mapeamento.
-    pub fn load_boot_rom(&mut self, data: Vec<u8>) {
-        if data.len() == 0x100 {
-            self.boot_rom = Some(data);
-            self.boot_rom_enabled = true;
-        } else {
-            eprintln!("Boot ROM inválida: esperado 256 bytes");
+    /// Carrega a boot ROM e ativa mapeamento.
+    /// 0x100 bytes (DMG/MGB/SGB) ou 0x900 bytes (CGB, com o header do
+    /// cartucho visível em 0x100–0x1FF).
+    pub fn load_boot_rom(&mut self, data: Vec<u8>) -> Result<(), String> {
+        if data.len() != 0x100 && data.len() != 0x900 {
+            return Err(format!(
+                "Boot ROM inválida: {} bytes (esperado 256 ou 2304)",
+                data.len()
+            ));
+        }
+        self.boot_rom = Some(data);
+        self.boot_rom_enabled = true;
+        self.key0 = 0;
+        Ok(())
+    }
+
+    /// Boot ROM ainda mapeada neste endereço?
+    #[inline]
+    fn boot_rom_mapped(&self, address: u16) -> bool {
+        match &self.boot_rom {
+            Some(rom) if self.boot_rom_enabled => {
+                address <= 0x00FF || ((0x0200..=0x08FF).contains(&address) && rom.len() == 0x900)
+            }
+            _ => false,
+        }
+    }
+
+    /// FF50: desmapeia a boot ROM. Na boot ROM CGB, KEY0 bit 2 (escrito pela
+    /// própria boot ROM a partir do header) trava o hardware em modo DMG.
+    fn disable_boot_rom(&mut self) {
+        self.boot_rom_enabled = false;
+        if self.cgb_mode && (self.key0 & 0x04) != 0 {
+            self.set_cgb_mode(false);
+            self.set_cgb_compat_hwio(true);
         }
     }
 
@@ -115,6 +158,61 @@ impl MemoryBus {
         Ok(())
     }
 
+    /// Repassa a inclinação do host ao cartucho (x > 0: direita, y > 0: para baixo)
+    pub fn set_tilt(&mut self, x: f32, y: f32) {
+        self.mbc.set_tilt(x, y);
+    }
+
+    /// Lixo de power-on da WRAM, HRAM e OAM. No hardware varia de unidade
+    /// para unidade; aqui sai de um gerador com semente fixa para que replays
+    /// e save states sejam reproduzíveis
+    pub fn fill_power_on_ram(&mut self, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        rng.fill(&mut self.wram[..]);
+        rng.fill(&mut self.hram[..]);
+        rng.fill(&mut self.ppu.oam[..]);
+    }
+
+    /// Liga o cabo link na porta serial (None desconecta)
+    pub fn set_link_cable(&mut self, link: Option<Box<dyn LinkEndpoint>>) {
+        self.serial.set_link(link);
+    }
+
+    /// Liga o infravermelho do cartucho a um par (None desconecta)
+    pub fn set_cart_ir_peer(&mut self, peer: Option<Box<dyn IrPeer>>) {
+        self.mbc.set_ir_peer(peer);
+    }
+
+    /// Liga a porta infravermelha do CGB (FF56) a um par (None desconecta)
+    pub fn set_ir_peer(&mut self, peer: Option<Box<dyn IrPeer>>) {
+        self.ir.set_peer(peer);
+    }
+
+    /// Grava o que o par IR tiver pendente (ex.: --ir-record antes de sair)
+    pub fn flush_ir(&mut self) {
+        self.ir.flush();
+    }
+
+    /// Troca a imagem do sensor da Game Boy Camera (None = cinza uniforme)
+    pub fn set_camera_source(&mut self, source: Option<CameraSource>) {
+        self.mbc.set_camera_source(source);
+    }
+
+    /// Troca o mapper do cartucho (--mapper)
+    pub fn set_mbc(&mut self, mbc: Box<dyn MBC + Send>) {
+        self.mbc = mbc;
+    }
+
+    /// Avisa o cartucho de que a boot ROM não vai rodar
+    pub fn skip_cart_boot(&mut self) {
+        self.mbc.skip_boot();
+    }
+
+    /// Duty do motor de vibração desde a última chamada (None sem motor)
+    pub fn take_rumble_duty(&mut self) -> Option<f32> {
+        self.mbc.take_rumble_duty()
+    }
+
     pub fn save_cart_ram(&self, path: &str) -> Result<(), String> {
         if let Some(data) = self.mbc.save_ram() {
             std::fs::write(path, &data).map_err(|e| e.to_string())?;
@@ -124,27 +222,26 @@ impl MemoryBus {
         }
     }
 
+    /// WRAM, HRAM e OAM zeradas; o lixo de power-on de cada modelo vem de
+    /// `fill_power_on_ram`
     pub fn new(mbc: Box<dyn MBC + Send>) -> Self {
-        let mut rng = rand::thread_rng();
-        let mut wram = [0u8; 0x2000];
-        let mut hram = [0u8; 0x7F];
-        rng.fill(&mut wram[..]);
-        rng.fill(&mut hram[..]);
         Self {
             mbc,
-            wram,
-            hram,
+            wram: [0; 0x8000],
+            hram: [0; 0x7F],
             timer: Timer::new(),
             joypad: Joypad::new(),
             ppu: PPU::PPU::new(),
             apu: APU::APU::new(),
+            sgb: None,
             tima: 0,
             tma: 0,
             tac: 0,
             ie: 0,
             if_: 0,
-            boot_rom: None, // Boot ROM (0x100 bytes)
+            boot_rom: None, // Boot ROM (0x100 bytes DMG/SGB, 0x900 bytes CGB)
             boot_rom_enabled: false,
+            key0: 0,
             oam_dma_active: false,
             oam_dma_src: 0,
             oam_dma_index: 0,
@@ -155,47 +252,52 @@ impl MemoryBus {
             oam_dma_startup_oam: [0; 160],
             oam_dma_pending_src: None,
             oam_dma_pending_cycles: 0,
-            serial_sb: 0x00,
-            serial_sc: 0x7E, // bits não usados em 1
-            serial_transfer_active: false,
-            serial_transfer_cycles: 0,
-            serial_clock_source: false,
-            serial_last_transmitted: 0x00,
-            serial_output_buffer: Vec::new(),
+            serial: SerialPort::new(),
             cpu_cycle_log: 0,
             cgb_mode: false,
             cgb_speed: false,
             key1: 0,
             cgb_hwio_enabled: false,
-            vbk: 0,
-            bcps: 0,
-            ocps: 0,
             ff72: 0,
             ff73: 0,
             ff75: 0,
+            ir: InfraredPort::new(),
+            svbk: 0,
+            hdma_src: 0,
+            hdma_dst: 0,
+            hdma_remaining: 0x7F,
+            hdma_active: false,
+            cpu_stall_cycles: 0,
+            double_speed_dot_carry: 0,
+            double_speed_apu_carry: 0,
         }
     }
 
-    /// Configura o modelo do Game Boy baseado na ROM
+    /// Modo CGB da ROM (o comportamento CGB do APU segue o modelo, não o modo)
     pub fn set_cgb_mode(&mut self, is_cgb: bool) {
-        self.apu.set_cgb_mode(is_cgb);
+        self.ppu.cgb_mode = is_cgb;
         self.cgb_mode = is_cgb;
     }
 
+    /// Liga/desliga o Super Game Boy (pacotes em P1 e saída 256×224)
+    pub fn set_sgb_enabled(&mut self, enabled: bool) {
+        self.sgb = enabled.then(|| Box::new(Sgb::new()));
+    }
+
     pub fn set_cgb_compat_hwio(&mut self, enabled: bool) {
         self.cgb_hwio_enabled = enabled;
         self.ppu.cgb_mode2_vblank_stat_quirk = enabled;
+        self.ppu.cgb_hardware = enabled;
+        self.ppu.vram_bank = 0;
         if enabled {
-            self.vbk = 0;
-            self.bcps = 0xC8;
-            self.ocps = 0xD0;
+            self.ppu.bcps = 0xC8;
+            self.ppu.ocps = 0xD0;
             self.ff72 = 0x00;
             self.ff73 = 0x00;
             self.ff75 = 0x8F;
         } else {
-            self.vbk = 0;
-            self.bcps = 0;
-            self.ocps = 0;
+            self.ppu.bcps = 0;
+            self.ppu.ocps = 0;
             self.ff72 = 0;
             self.ff73 = 0;
             self.ff75 = 0;
@@ -218,8 +320,8 @@ impl MemoryBus {
         if self.oam_dma_active && (0xFE00..=0xFE9F).contains(&address) {
             return 0xFF;
         }
-        // Boot ROM mapeada em 0x0000–0x00FF enquanto boot_rom_enabled
-        if address <= 0x00FF && self.boot_rom_enabled {
+        // Boot ROM mapeada em 0x0000–0x00FF (e 0x0200–0x08FF no CGB) enquanto boot_rom_enabled
+        if self.boot_rom_mapped(address) {
             if let Some(ref rom) = self.boot_rom {
                 return rom[address as usize];
             }
@@ -236,8 +338,7 @@ impl MemoryBus {
                 }
             }
             0xA000..=0xBFFF => self.mbc.read_ram(address),
-            0xC000..=0xDFFF => self.wram[(address - 0xC000) as usize],
-            0xE000..=0xFDFF => self.wram[(address - 0xE000) as usize],
+            0xC000..=0xFDFF => self.wram[self.wram_index(address)],
             // OAM: bloqueada em mode 2/3 e durante DMA
             0xFE00..=0xFE9F => {
                 if self.ppu.cpu_oam_blocked() || self.oam_dma_active {
@@ -246,19 +347,12 @@ impl MemoryBus {
                     self.ppu.read_oam(address)
                 }
             }
-            0xFF00 => self.joypad.read(),
-            0xFF01 => self.serial_sb,
-            0xFF02 => {
-                // Bit 7: Transfer Start Flag (read-only durante transferência)
-                // Bits 1-6: Não usados, sempre leem como 1
-                // Bit 0: Clock Source (readable)
-                let transfer_flag = if self.serial_transfer_active {
-                    0x80
-                } else {
-                    0x00
-                };
-                transfer_flag | 0x7E | (if self.serial_clock_source { 0x01 } else { 0x00 })
-            }
+            0xFF00 => match &self.sgb {
+                Some(sgb) => sgb.read_p1(self.joypad.read()),
+                None => self.joypad.read(),
+            },
+            0xFF01 => self.serial.read_sb(),
+            0xFF02 => self.serial.read_sc(self.cgb_mode),
             0xFF04 => self.timer.read_div(),
             0xFF05 => self.tima,
             0xFF06 => self.tma,
@@ -280,27 +374,32 @@ impl MemoryBus {
             }
             0xFF4F => {
                 if self.cgb_hwio_enabled {
-                    0xFE | (self.vbk & 0x01)
+                    0xFE | (self.ppu.vram_bank & 0x01)
                 } else {
                     0xFF
                 }
             }
             0xFF68 => {
                 if self.cgb_hwio_enabled {
-                    self.bcps
+                    self.ppu.bcps
                 } else {
                     0xFF
                 }
             }
-            0xFF69 => 0xFF,
+            // BCPD/OCPD: só acessíveis em modo CGB (no modo compatível ficam travados)
+            0xFF69 if self.cgb_mode => self.ppu.read_palette_data(false),
             0xFF6A => {
                 if self.cgb_hwio_enabled {
-                    self.ocps
+                    self.ppu.ocps
                 } else {
                     0xFF
                 }
             }
-            0xFF6B => 0xFF,
+            0xFF6B if self.cgb_mode => self.ppu.read_palette_data(true),
+            // HDMA1-4 são write-only
+            0xFF55 if self.cgb_mode => self.read_hdma5(),
+            0xFF56 if self.cgb_mode => self.ir.read(),
+            0xFF70 if self.cgb_mode => 0xF8 | self.svbk,
             0xFF72 => {
                 if self.cgb_hwio_enabled {
                     self.ff72
@@ -346,7 +445,7 @@ impl MemoryBus {
         }
         if address == 0xFF50 {
             if self.boot_rom_enabled && (value & 0x01) != 0 {
-                self.boot_rom_enabled = false;
+                self.disable_boot_rom();
             }
             return;
         }
@@ -369,23 +468,10 @@ impl MemoryBus {
                 }
             }
             0xA000..=0xBFFF => self.mbc.write_ram(address, value),
-            0xC000..=0xDFFF => {
-                let idx = (address - 0xC000) as usize;
+            // WRAM e echo RAM compartilham o mesmo armazenamento
+            0xC000..=0xFDFF => {
+                let idx = self.wram_index(address);
                 self.wram[idx] = value;
-                // Espelha na echo RAM
-                let echo_addr = address + 0x2000;
-                if echo_addr <= 0xFDFF {
-                    self.wram[(echo_addr - 0xE000) as usize] = value;
-                }
-            }
-            0xE000..=0xFDFF => {
-                let idx = (address - 0xE000) as usize;
-                self.wram[idx] = value;
-                // Espelha na WRAM principal
-                let main_addr = address - 0x2000;
-                if main_addr >= 0xC000 && main_addr <= 0xDFFF {
-                    self.wram[(main_addr - 0xC000) as usize] = value;
-                }
             }
             // OAM: bloqueada em mode 2/3 e durante DMA
             0xFE00..=0xFE9F => {
@@ -395,30 +481,16 @@ impl MemoryBus {
                     self.ppu.write_oam(address, value);
                 }
             }
-            0xFF00 => self.joypad.write(value),
-            0xFF01 => {
-                // SB pode ser escrito mesmo durante transferência (mas não é recomendado)
-                self.serial_sb = value;
-                // Guarda o último byte escrito para uso em testes/debug
-                if !self.serial_transfer_active {
-                    self.serial_last_transmitted = value;
+            0xFF00 => {
+                self.joypad.write(value);
+                if let Some(sgb) = self.sgb.as_mut() {
+                    sgb.write_p1(value, &self.ppu);
                 }
             }
+            0xFF01 => self.serial.write_sb(value),
             0xFF02 => {
-                // SC: bits 1-6 são write-only (não usados)
-                // Bit 0: Clock Source (0=external/slave, 1=internal/master)
-                // Bit 7: Transfer Start Flag
-                let old_transfer_start = (self.serial_sc & 0x80) != 0;
-                let new_transfer_start = (value & 0x80) != 0;
-                let clock_source = (value & 0x01) != 0;
-
-                self.serial_clock_source = clock_source;
-                self.serial_sc = value & 0b1000_0001;
-
-                // Inicia transferência se bit 7 mudou de 0 para 1
-                if !old_transfer_start && new_transfer_start {
-                    self.start_serial_transfer();
-                }
+                let div_counter = self.timer.get_div_counter();
+                self.serial.write_sc(value, self.cgb_mode, div_counter);
             }
             0xFF04 => {
                 let (new_tima, new_if, events) =
@@ -468,6 +540,8 @@ impl MemoryBus {
             }
             0xFF10..=0xFF3F => self.apu.write_register(address, value),
             0xFF40..=0xFF4B => self.ppu.write_register(address, value, &mut self.if_),
+            // KEY0: só gravável pela boot ROM CGB, antes de FF50
+            0xFF4C if self.cgb_mode && self.boot_rom_enabled => self.key0 = value,
             0xFF4D => {
                 // KEY1: apenas bit 0 é gravável (solicitação de troca de velocidade)
                 if self.cgb_mode {
@@ -476,21 +550,37 @@ impl MemoryBus {
             }
             0xFF4F => {
                 if self.cgb_hwio_enabled {
-                    self.vbk = value & 0x01;
+                    self.ppu.vram_bank = value & 0x01;
                 }
             }
             0xFF68 => {
                 if self.cgb_hwio_enabled {
-                    self.bcps = (value & 0xBF) | 0x40;
+                    self.ppu.bcps = (value & 0xBF) | 0x40;
                 }
             }
-            0xFF69 => {}
+            // BCPD/OCPD: só graváveis em modo CGB
+            0xFF69 if self.cgb_mode => self.ppu.write_palette_data(false, value),
             0xFF6A => {
                 if self.cgb_hwio_enabled {
-                    self.ocps = (value & 0xBF) | 0x40;
+                    self.ppu.ocps = (value & 0xBF) | 0x40;
                 }
             }
-            0xFF6B => {}
+            0xFF6B if self.cgb_mode => self.ppu.write_palette_data(true, value),
+            0xFF51 if self.cgb_mode => {
+                self.hdma_src = (self.hdma_src & 0x00FF) | ((value as u16) << 8);
+            }
+            0xFF52 if self.cgb_mode => {
+                self.hdma_src = (self.hdma_src & 0xFF00) | (value & 0xF0) as u16;
+            }
+            0xFF53 if self.cgb_mode => {
+                self.hdma_dst = (self.hdma_dst & 0x00FF) | (((value & 0x1F) as u16) << 8);
+            }
+            0xFF54 if self.cgb_mode => {
+                self.hdma_dst = (self.hdma_dst & 0x1F00) | (value & 0xF0) as u16;
+            }
+            0xFF55 if self.cgb_mode => self.write_hdma5(value),
+            0xFF56 if self.cgb_mode => self.ir.write(value),
+            0xFF70 if self.cgb_mode => self.svbk = value & 0x07,
             0xFF72 => {
                 if self.cgb_hwio_enabled {
                     self.ff72 = value;
@@ -538,19 +628,16 @@ impl MemoryBus {
             0x0000..=0x7FFF => self.mbc.read_rom(addr),
             0x8000..=0x9FFF => self.ppu.read_vram(addr),
             0xA000..=0xBFFF => self.mbc.read_ram(addr),
-            0xC000..=0xDFFF => self.wram[(addr - 0xC000) as usize],
+            0xC000..=0xDFFF => self.wram[self.wram_index(addr)],
             0xE000..=0xFDFF => {
                 let base = addr - 0x2000;
                 if (0xC000..=0xDDFF).contains(&base) {
-                    self.wram[(base - 0xC000) as usize]
+                    self.wram[self.wram_index(base)]
                 } else {
                     0xFF
                 }
             }
-            0xFE00..=0xFFFF => {
-                let base = addr - 0x2000;
-                self.wram[(base - 0xC000) as usize]
-            }
+            0xFE00..=0xFFFF => self.wram[self.wram_index(addr - 0x2000)],
         }
     }
 
@@ -616,7 +703,11 @@ impl MemoryBus {
 
     pub fn tick(&mut self, cycles: u32) {
         self.step_oam_dma(cycles);
-        self.step_serial_transfer(cycles);
+        if self.serial.step(cycles, self.cgb_mode) {
+            // Dispara interrupção serial (bit 3 do IF)
+            self.if_ |= 0x08;
+        }
+        self.mbc.tick(cycles);
 
         // Timer otimizado - processa cycles em bulk
         let (new_tima, new_if, events) =
@@ -633,10 +724,24 @@ impl MemoryBus {
             self.apu.div_secondary_event();
         }
 
-        self.apu.tick_t_cycles(cycles);
+        // Timer, serial e OAM DMA seguem o clock da CPU. PPU e APU ficam
+        // sempre em 4MHz: em velocidade dupla recebem metade dos ciclos.
+        let (dots, m_cycles) = if self.cgb_speed {
+            self.double_speed_dot_carry += cycles;
+            let dots = self.double_speed_dot_carry / 2;
+            self.double_speed_dot_carry %= 2;
+            self.double_speed_apu_carry += dots;
+            let m_cycles = self.double_speed_apu_carry / 4;
+            self.double_speed_apu_carry %= 4;
+            (dots, m_cycles)
+        } else {
+            (cycles, cycles / 4)
+        };
+
+        self.apu.tick_t_cycles(dots);
+        self.ir.tick(dots);
 
         // APU channel timers - otimizado para processar múltiplos M-cycles de uma vez
-        let m_cycles = cycles / 4;
         if m_cycles > 0 {
             // Chama tick_m_cycle apenas uma vez com o número de M-cycles
             // Se tick_m_cycle não suporta múltiplos cycles, mantém o loop mas otimizado
@@ -645,7 +750,12 @@ impl MemoryBus {
             }
         }
 
-        self.ppu.step(cycles, &mut self.if_);
+        self.ppu.step(dots, &mut self.if_);
+
+        // HBlank DMA: um bloco de 16 bytes a cada entrada no modo 0
+        if self.ppu.take_hblank_event() && self.hdma_active {
+            self.hdma_transfer_block();
+        }
     }
 
     #[inline]
@@ -767,61 +877,223 @@ impl MemoryBus {
         }
     }
 
-    // ========== SERIAL PORT ==========
+    // ========== CGB WRAM / HDMA ==========
 
-    /// Inicia uma transferência serial
-    /// Chamado quando bit 7 de SC (FF02) é setado para 1
-    fn start_serial_transfer(&mut self) {
-        // Só inicia se estiver em modo internal clock (master)
-        // Em modo external clock (slave), a transferência é controlada externamente
-        if self.serial_clock_source {
-            self.serial_transfer_active = true;
-            self.serial_transfer_cycles = (self.timer.get_div_counter() & 0x01FF) as u32;
-            // Guarda o byte que será transmitido
-            self.serial_last_transmitted = self.serial_sb;
+    /// Índice em `wram` para um endereço em 0xC000-0xFDFF (echo RAM incluída).
+    /// 0xD000-0xDFFF segue o banco de SVBK em modo CGB.
+    #[inline]
+    fn wram_index(&self, address: u16) -> usize {
+        let offset = (address.wrapping_sub(0xC000) & 0x1FFF) as usize;
+        if offset < 0x1000 {
+            offset
+        } else {
+            let bank = if self.cgb_mode {
+                ((self.svbk & 0x07) as usize).max(1)
+            } else {
+                1
+            };
+            bank * 0x1000 + (offset - 0x1000)
         }
     }
 
-    /// Avança a transferência serial
-    /// Internal clock: 8192 Hz = 512 ciclos de CPU por bit = 4096 ciclos por byte
-    /// External clock: aguarda sinal externo (não implementado ainda)
-    fn step_serial_transfer(&mut self, cycles: u32) {
-        if !self.serial_transfer_active {
-            return;
+    /// STOP com KEY1 bit 0 setado: troca a velocidade da CPU.
+    /// Como todo STOP, zera o DIV; a CPU fica parada por 2050 M-cycles.
+    pub fn switch_speed(&mut self) {
+        self.write(0xFF04, 0);
+        self.cgb_speed = !self.cgb_speed;
+        self.key1 = 0;
+        self.double_speed_dot_carry = 0;
+        self.double_speed_apu_carry = 0;
+        self.cpu_stall_cycles += SPEED_SWITCH_STALL_CYCLES;
+    }
+
+    /// Ciclos de CPU parada acumulados por DMA ou troca de velocidade (zera o contador)
+    pub fn take_cpu_stall_cycles(&mut self) -> u32 {
+        std::mem::take(&mut self.cpu_stall_cycles)
+    }
+
+    fn read_hdma5(&self) -> u8 {
+        // Bit 7 = 0 enquanto o HBlank DMA está ativo; 0xFF depois de terminar
+        if self.hdma_active {
+            self.hdma_remaining & 0x7F
+        } else {
+            0x80 | self.hdma_remaining
         }
+    }
 
-        // Só processa se estiver em modo internal clock (master)
-        if !self.serial_clock_source {
-            // Em modo external clock (slave), a transferência é controlada externamente
-            // Por enquanto, não implementamos comunicação real entre consoles
+    fn write_hdma5(&mut self, value: u8) {
+        if self.hdma_active && (value & 0x80) == 0 {
+            // Escrever bit 7 = 0 durante HBlank DMA cancela a transferência
+            self.hdma_active = false;
             return;
         }
 
-        // Internal clock: 8192 Hz = 512 ciclos por bit = 4096 ciclos por byte completo
-        const SERIAL_CYCLES_PER_BYTE: u32 = 4096; // 8 bits * 512 ciclos por bit
+        self.hdma_remaining = value & 0x7F;
+        if (value & 0x80) != 0 {
+            self.hdma_active = true;
+            // Com o LCD desligado não há HBlank: o primeiro bloco sai imediatamente
+            if !self.lcd_on() {
+                self.hdma_transfer_block();
+            }
+        } else {
+            // GDMA: copia tudo de uma vez e a CPU fica parada até o fim
+            loop {
+                self.hdma_copy_block();
+                self.hdma_remaining = self.hdma_remaining.wrapping_sub(1) & 0x7F;
+                if self.hdma_remaining == 0x7F {
+                    break;
+                }
+            }
+        }
+    }
 
-        self.serial_transfer_cycles = self.serial_transfer_cycles.saturating_add(cycles);
+    /// Copia um bloco do HBlank DMA e encerra quando o contador zerar
+    fn hdma_transfer_block(&mut self) {
+        self.hdma_copy_block();
+        self.hdma_remaining = self.hdma_remaining.wrapping_sub(1) & 0x7F;
+        if self.hdma_remaining == 0x7F {
+            self.hdma_active = false;
+        }
+    }
 
-        if self.serial_transfer_cycles >= SERIAL_CYCLES_PER_BYTE {
-            // Transferência completa
-            self.complete_serial_transfer();
+    /// Copia 16 bytes para a VRAM (banco atual) e acumula a parada da CPU:
+    /// 8 M-cycles por bloco em velocidade normal, 16 em velocidade dupla
+    fn hdma_copy_block(&mut self) {
+        for i in 0..16u16 {
+            let src = self.hdma_src.wrapping_add(i);
+            let value = match src {
+                0x0000..=0x7FFF => self.mbc.read_rom(src),
+                0xA000..=0xBFFF => self.mbc.read_ram(src),
+                0xC000..=0xDFFF => self.wram[self.wram_index(src)],
+                // VRAM como origem não é válida
+                _ => 0xFF,
+            };
+            let dst = 0x8000 | (self.hdma_dst.wrapping_add(i) & 0x1FFF);
+            self.ppu.write_vram(dst, value);
         }
+        self.hdma_src = self.hdma_src.wrapping_add(16);
+        self.hdma_dst = self.hdma_dst.wrapping_add(16) & 0x1FF0;
+        self.cpu_stall_cycles += if self.cgb_speed { 64 } else { 32 };
     }
 
-    /// Completa a transferência serial e dispara interrupção
-    fn complete_serial_transfer(&mut self) {
-        // Reset do bit 7 (Transfer Start Flag)
-        self.serial_sc &= !0x80;
-        self.serial_transfer_active = false;
-        self.serial_transfer_cycles = 0;
+    // ========== SAVE STATE ==========
+
+    pub fn save_state(&self, w: &mut StateWriter) {
+        self.mbc.save_state(w);
+        w.write_bytes(&self.wram);
+        w.write_bytes(&self.hram);
+        self.timer.save_state(w);
+        self.joypad.save_state(w);
+        self.ppu.save_state(w);
+        self.apu.save_state(w);
+        w.write_bool(self.sgb.is_some());
+        if let Some(sgb) = &self.sgb {
+            sgb.save_state(w);
+        }
+        w.write_u8(self.tima);
+        w.write_u8(self.tma);
+        w.write_u8(self.tac);
+        w.write_u8(self.ie);
+        w.write_u8(self.if_);
+        w.write_bool(self.boot_rom.is_some());
+        if let Some(ref rom) = self.boot_rom {
+            w.write_vec(rom);
+        }
+        w.write_bool(self.boot_rom_enabled);
+        w.write_u8(self.key0);
+
+        w.write_bool(self.oam_dma_active);
+        w.write_u16(self.oam_dma_src);
+        w.write_u8(self.oam_dma_index);
+        w.write_u32(self.oam_dma_cycles);
+        w.write_u8(self.oam_dma_value);
+        w.write_bool(self.oam_dma_finishing);
+        w.write_u32(self.oam_dma_block_delay);
+        w.write_bytes(&self.oam_dma_startup_oam);
+        w.write_opt_u16(self.oam_dma_pending_src);
+        w.write_u32(self.oam_dma_pending_cycles);
 
-        // Captura o byte transmitido no buffer antes que o ROM possa limpar IF
-        self.serial_output_buffer.push(self.serial_last_transmitted);
+        self.serial.save_state(w);
 
-        // SB recebe 0xFF (nenhum byte recebido, sem cabo serial)
-        self.serial_sb = 0xFF;
+        w.write_u32(self.cpu_cycle_log);
 
-        // Dispara interrupção serial (bit 3 do IF)
-        self.if_ |= 0x08;
+        w.write_bool(self.cgb_mode);
+        w.write_bool(self.cgb_speed);
+        w.write_u8(self.key1);
+        w.write_bool(self.cgb_hwio_enabled);
+        w.write_u8(self.ff72);
+        w.write_u8(self.ff73);
+        w.write_u8(self.ff75);
+        self.ir.save_state(w);
+        w.write_u8(self.svbk);
+        w.write_u16(self.hdma_src);
+        w.write_u16(self.hdma_dst);
+        w.write_u8(self.hdma_remaining);
+        w.write_bool(self.hdma_active);
+        w.write_u32(self.cpu_stall_cycles);
+        w.write_u32(self.double_speed_dot_carry);
+        w.write_u32(self.double_speed_apu_carry);
+    }
+
+    pub fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.mbc.load_state(r)?;
+        r.read_bytes(&mut self.wram)?;
+        r.read_bytes(&mut self.hram)?;
+        self.timer.load_state(r)?;
+        self.joypad.load_state(r)?;
+        self.ppu.load_state(r)?;
+        self.apu.load_state(r)?;
+        if r.read_bool()? {
+            let mut sgb = Box::new(Sgb::new());
+            sgb.load_state(r)?;
+            self.sgb = Some(sgb);
+        } else {
+            self.sgb = None;
+        }
+        self.tima = r.read_u8()?;
+        self.tma = r.read_u8()?;
+        self.tac = r.read_u8()?;
+        self.ie = r.read_u8()?;
+        self.if_ = r.read_u8()?;
+        self.boot_rom = if r.read_bool()? {
+            Some(r.read_vec()?)
+        } else {
+            None
+        };
+        self.boot_rom_enabled = r.read_bool()?;
+        self.key0 = r.read_u8()?;
+
+        self.oam_dma_active = r.read_bool()?;
+        self.oam_dma_src = r.read_u16()?;
+        self.oam_dma_index = r.read_u8()?;
+        self.oam_dma_cycles = r.read_u32()?;
+        self.oam_dma_value = r.read_u8()?;
+        self.oam_dma_finishing = r.read_bool()?;
+        self.oam_dma_block_delay = r.read_u32()?;
+        r.read_bytes(&mut self.oam_dma_startup_oam)?;
+        self.oam_dma_pending_src = r.read_opt_u16()?;
+        self.oam_dma_pending_cycles = r.read_u32()?;
+
+        self.serial.load_state(r)?;
+
+        self.cpu_cycle_log = r.read_u32()?;
+
+        self.cgb_mode = r.read_bool()?;
+        self.cgb_speed = r.read_bool()?;
+        self.key1 = r.read_u8()?;
+        self.cgb_hwio_enabled = r.read_bool()?;
+        self.ff72 = r.read_u8()?;
+        self.ff73 = r.read_u8()?;
+        self.ff75 = r.read_u8()?;
+        self.ir.load_state(r)?;
+        self.svbk = r.read_u8()?;
+        self.hdma_src = r.read_u16()?;
+        self.hdma_dst = r.read_u16()?;
+        self.hdma_remaining = r.read_u8()?;
+        self.hdma_active = r.read_bool()?;
+        self.cpu_stall_cycles = r.read_u32()?;
+        self.double_speed_dot_carry = r.read_u32()?;
+        self.double_speed_apu_carry = r.read_u32()?;
+        Ok(())
     }
 }
@@ -0,0 +1,215 @@
+//! Banco de dados de cartuchos: nome canônico, região e correções para
+//! headers mentirosos e dumps ruins (mapper, RAM e modelo recomendado)
+
+use crate::GB::CPU::BootModel;
+use crate::GB::cartridge;
+use crate::GB::mbc::Unlicensed;
+
+/// Entradas embutidas, no mesmo formato aceito por `--cart-db`, pelo CRC32
+/// No-Intro do dump. Hashes ainda não conferidos contra o dump ficam `não`.
+const BUILTIN_DB: &str = "\
+# chave          | título                          | região | verificado | mapper | RAM (KB) | modelo
+crc32:46DF91AD   | Tetris (Rev 1)                  | WORLD  | sim        | -      | -        | -
+# Vermelho/Azul/Amarelo têm bordas e paletas do Super Game Boy
+crc32:9F7FDD53   | Pokemon Red Version             | USA    | sim        | -      | -        | sgb
+crc32:D6DA8A1A   | Pokemon Blue Version            | USA    | sim        | -      | -        | sgb
+crc32:7D527D62   | Pokemon Yellow Version          | USA    | sim        | -      | -        | sgb
+# Ouro/Prata/Cristal: MBC3 com relógio; o header de Ouro/Prata pede só
+# compatibilidade CGB, mas as cores são o jeito normal de jogar
+crc32:6BDE3C3E   | Pokemon Gold Version            | USA    | sim        | -      | -        | cgb
+crc32:8AD48636   | Pokemon Silver Version          | USA    | sim        | -      | -        | cgb
+crc32:EE6F5188   | Pokemon Crystal Version         | USA    | sim        | -      | -        | cgb
+crc32:3358E30A   | Pokemon Crystal Version (Rev 1) | USA    | sim        | -      | -        | cgb
+# Wisdom Tree: o header diz ROM ONLY (0x00) e 32KB, mas a ROM tem 64KB ou mais
+# e troca os 32KB inteiros pelo endereço escrito
+crc32:2C6F7E1A   | Joshua & the Battle of Jericho  | USA    | não        | wisdom-tree | -   | -
+crc32:A1D54CD4   | Exodus                          | USA    | não        | wisdom-tree | -   | -
+crc32:5D2E5F49   | Spiritual Warfare               | USA    | não        | wisdom-tree | -   | -
+crc32:3D5EC6F4   | King James Bible                | USA    | não        | wisdom-tree | -   | -
+";
+
+/// Como a entrada identifica a ROM
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CartKey {
+    /// CRC32 da ROM inteira: identifica um dump exato
+    Crc32(u32),
+    /// Checksum global gravado em 0x14E-0x14F: pega também dumps com bytes ruins
+    GlobalChecksum(u16),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Region {
+    Japan,
+    Usa,
+    Europe,
+    World,
+}
+
+impl Region {
+    pub fn from_name(name: &str) -> Option<Region> {
+        match name.to_ascii_uppercase().as_str() {
+            "JPN" | "JAPAN" => Some(Region::Japan),
+            "USA" => Some(Region::Usa),
+            "EUR" | "EUROPE" => Some(Region::Europe),
+            "WORLD" => Some(Region::World),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Region::Japan => "Japão",
+            Region::Usa => "EUA",
+            Region::Europe => "Europa",
+            Region::World => "Mundo",
+        }
+    }
+}
+
+/// Mapper que substitui o declarado no header
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapperOverride {
+    /// Byte de tipo do header (0x147) correto
+    CartType(u8),
+    /// Mapper sem licença
+    Unlicensed(Unlicensed),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CartInfo {
+    pub title: String,
+    pub region: Region,
+    pub verified: bool,
+    pub mapper: Option<MapperOverride>,
+    /// RAM externa em bytes
+    pub ram_size: Option<usize>,
+    pub model: Option<BootModel>,
+}
+
+#[derive(Debug)]
+pub struct CartDb {
+    entries: Vec<(CartKey, CartInfo)>,
+}
+
+impl CartDb {
+    pub fn builtin() -> CartDb {
+        CartDb::parse(BUILTIN_DB).expect("Banco de cartuchos embutido inválido")
+    }
+
+    /// Uma entrada por linha, campos separados por `|`; `-` deixa o campo
+    /// como está no header e `#` começa um comentário
+    pub fn parse(text: &str) -> Result<CartDb, String> {
+        let mut entries = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let entry = parse_entry(line).map_err(|e| format!("Linha {}: {}", n + 1, e))?;
+            entries.push(entry);
+        }
+        Ok(CartDb { entries })
+    }
+
+    pub fn load(path: &str) -> Result<CartDb, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        CartDb::parse(&text)
+    }
+
+    /// Acrescenta as entradas de `other` com prioridade sobre as atuais
+    pub fn merge(&mut self, other: CartDb) {
+        let mut entries = other.entries;
+        entries.append(&mut self.entries);
+        self.entries = entries;
+    }
+
+    /// CRC32 exato tem prioridade; senão vale o checksum global do header.
+    /// O CRC (a ROM inteira) só é calculado se alguma entrada usa CRC32
+    pub fn lookup(&self, rom: &[u8]) -> Option<&CartInfo> {
+        let has_crc = self
+            .entries
+            .iter()
+            .any(|(k, _)| matches!(k, CartKey::Crc32(_)));
+        if has_crc && let Some(info) = self.get(CartKey::Crc32(cartridge::crc32(rom))) {
+            return Some(info);
+        }
+        self.get(CartKey::GlobalChecksum(cartridge::header_global_checksum(
+            rom,
+        )))
+    }
+
+    /// Entrada com exatamente esta chave
+    pub fn get(&self, key: CartKey) -> Option<&CartInfo> {
+        self.entries
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, info)| info)
+    }
+}
+
+fn parse_hex(text: &str) -> Result<u32, String> {
+    let digits = text.trim_start_matches("0x").trim_start_matches("0X");
+    u32::from_str_radix(digits, 16).map_err(|_| format!("Hexadecimal inválido: {}", text))
+}
+
+fn parse_entry(line: &str) -> Result<(CartKey, CartInfo), String> {
+    let fields: Vec<&str> = line.split('|').map(str::trim).collect();
+    let [key, title, region, verified, mapper, ram, model] = fields[..] else {
+        return Err(format!("Esperados 7 campos, achei {}", fields.len()));
+    };
+
+    let key = match key.split_once(':') {
+        Some(("crc32", v)) => CartKey::Crc32(parse_hex(v)?),
+        Some(("global", v)) => {
+            let v = parse_hex(v)?;
+            CartKey::GlobalChecksum(
+                u16::try_from(v).map_err(|_| format!("Checksum global grande demais: {:X}", v))?,
+            )
+        }
+        _ => return Err(format!("Chave inválida: {}", key)),
+    };
+    let region = Region::from_name(region).ok_or_else(|| format!("Região inválida: {}", region))?;
+    let verified = match verified {
+        "sim" | "yes" => true,
+        "não" | "nao" | "no" => false,
+        _ => return Err(format!("Campo verificado inválido: {}", verified)),
+    };
+    let mapper = match mapper {
+        "-" => None,
+        name => Some(match Unlicensed::from_name(name) {
+            Some(m) => MapperOverride::Unlicensed(m),
+            None => {
+                let t = parse_hex(name)?;
+                MapperOverride::CartType(
+                    u8::try_from(t).map_err(|_| format!("Tipo de cartucho inválido: {}", name))?,
+                )
+            }
+        }),
+    };
+    let ram_size = match ram {
+        "-" => None,
+        kb => Some(
+            kb.parse::<usize>()
+                .map_err(|_| format!("RAM inválida: {}", kb))?
+                * 1024,
+        ),
+    };
+    let model = match model {
+        "-" => None,
+        name => {
+            Some(BootModel::from_name(name).ok_or_else(|| format!("Modelo inválido: {}", name))?)
+        }
+    };
+
+    Ok((
+        key,
+        CartInfo {
+            title: title.to_string(),
+            region,
+            verified,
+            mapper,
+            ram_size,
+            model,
+        },
+    ))
+}
@@ -1,6 +1,8 @@
 //! Módulo para parsing e validação de cartuchos
 
-const NINTENDO_LOGO: [u8; 48] = [
+use crate::GB::cartdb::CartInfo;
+
+pub const NINTENDO_LOGO: [u8; 48] = [
     0xCE, 0xED, 0x66, 0x66, 0xCC, 0x0D, 0x00, 0x0B, 0x03, 0x73, 0x00, 0x83, 0x00, 0x0C, 0x00, 0x0D,
     0x00, 0x08, 0x11, 0x1F, 0x88, 0x89, 0x00, 0x0E, 0xDC, 0xCC, 0x6E, 0xE6, 0xDD, 0xDD, 0xD9, 0x99,
     0xBB, 0xBB, 0x67, 0x63, 0x6E, 0x0E, 0xEC, 0xCC, 0xDD, 0xDC, 0x99, 0x9F, 0xBB, 0xB9, 0x33, 0x3E,
@@ -59,14 +61,53 @@ pub fn is_cgb_only_rom(data: &[u8]) -> bool {
     data.get(0x143).copied().unwrap_or(0x00) == 0xC0
 }
 
+/// Checksum global gravado no header (0x14E-0x14F, big-endian)
+pub fn header_global_checksum(data: &[u8]) -> u16 {
+    let hi = data.get(0x014E).copied().unwrap_or(0);
+    let lo = data.get(0x014F).copied().unwrap_or(0);
+    u16::from_be_bytes([hi, lo])
+}
+
+/// Soma de todos os bytes da ROM, exceto os do próprio checksum global.
+/// A boot ROM não confere, mas diverge em dumps ruins
+pub fn global_checksum(data: &[u8]) -> u16 {
+    data.iter()
+        .enumerate()
+        .filter(|&(i, _)| i != 0x014E && i != 0x014F)
+        .fold(0u16, |sum, (_, &b)| sum.wrapping_add(b as u16))
+}
+
+/// CRC-32 (IEEE, o mesmo do zip) usado para reconhecer dumps
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
 /// Retorna o nome do tipo de cartucho
 pub fn get_cart_type_name(cart_type: u8) -> &'static str {
     match cart_type {
         0x00 => "ROM ONLY",
         0x01 | 0x02 | 0x03 => "MBC1",
         0x05 | 0x06 => "MBC2",
+        0x0B..=0x0D => "MMM01",
         0x0F | 0x10 | 0x11 | 0x12 | 0x13 => "MBC3",
         0x19 | 0x1A | 0x1B | 0x1C | 0x1D | 0x1E => "MBC5",
+        0x20 => "MBC6",
+        0x22 => "MBC7",
+        0xFC => "POCKET CAMERA",
+        0xFD => "TAMA5",
+        0xFE => "HuC3",
+        0xFF => "HuC1",
         _ => "(desconhecido)",
     }
 }
@@ -105,8 +146,8 @@ pub fn get_ram_size_kb(code: u8) -> u32 {
     bytes / 1024
 }
 
-/// Imprime informações do cartucho
-pub fn print_info(data: &[u8]) {
+/// Imprime informações do cartucho, com os dados do banco de cartuchos se houver
+pub fn print_info(data: &[u8], info: Option<&CartInfo>) {
     let title = get_title(data);
     let cart_type = data.get(0x0147).copied().unwrap_or(0xFF);
     let rom_code = data.get(0x0148).copied().unwrap_or(0xFF);
@@ -120,4 +161,36 @@ pub fn print_info(data: &[u8]) {
         get_rom_size_kb(rom_code),
         get_ram_size_kb(ram_code)
     );
+
+    let stored = header_global_checksum(data);
+    let computed = global_checksum(data);
+    if stored == computed {
+        println!("Checksum global: {:04X} (ok)", stored);
+    } else {
+        println!(
+            "Checksum global: {:04X} (calculado {:04X}: dump alterado?)",
+            stored, computed
+        );
+    }
+
+    match info {
+        Some(info) => println!(
+            "Banco de cartuchos: {} [{}] {}",
+            info.title,
+            info.region.name(),
+            if info.verified {
+                "(dump verificado)"
+            } else {
+                "(dump conhecido, não verificado)"
+            }
+        ),
+        None => {
+            let region = if data.get(0x014A) == Some(&0x00) {
+                "Japão"
+            } else {
+                "fora do Japão"
+            };
+            println!("Região (header): {} | dump não catalogado", region);
+        }
+    }
 }
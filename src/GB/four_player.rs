@@ -0,0 +1,255 @@
+//! Four Player Adapter (DMG-07): o adaptador é o master do cabo e dá o clock
+//! para até 4 Game Boys, todos em external clock.
+//!
+//! Fase de ping: cada jogador recebe pacotes FE, STAT, STAT, STAT e responde
+//! ACK1 (0x88), ACK2 (0x88), RATE e SIZE. O nibble alto de STAT marca quem
+//! respondeu os dois ACKs e os bits 0-2 dão o número do jogador. Quando o
+//! jogador 1 manda 0xAA, o adaptador manda 0xCC x4 e entra na fase de
+//! transmissão: a cada ciclo recebe SIZE bytes de cada jogador e manda a
+//! todos os pacotes do ciclo anterior emendados (jogador 1 a 4). Um pacote
+//! só de 0xFF do jogador 1 volta ao ping
+
+use crate::GB::CPU::CPU;
+use crate::GB::serial::{self, LinkEndpoint, LocalLink};
+
+pub const PLAYERS: usize = 4;
+
+const PING_HEADER: u8 = 0xFE;
+const ACK: u8 = 0x88;
+const START: u8 = 0xAA;
+const SYNC: u8 = 0xCC;
+const RESTART: u8 = 0xFF;
+const DEFAULT_SIZE: usize = 4;
+const MAX_SIZE: usize = 16;
+
+/// Um bit a 8192 Hz
+const BIT_CYCLES: u64 = 512;
+/// Intervalo aproximado entre o início de dois bytes (~2ms); cada unidade
+/// do nibble baixo de RATE acrescenta um bit de espera
+const BYTE_CYCLES: u64 = 8192;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Ping,
+    /// 0xCC x4 entre o ping e a transmissão
+    Sync,
+    Transmission,
+}
+
+pub struct FourPlayerAdapter {
+    ports: [Option<LocalLink>; PLAYERS],
+    phase: Phase,
+    /// Posição do byte atual no pacote (ou no ciclo de transmissão)
+    position: usize,
+    /// Bits 0-3: jogadores que responderam os dois ACKs
+    connected: u8,
+    acked: [bool; PLAYERS],
+    start_requested: bool,
+    rate: u8,
+    size: usize,
+    incoming: [Vec<u8>; PLAYERS],
+    outgoing: Vec<u8>,
+
+    // Byte em andamento, um shift register por jogador
+    out: [u8; PLAYERS],
+    received: [u8; PLAYERS],
+    bit: u8,
+    byte_start: u64,
+    next_event: u64,
+    clock: u64,
+}
+
+impl Default for FourPlayerAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FourPlayerAdapter {
+    pub fn new() -> Self {
+        Self {
+            ports: [None, None, None, None],
+            phase: Phase::Ping,
+            position: 0,
+            connected: 0,
+            acked: [false; PLAYERS],
+            start_requested: false,
+            rate: 0,
+            size: DEFAULT_SIZE,
+            incoming: Default::default(),
+            outgoing: Vec::new(),
+            out: [0; PLAYERS],
+            received: [0; PLAYERS],
+            bit: 0,
+            byte_start: 0,
+            // Dá tempo dos Game Boys armarem antes do primeiro ping
+            next_event: BYTE_CYCLES,
+            clock: 0,
+        }
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// Jogadores que responderam ao ping (bit 0 = jogador 1)
+    pub fn connected(&self) -> u8 {
+        self.connected
+    }
+
+    /// Ocupa a porta `player` (0-3) e devolve a ponta do cabo que vai no Game Boy
+    pub fn attach(&mut self, player: usize) -> LocalLink {
+        let (gb, adapter) = LocalLink::pair();
+        self.ports[player] = Some(adapter);
+        gb
+    }
+
+    /// Liga a CPU na porta `player` (0-3)
+    pub fn connect(&mut self, player: usize, cpu: &mut CPU) {
+        let link = self.attach(player);
+        cpu.bus.set_link_cable(Some(Box::new(link)));
+    }
+
+    pub fn step(&mut self, cycles: u32) {
+        self.advance_to(self.clock + cycles as u64);
+    }
+
+    fn advance_to(&mut self, target: u64) {
+        while self.next_event <= target {
+            let now = self.next_event;
+            self.clock_bit(now);
+        }
+        self.clock = target;
+    }
+
+    /// Roda as CPUs (jogadores ligados por `connect`) em lockstep com o
+    /// adaptador por `cycles` ciclos de 4MHz. O adaptador só avança até a
+    /// CPU mais atrasada, então todas veem cada pulso antes do seguinte
+    pub fn run(&mut self, cpus: &mut [CPU], cycles: u64) {
+        let base = self.clock;
+        let mut cpus: Vec<&mut CPU> = cpus.iter_mut().collect();
+        serial::run_lockstep(&mut cpus, cycles, |now| self.advance_to(base + now));
+    }
+
+    fn byte_for(&self, player: usize) -> u8 {
+        match self.phase {
+            Phase::Ping if self.position == 0 => PING_HEADER,
+            Phase::Ping => (self.connected << 4) | (player as u8 + 1),
+            Phase::Sync => SYNC,
+            Phase::Transmission => self.outgoing.get(self.position).copied().unwrap_or(0),
+        }
+    }
+
+    fn clock_bit(&mut self, now: u64) {
+        if self.bit == 0 {
+            self.byte_start = now;
+            for player in 0..PLAYERS {
+                self.out[player] = self.byte_for(player);
+            }
+        }
+        for player in 0..PLAYERS {
+            let bit_out = self.out[player] & 0x80 != 0;
+            // Porta vazia: linha em repouso (1)
+            let bit_in = match self.ports[player].as_mut() {
+                Some(port) => port.clock_out(bit_out, now),
+                None => true,
+            };
+            self.out[player] <<= 1;
+            self.received[player] = (self.received[player] << 1) | bit_in as u8;
+        }
+        self.bit += 1;
+        if self.bit < 8 {
+            self.next_event = now + BIT_CYCLES;
+            return;
+        }
+        self.bit = 0;
+        let period = BYTE_CYCLES + (self.rate & 0x0F) as u64 * BIT_CYCLES;
+        self.next_event = self.byte_start + period;
+        self.finish_byte();
+    }
+
+    fn finish_byte(&mut self) {
+        match self.phase {
+            Phase::Ping => self.finish_ping_byte(),
+            Phase::Sync => {
+                self.position += 1;
+                if self.position == 4 {
+                    self.phase = Phase::Transmission;
+                    self.position = 0;
+                    self.outgoing = vec![0; self.size * PLAYERS];
+                    self.incoming = Default::default();
+                }
+            }
+            Phase::Transmission => self.finish_transmission_byte(),
+        }
+    }
+
+    fn finish_ping_byte(&mut self) {
+        for player in 0..PLAYERS {
+            if self.ports[player].is_none() {
+                continue;
+            }
+            // O 0xAA do jogador 1 também vale como resposta
+            let ack = matches!(self.received[player], ACK | START);
+            let mask = 1 << player;
+            match self.position {
+                0 => self.acked[player] = ack,
+                1 if self.acked[player] && ack => self.connected |= mask,
+                1 => self.connected &= !mask,
+                _ => {}
+            }
+        }
+
+        // O jogador 1 manda no jogo: RATE, SIZE e o início da transmissão
+        let first = self.received[0];
+        if self.ports[0].is_some() {
+            if first == START {
+                self.start_requested = true;
+            } else if self.position == 2 {
+                self.rate = first;
+            } else if self.position == 3 {
+                self.size = match first as usize {
+                    0 => DEFAULT_SIZE,
+                    size => size.min(MAX_SIZE),
+                };
+            }
+        }
+
+        self.position += 1;
+        if self.position == 4 {
+            self.position = 0;
+            if self.start_requested {
+                self.start_requested = false;
+                self.phase = Phase::Sync;
+            }
+        }
+    }
+
+    fn finish_transmission_byte(&mut self) {
+        if self.position < self.size {
+            for player in 0..PLAYERS {
+                if self.ports[player].is_some() {
+                    self.incoming[player].push(self.received[player]);
+                }
+            }
+        }
+
+        self.position += 1;
+        if self.position < self.size * PLAYERS {
+            return;
+        }
+        self.position = 0;
+        let incoming = std::mem::take(&mut self.incoming);
+        if self.ports[0].is_some() && incoming[0].iter().all(|&b| b == RESTART) {
+            self.phase = Phase::Ping;
+            self.connected = 0;
+            return;
+        }
+        // Jogador ausente entra com zeros
+        self.outgoing.clear();
+        for mut packet in incoming {
+            packet.resize(self.size, 0x00);
+            self.outgoing.extend_from_slice(&packet);
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! Link infravermelho entre emuladores
+//! Cada lado acende o próprio LED e enxerga o LED do outro.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::GB::savestate::{StateReader, StateWriter};
+
+/// O receptor se acostuma com luz constante: depois de ~3ms acesa ela deixa
+/// de ser detectada (valor aproximado)
+const IR_FADE_CYCLES: u32 = 4_194_304 * 3 / 1000;
+
+/// Um lado do link IR, visto pelo hardware emulado (cartucho HuC1 etc.)
+pub trait IrPeer: Send {
+    /// Acende/apaga o LED deste lado
+    fn set_led(&mut self, on: bool);
+
+    /// true se o outro lado está com o LED aceso
+    fn light_detected(&self) -> bool;
+
+    /// Passagem do tempo em ciclos de 4MHz (para pares com relógio próprio)
+    fn tick(&mut self, _cycles: u32) {}
+
+    /// Grava o que estiver pendente (gravação em arquivo etc.)
+    fn flush(&mut self) {}
+}
+
+/// Ponta de um link IR dentro do mesmo processo (duas instâncias de CPU)
+pub struct LocalIrPeer {
+    own: Arc<AtomicBool>,
+    remote: Arc<AtomicBool>,
+}
+
+impl LocalIrPeer {
+    /// Cria as duas pontas já apontadas uma para a outra
+    pub fn pair() -> (LocalIrPeer, LocalIrPeer) {
+        let a = Arc::new(AtomicBool::new(false));
+        let b = Arc::new(AtomicBool::new(false));
+        (
+            LocalIrPeer {
+                own: a.clone(),
+                remote: b.clone(),
+            },
+            LocalIrPeer { own: b, remote: a },
+        )
+    }
+}
+
+impl IrPeer for LocalIrPeer {
+    fn set_led(&mut self, on: bool) {
+        self.own.store(on, Ordering::Release);
+    }
+
+    fn light_detected(&self) -> bool {
+        self.remote.load(Ordering::Acquire)
+    }
+}
+
+/// Luz gravada num arquivo: cada linha é `<ciclo> <0|1>`, o estado do LED
+/// remoto a partir daquele ciclo (4MHz, contados desde que o par foi ligado).
+/// As mudanças do LED local são gravadas no mesmo formato
+pub struct IrStream {
+    events: Vec<(u64, bool)>,
+    next: usize,
+    clock: u64,
+    light: bool,
+    led: bool,
+    recorded: Vec<(u64, bool)>,
+    record_path: Option<String>,
+}
+
+impl IrStream {
+    pub fn new(mut events: Vec<(u64, bool)>) -> Self {
+        events.sort_by_key(|&(cycle, _)| cycle);
+        let mut stream = Self {
+            events,
+            next: 0,
+            clock: 0,
+            light: false,
+            led: false,
+            recorded: Vec::new(),
+            record_path: None,
+        };
+        stream.advance();
+        stream
+    }
+
+    /// `#` começa um comentário
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut events = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let event = match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [cycle, state] => cycle.parse::<u64>().ok().zip(match state {
+                    "0" => Some(false),
+                    "1" => Some(true),
+                    _ => None,
+                }),
+                _ => None,
+            };
+            events
+                .push(event.ok_or_else(|| format!("Linha {}: evento inválido: {}", n + 1, line))?);
+        }
+        Ok(Self::new(events))
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Self::parse(&text)
+    }
+
+    /// Grava o LED local em `path` no `flush` ou quando o par for desligado
+    pub fn record_to(mut self, path: &str) -> Self {
+        self.record_path = Some(path.to_string());
+        self
+    }
+
+    /// Mudanças do LED local até agora
+    pub fn recorded(&self) -> &[(u64, bool)] {
+        &self.recorded
+    }
+
+    pub fn format(events: &[(u64, bool)]) -> String {
+        events
+            .iter()
+            .map(|&(cycle, on)| format!("{} {}\n", cycle, on as u8))
+            .collect()
+    }
+
+    fn advance(&mut self) {
+        while let Some(&(cycle, on)) = self.events.get(self.next) {
+            if cycle > self.clock {
+                break;
+            }
+            self.light = on;
+            self.next += 1;
+        }
+    }
+}
+
+impl IrPeer for IrStream {
+    fn set_led(&mut self, on: bool) {
+        if on != self.led {
+            self.led = on;
+            self.recorded.push((self.clock, on));
+        }
+    }
+
+    fn light_detected(&self) -> bool {
+        self.light
+    }
+
+    fn tick(&mut self, cycles: u32) {
+        self.clock += cycles as u64;
+        self.advance();
+    }
+
+    /// Grava o LED local em `record_to` (também feito no Drop)
+    fn flush(&mut self) {
+        if let Some(path) = &self.record_path
+            && let Err(e) = std::fs::write(path, Self::format(&self.recorded))
+        {
+            eprintln!("⚠️ Erro ao gravar IR: {}: {}", path, e);
+        }
+    }
+}
+
+impl Drop for IrStream {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+/// Registrador RP (FF56) do CGB
+pub struct InfraredPort {
+    rp: u8, // bit 0: LED, bits 6-7: leitura habilitada (3)
+    light_cycles: u32,
+    peer: Option<Box<dyn IrPeer>>,
+}
+
+impl Default for InfraredPort {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InfraredPort {
+    pub fn new() -> Self {
+        Self {
+            rp: 0x00,
+            light_cycles: 0,
+            peer: None,
+        }
+    }
+
+    /// Liga o par IR (None desconecta: nunca chega luz)
+    pub fn set_peer(&mut self, peer: Option<Box<dyn IrPeer>>) {
+        self.peer = peer;
+        self.light_cycles = 0;
+        let led = self.rp & 0x01 != 0;
+        if let Some(peer) = self.peer.as_mut() {
+            peer.set_led(led);
+        }
+    }
+
+    fn receiving(&self) -> bool {
+        self.light_cycles > 0 && self.light_cycles <= IR_FADE_CYCLES
+    }
+
+    /// Bit 1 em 0 = recebendo luz; só com leitura habilitada (bits 6-7 = 3).
+    /// Bits 2-5 leem 1
+    pub fn read(&self) -> u8 {
+        let read_enabled = self.rp & 0xC0 == 0xC0;
+        let signal = if read_enabled && self.receiving() {
+            0x00
+        } else {
+            0x02
+        };
+        self.rp | 0x3C | signal
+    }
+
+    pub fn write(&mut self, value: u8) {
+        self.rp = value & 0xC1;
+        let led = value & 0x01 != 0;
+        if let Some(peer) = self.peer.as_mut() {
+            peer.set_led(led);
+        }
+    }
+
+    pub fn flush(&mut self) {
+        if let Some(peer) = self.peer.as_mut() {
+            peer.flush();
+        }
+    }
+
+    /// `cycles` em 4MHz (tempo real, também em velocidade dupla)
+    pub fn tick(&mut self, cycles: u32) {
+        let Some(peer) = self.peer.as_mut() else {
+            return;
+        };
+        peer.tick(cycles);
+        self.light_cycles = if peer.light_detected() {
+            // Conta a partir do primeiro ciclo com luz
+            self.light_cycles.saturating_add(cycles).max(1)
+        } else {
+            0
+        };
+    }
+
+    pub fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.rp);
+        w.write_u32(self.light_cycles);
+    }
+
+    pub fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.rp = r.read_u8()? & 0xC1;
+        self.light_cycles = r.read_u32()?;
+        // O par vê o LED restaurado
+        let led = self.rp & 0x01 != 0;
+        if let Some(peer) = self.peer.as_mut() {
+            peer.set_led(led);
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
 // Joypad module: encapsula toda a lógica do controle
 
+use crate::GB::savestate::{StateReader, StateWriter};
+
 pub struct Joypad {
     select: u8,              // bits 4 e 5: seleção de grupo
     dpad: u8,                // bits 0-3: estado do D-pad (0=pressed, 1=released)
@@ -127,6 +129,25 @@ impl Joypad {
         }
     }
 
+    pub fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.select);
+        w.write_u8(self.dpad);
+        w.write_u8(self.buttons);
+        w.write_bool(self.interrupt_pending);
+        w.write_u8(self.prev_state);
+        w.write_u8(self.state);
+    }
+
+    pub fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.select = r.read_u8()?;
+        self.dpad = r.read_u8()?;
+        self.buttons = r.read_u8()?;
+        self.interrupt_pending = r.read_bool()?;
+        self.prev_state = r.read_u8()?;
+        self.state = r.read_u8()?;
+        Ok(())
+    }
+
     pub fn release(&mut self, button: &str) {
         match button {
             "RIGHT" => self.dpad |= 1 << 0,
@@ -0,0 +1,157 @@
+use super::MBC;
+use super::mbc5::MBC5;
+use crate::GB::savestate::{StateReader, StateWriter};
+
+/// Família do bootleg: mesmas portas, tabelas de embaralhamento diferentes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BbdVariant {
+    Bbd,
+    Hitek,
+}
+
+// Para cada modo, de qual bit de entrada vem cada bit de saída.
+// Modos não documentados ficam como identidade.
+const BBD_DATA: [[u8; 8]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7],
+    [0, 1, 2, 3, 4, 5, 6, 7],
+    [0, 1, 2, 3, 4, 5, 6, 7],
+    [0, 1, 2, 3, 4, 5, 6, 7],
+    [0, 5, 1, 3, 4, 2, 6, 7], // Garou
+    [0, 4, 2, 3, 1, 5, 6, 7], // Harry
+    [0, 1, 2, 3, 4, 5, 6, 7],
+    [0, 1, 5, 3, 4, 2, 6, 7], // Digimon
+];
+
+const BBD_BANK: [[u8; 8]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7],
+    [0, 1, 2, 3, 4, 5, 6, 7],
+    [0, 1, 2, 3, 4, 5, 6, 7],
+    [3, 4, 2, 0, 1, 5, 6, 7], // Digimon/Garou
+    [0, 1, 2, 3, 4, 5, 6, 7],
+    [1, 2, 3, 4, 0, 5, 6, 7], // Harry
+    [0, 1, 2, 3, 4, 5, 6, 7],
+    [0, 1, 2, 3, 4, 5, 6, 7],
+];
+
+const HITEK_DATA: [[u8; 8]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7],
+    [0, 6, 5, 3, 4, 1, 2, 7],
+    [0, 5, 6, 3, 4, 2, 1, 7],
+    [0, 6, 2, 3, 4, 5, 1, 7],
+    [0, 6, 1, 3, 4, 5, 2, 7],
+    [0, 1, 6, 3, 4, 5, 2, 7],
+    [0, 2, 6, 3, 4, 1, 5, 7],
+    [0, 6, 2, 3, 4, 1, 5, 7],
+];
+
+const HITEK_BANK: [[u8; 8]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7],
+    [3, 2, 1, 0, 4, 5, 6, 7],
+    [2, 1, 0, 3, 4, 5, 6, 7],
+    [1, 0, 3, 2, 4, 5, 6, 7],
+    [0, 3, 2, 1, 4, 5, 6, 7],
+    [2, 3, 0, 1, 4, 5, 6, 7],
+    [3, 0, 1, 2, 4, 5, 6, 7],
+    [2, 0, 1, 3, 4, 5, 6, 7],
+];
+
+fn reorder_bits(value: u8, order: &[u8; 8]) -> u8 {
+    order
+        .iter()
+        .enumerate()
+        .fold(0, |out, (bit, &from)| out | (((value >> from) & 1) << bit))
+}
+
+/// Bootlegs BBD/Hitek: um MBC5 com os bits dos dados de 4000-7FFF e do
+/// número do banco embaralhados. O modo de cada um é escolhido pelo próprio
+/// jogo em 2001 (dados) e 2080 (banco)
+pub struct Bbd {
+    inner: MBC5,
+    variant: BbdVariant,
+    data_swap: u8,
+    bank_swap: u8,
+}
+
+impl Bbd {
+    pub fn new(rom: Vec<u8>, ram_size: usize, variant: BbdVariant) -> Self {
+        Self {
+            inner: MBC5::new(rom, ram_size),
+            variant,
+            data_swap: 0,
+            bank_swap: 0,
+        }
+    }
+
+    /// CRC32 do segundo logo (0x0184-0x01B3), que esses cartuchos trazem
+    pub fn detect(rom: &[u8]) -> Option<BbdVariant> {
+        let logo = rom.get(0x0184..0x01B4)?;
+        match crate::GB::cartridge::crc32(logo) {
+            0x4FDA_B691 => Some(BbdVariant::Hitek),
+            // Dumps já "consertados" marcam 0x7FFF com 0x01
+            0xC7D8_C1DF | 0x6D1E_A662 if rom.get(0x7FFF) != Some(&0x01) => Some(BbdVariant::Bbd),
+            _ => None,
+        }
+    }
+
+    fn tables(&self) -> (&'static [[u8; 8]; 8], &'static [[u8; 8]; 8]) {
+        match self.variant {
+            BbdVariant::Bbd => (&BBD_DATA, &BBD_BANK),
+            BbdVariant::Hitek => (&HITEK_DATA, &HITEK_BANK),
+        }
+    }
+}
+
+impl MBC for Bbd {
+    fn read_rom(&self, address: u16) -> u8 {
+        let value = self.inner.read_rom(address);
+        if address < 0x4000 {
+            return value;
+        }
+        let (data, _) = self.tables();
+        reorder_bits(value, &data[self.data_swap as usize])
+    }
+
+    fn write_register(&mut self, address: u16, value: u8) {
+        let mut value = value;
+        match address & 0xF0FF {
+            0x2000 => {
+                let (_, bank) = self.tables();
+                value = reorder_bits(value, &bank[self.bank_swap as usize]);
+            }
+            0x2001 => self.data_swap = value & 0x07,
+            0x2080 => self.bank_swap = value & 0x07,
+            _ => {}
+        }
+        // As portas de modo ficam dentro de 2000-2FFF: o MBC5 também as vê
+        self.inner.write_register(address, value);
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        self.inner.read_ram(address)
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        self.inner.write_ram(address, value);
+    }
+
+    fn save_ram(&self) -> Option<Vec<u8>> {
+        self.inner.save_ram()
+    }
+
+    fn load_ram(&mut self, data: &[u8]) {
+        self.inner.load_ram(data);
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        self.inner.save_state(w);
+        w.write_u8(self.data_swap);
+        w.write_u8(self.bank_swap);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.inner.load_state(r)?;
+        self.data_swap = r.read_u8()? & 0x07;
+        self.bank_swap = r.read_u8()? & 0x07;
+        Ok(())
+    }
+}
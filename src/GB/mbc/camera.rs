@@ -0,0 +1,346 @@
+use super::MBC;
+use crate::GB::savestate::{StateReader, StateWriter};
+
+/// Resolução da imagem entregue pelo sensor M64282FP ao cartucho
+pub const SENSOR_WIDTH: usize = 128;
+pub const SENSOR_HEIGHT: usize = 112;
+
+/// A imagem processada vai para a RAM (banco 0) a partir daqui, em tiles 2bpp
+const IMAGE_RAM_START: usize = 0x0100;
+/// Duração mínima da captura, mais 64 ciclos por unidade de exposição
+const CAPTURE_BASE_CYCLES: u32 = 129_792;
+/// Registradores A000-A035 (espelhados a cada 0x80 bytes)
+const REGISTER_COUNT: usize = 0x36;
+const DITHER_MATRIX_START: usize = 0x06;
+/// Ganho do realce de bordas (A004 bits 4-6)
+const EDGE_RATIOS: [f32; 8] = [0.5, 0.75, 1.0, 1.25, 2.0, 3.0, 4.0, 5.0];
+
+/// Callback que preenche SENSOR_WIDTH × SENSOR_HEIGHT bytes de cinza
+/// (0 = preto, 255 = branco) no momento da captura
+pub type CameraCallback = Box<dyn FnMut(&mut [u8]) + Send>;
+
+/// De onde vem a "luz" que chega ao sensor
+pub enum CameraSource {
+    /// Imagem fixa, já no tamanho do sensor
+    Image(Vec<u8>),
+    Callback(CameraCallback),
+}
+
+impl CameraSource {
+    /// Carrega um PGM (P2 ou P5) e redimensiona para o tamanho do sensor
+    pub fn from_pgm_file(path: &str) -> Result<Self, String> {
+        let data = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+        Self::from_pgm(&data)
+    }
+
+    pub fn from_pgm(data: &[u8]) -> Result<Self, String> {
+        let (width, height, pixels) = parse_pgm(data)?;
+        let mut image = vec![0u8; SENSOR_WIDTH * SENSOR_HEIGHT];
+        for y in 0..SENSOR_HEIGHT {
+            let src_y = y * height / SENSOR_HEIGHT;
+            for x in 0..SENSOR_WIDTH {
+                let src_x = x * width / SENSOR_WIDTH;
+                image[y * SENSOR_WIDTH + x] = pixels[src_y * width + src_x];
+            }
+        }
+        Ok(CameraSource::Image(image))
+    }
+
+    fn capture(&mut self, out: &mut [u8]) {
+        match self {
+            CameraSource::Image(image) => out.copy_from_slice(image),
+            CameraSource::Callback(callback) => callback(out),
+        }
+    }
+}
+
+/// Lê o cabeçalho e os pixels de um PGM, normalizando para 0-255
+/// (também usado pelas referências de tela do test runner)
+pub fn parse_pgm(data: &[u8]) -> Result<(usize, usize, Vec<u8>), String> {
+    let mut pos = 0;
+    let mut token = || -> Result<String, String> {
+        loop {
+            match data.get(pos) {
+                Some(b'#') => {
+                    while data.get(pos).is_some_and(|&c| c != b'\n') {
+                        pos += 1;
+                    }
+                }
+                Some(c) if c.is_ascii_whitespace() => pos += 1,
+                Some(_) => break,
+                None => return Err("PGM truncado".to_string()),
+            }
+        }
+        let start = pos;
+        while data.get(pos).is_some_and(|c| !c.is_ascii_whitespace()) {
+            pos += 1;
+        }
+        Ok(String::from_utf8_lossy(&data[start..pos]).into_owned())
+    };
+
+    let magic = token()?;
+    let number = |s: String| {
+        s.parse::<usize>()
+            .map_err(|_| format!("Valor inválido no PGM: {}", s))
+    };
+    let width = number(token()?)?;
+    let height = number(token()?)?;
+    let max = number(token()?)?;
+    if width == 0 || height == 0 || max == 0 || max > 0xFFFF {
+        return Err("Cabeçalho PGM inválido".to_string());
+    }
+    let scale = |v: usize| (v.min(max) * 255 / max) as u8;
+
+    let count = width * height;
+    let pixels = match magic.as_str() {
+        "P2" => (0..count)
+            .map(|_| token().and_then(number).map(scale))
+            .collect::<Result<Vec<u8>, String>>()?,
+        "P5" => {
+            // Um único espaço separa o cabeçalho dos dados binários
+            let start = pos + 1;
+            let bytes_per_pixel = if max > 0xFF { 2 } else { 1 };
+            let raw = data
+                .get(start..start + count * bytes_per_pixel)
+                .ok_or("PGM truncado")?;
+            raw.chunks(bytes_per_pixel)
+                .map(|c| match c {
+                    [hi, lo] => scale(((*hi as usize) << 8) | *lo as usize),
+                    [v] => scale(*v as usize),
+                    _ => 0,
+                })
+                .collect()
+        }
+        _ => return Err(format!("Formato de imagem não suportado: {}", magic)),
+    };
+    Ok((width, height, pixels))
+}
+
+/// Game Boy Camera (Pocket Camera): 128KB de RAM em 16 bancos e os
+/// registradores do sensor mapeados em A000 quando o banco de RAM tem o bit 4
+pub struct Camera {
+    rom: Vec<u8>,
+    ram: Vec<u8>,
+    ram_enabled: bool,
+    rom_bank: u8,
+    ram_bank: u8,
+    registers: [u8; REGISTER_COUNT],
+    /// Ciclos até a captura em andamento terminar (0 = livre)
+    capture_cycles: u32,
+    source: Option<CameraSource>,
+}
+
+impl Camera {
+    pub fn new(rom: Vec<u8>) -> Self {
+        Self {
+            rom,
+            ram: vec![0; 128 * 1024],
+            ram_enabled: false,
+            rom_bank: 1,
+            ram_bank: 0,
+            registers: [0; REGISTER_COUNT],
+            capture_cycles: 0,
+            source: None,
+        }
+    }
+
+    fn rom_bank_count(&self) -> usize {
+        (self.rom.len() / 0x4000).max(1)
+    }
+
+    fn registers_mapped(&self) -> bool {
+        self.ram_bank & 0x10 != 0
+    }
+
+    fn ram_index(&self, address: u16) -> usize {
+        (self.ram_bank as usize & 0x0F) * 0x2000 + (address - 0xA000) as usize
+    }
+
+    fn exposure(&self) -> u32 {
+        ((self.registers[2] as u32) << 8) | self.registers[3] as u32
+    }
+
+    /// Pipeline do M64282FP: exposição e ganho, inversão, realce de bordas e
+    /// quantização em 4 tons pela matriz de dithering 4x4
+    pub fn process_image(registers: &[u8], raw: &[u8]) -> Vec<u8> {
+        // A001: bits 0-4 ganho (~1dB por passo), bits 5-6 direção do realce
+        let gain = 10f32.powf((registers[1] & 0x1F) as f32 / 20.0);
+        let exposure = (((registers[2] as u32) << 8) | registers[3] as u32) as f32 / 0x1000 as f32;
+        let invert = registers[4] & 0x08 != 0;
+
+        let signal: Vec<f32> = raw
+            .iter()
+            .map(|&light| {
+                let v = (light as f32 * exposure * gain).clamp(0.0, 255.0);
+                if invert { 255.0 - v } else { v }
+            })
+            .collect();
+
+        let at = |x: isize, y: isize| {
+            let x = x.clamp(0, SENSOR_WIDTH as isize - 1) as usize;
+            let y = y.clamp(0, SENSOR_HEIGHT as isize - 1) as usize;
+            signal[y * SENSOR_WIDTH + x]
+        };
+        let ratio = EDGE_RATIOS[((registers[4] >> 4) & 0x07) as usize];
+        let horizontal = registers[1] & 0x20 != 0;
+        let vertical = registers[1] & 0x40 != 0;
+
+        let mut shades = vec![0u8; SENSOR_WIDTH * SENSOR_HEIGHT];
+        for y in 0..SENSOR_HEIGHT {
+            for x in 0..SENSOR_WIDTH {
+                let (xi, yi) = (x as isize, y as isize);
+                let center = at(xi, yi);
+                let mut value = center;
+                if horizontal {
+                    value += (2.0 * center - at(xi - 1, yi) - at(xi + 1, yi)) * ratio;
+                }
+                if vertical {
+                    value += (2.0 * center - at(xi, yi - 1) - at(xi, yi + 1)) * ratio;
+                }
+
+                // Três limiares por posição da matriz: abaixo do primeiro é preto
+                let base = DITHER_MATRIX_START + ((y & 3) * 4 + (x & 3)) * 3;
+                let thresholds = &registers[base..base + 3];
+                shades[y * SENSOR_WIDTH + x] = if value < thresholds[0] as f32 {
+                    3
+                } else if value < thresholds[1] as f32 {
+                    2
+                } else if value < thresholds[2] as f32 {
+                    1
+                } else {
+                    0
+                };
+            }
+        }
+        shades
+    }
+
+    /// Fim da exposição: processa a imagem e grava os tiles na RAM
+    fn finish_capture(&mut self) {
+        let mut raw = vec![0x80u8; SENSOR_WIDTH * SENSOR_HEIGHT];
+        if let Some(source) = self.source.as_mut() {
+            source.capture(&mut raw);
+        }
+        let shades = Self::process_image(&self.registers, &raw);
+
+        for tile_y in 0..SENSOR_HEIGHT / 8 {
+            for tile_x in 0..SENSOR_WIDTH / 8 {
+                let tile = IMAGE_RAM_START + (tile_y * (SENSOR_WIDTH / 8) + tile_x) * 16;
+                for row in 0..8 {
+                    let (mut lo, mut hi) = (0u8, 0u8);
+                    for col in 0..8 {
+                        let shade = shades[(tile_y * 8 + row) * SENSOR_WIDTH + tile_x * 8 + col];
+                        lo = (lo << 1) | (shade & 1);
+                        hi = (hi << 1) | (shade >> 1);
+                    }
+                    self.ram[tile + row * 2] = lo;
+                    self.ram[tile + row * 2 + 1] = hi;
+                }
+            }
+        }
+        self.registers[0] &= !0x01;
+    }
+}
+
+impl MBC for Camera {
+    fn read_rom(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x3FFF => self.rom.get(address as usize).copied().unwrap_or(0xFF),
+            0x4000..=0x7FFF => {
+                let bank = self.rom_bank as usize % self.rom_bank_count();
+                let idx = bank * 0x4000 + ((address - 0x4000) as usize);
+                self.rom.get(idx).copied().unwrap_or(0xFF)
+            }
+            _ => 0xFF,
+        }
+    }
+
+    fn write_register(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1FFF => self.ram_enabled = (value & 0x0F) == 0x0A,
+            0x2000..=0x3FFF => self.rom_bank = value & 0x3F,
+            0x4000..=0x5FFF => self.ram_bank = value & 0x1F,
+            _ => {}
+        }
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        if self.registers_mapped() {
+            // Só A000 é legível: bit 0 = captura em andamento
+            return if address & 0x7F == 0 {
+                self.registers[0] & 0x07
+            } else {
+                0x00
+            };
+        }
+        // A RAM é legível mesmo desabilitada, mas some durante a captura
+        if self.capture_cycles > 0 {
+            return 0x00;
+        }
+        self.ram[self.ram_index(address)]
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if self.registers_mapped() {
+            let reg = (address & 0x7F) as usize;
+            if reg == 0 {
+                self.registers[0] = value & 0x07;
+                if value & 0x01 != 0 && self.capture_cycles == 0 {
+                    self.capture_cycles = CAPTURE_BASE_CYCLES + self.exposure() * 64;
+                } else if value & 0x01 == 0 {
+                    // Zerar o bit 0 cancela a captura
+                    self.capture_cycles = 0;
+                }
+            } else if reg < REGISTER_COUNT {
+                self.registers[reg] = value;
+            }
+            return;
+        }
+        if !self.ram_enabled || self.capture_cycles > 0 {
+            return;
+        }
+        let idx = self.ram_index(address);
+        self.ram[idx] = value;
+    }
+
+    fn save_ram(&self) -> Option<Vec<u8>> {
+        Some(self.ram.clone())
+    }
+
+    fn load_ram(&mut self, data: &[u8]) {
+        let len = data.len().min(self.ram.len());
+        self.ram[..len].copy_from_slice(&data[..len]);
+    }
+
+    fn tick(&mut self, cycles: u32) {
+        if self.capture_cycles == 0 {
+            return;
+        }
+        self.capture_cycles = self.capture_cycles.saturating_sub(cycles);
+        if self.capture_cycles == 0 {
+            self.finish_capture();
+        }
+    }
+
+    fn set_camera_source(&mut self, source: Option<CameraSource>) {
+        self.source = source;
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_bool(self.ram_enabled);
+        w.write_u8(self.rom_bank);
+        w.write_u8(self.ram_bank);
+        w.write_bytes(&self.registers);
+        w.write_u32(self.capture_cycles);
+        w.write_vec(&self.ram);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.ram_enabled = r.read_bool()?;
+        self.rom_bank = r.read_u8()?;
+        self.ram_bank = r.read_u8()?;
+        r.read_bytes(&mut self.registers)?;
+        self.capture_cycles = r.read_u32()?;
+        r.read_vec_into(&mut self.ram)
+    }
+}
@@ -0,0 +1,130 @@
+use super::MBC;
+use crate::GB::infrared::IrPeer;
+use crate::GB::savestate::{StateReader, StateWriter};
+
+/// Cartucho HuC1 (Hudson): banking no estilo MBC1 sem modo de banking e
+/// um LED/sensor infravermelho no lugar da RAM quando 0x0E é escrito em 0000-1FFF
+pub struct HuC1 {
+    rom: Vec<u8>,
+    ram: Vec<u8>,
+    ir_mode: bool,
+    rom_bank: u8,
+    ram_bank: u8,
+    ir_led: bool,
+    ir_peer: Option<Box<dyn IrPeer>>,
+}
+
+impl HuC1 {
+    pub fn new(rom: Vec<u8>, ram_size: usize) -> Self {
+        Self {
+            rom,
+            ram: vec![0; ram_size],
+            ir_mode: false,
+            rom_bank: 1,
+            ram_bank: 0,
+            ir_led: false,
+            ir_peer: None,
+        }
+    }
+
+    fn rom_bank_count(&self) -> usize {
+        (self.rom.len() / 0x4000).max(1)
+    }
+
+    fn ram_index(&self, address: u16) -> usize {
+        let banks = (self.ram.len() / 0x2000).max(1);
+        (self.ram_bank as usize % banks) * 0x2000 + ((address - 0xA000) as usize)
+    }
+
+    fn set_led(&mut self, on: bool) {
+        self.ir_led = on;
+        if let Some(peer) = self.ir_peer.as_mut() {
+            peer.set_led(on);
+        }
+    }
+}
+
+impl MBC for HuC1 {
+    fn read_rom(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x3FFF => self.rom.get(address as usize).copied().unwrap_or(0xFF),
+            0x4000..=0x7FFF => {
+                let bank = self.rom_bank as usize % self.rom_bank_count();
+                let idx = bank * 0x4000 + ((address - 0x4000) as usize);
+                self.rom.get(idx).copied().unwrap_or(0xFF)
+            }
+            _ => 0xFF,
+        }
+    }
+
+    fn write_register(&mut self, address: u16, value: u8) {
+        match address {
+            // 0x0E liga o modo IR; qualquer outro valor volta para a RAM
+            0x0000..=0x1FFF => self.ir_mode = value == 0x0E,
+            0x2000..=0x3FFF => self.rom_bank = (value & 0x3F).max(1),
+            0x4000..=0x5FFF => self.ram_bank = value & 0x03,
+            _ => {}
+        }
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        if self.ir_mode {
+            // bit 0 = 1 quando o sensor recebe luz
+            let light = self
+                .ir_peer
+                .as_ref()
+                .is_some_and(|peer| peer.light_detected());
+            return 0xC0 | light as u8;
+        }
+        self.ram
+            .get(self.ram_index(address))
+            .copied()
+            .unwrap_or(0xFF)
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if self.ir_mode {
+            self.set_led(value & 0x01 != 0);
+            return;
+        }
+        let idx = self.ram_index(address);
+        if idx < self.ram.len() {
+            self.ram[idx] = value;
+        }
+    }
+
+    fn save_ram(&self) -> Option<Vec<u8>> {
+        if self.ram.is_empty() {
+            None
+        } else {
+            Some(self.ram.clone())
+        }
+    }
+
+    fn load_ram(&mut self, data: &[u8]) {
+        let len = data.len().min(self.ram.len());
+        self.ram[..len].copy_from_slice(&data[..len]);
+    }
+
+    fn set_ir_peer(&mut self, peer: Option<Box<dyn IrPeer>>) {
+        self.ir_peer = peer;
+        self.set_led(self.ir_led);
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_bool(self.ir_mode);
+        w.write_u8(self.rom_bank);
+        w.write_u8(self.ram_bank);
+        w.write_bool(self.ir_led);
+        w.write_vec(&self.ram);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.ir_mode = r.read_bool()?;
+        self.rom_bank = r.read_u8()?;
+        self.ram_bank = r.read_u8()?;
+        let led = r.read_bool()?;
+        self.set_led(led);
+        r.read_vec_into(&mut self.ram)
+    }
+}
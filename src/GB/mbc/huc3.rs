@@ -0,0 +1,272 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::MBC;
+use crate::GB::savestate::{StateReader, StateWriter};
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// Cartucho HuC3 (Hudson): banking estilo MBC1/MBC3 e um microcontrolador
+/// com RTC (minutos do dia + dias), acessado por comandos de 1 byte em A000.
+/// O modo escrito em 0000-1FFF escolhe o que A000-BFFF enxerga.
+pub struct HuC3 {
+    rom: Vec<u8>,
+    ram: Vec<u8>,
+    mode: u8,
+    rom_bank: u8,
+    ram_bank: u8,
+
+    // Memória de nibbles do microcontrolador; 0x00-0x06 recebe o horário
+    // no comando 0x60 (minutos em 0x00-0x02, dias em 0x03-0x06)
+    rtc_mem: [u8; 256],
+    rtc_address: u8,
+    // Última resposta: comando no nibble alto, valor no baixo
+    response: u8,
+
+    minutes: u16,
+    days: u16,
+    // Segundos dentro do minuto atual (o jogo não vê, mas evita perder tempo)
+    seconds: u8,
+    // Timestamp do host (segundos desde UNIX_EPOCH) para avanço do RTC
+    rtc_last_update: i64,
+
+    // IR / alto-falante: só guardam o último valor escrito
+    ir_led: bool,
+    tone: u8,
+}
+
+impl HuC3 {
+    pub fn new(rom: Vec<u8>, ram_size: usize) -> Self {
+        Self {
+            rom,
+            ram: vec![0; ram_size],
+            mode: 0,
+            rom_bank: 1,
+            ram_bank: 0,
+            rtc_mem: [0; 256],
+            rtc_address: 0,
+            response: 0,
+            minutes: 0,
+            days: 0,
+            seconds: 0,
+            rtc_last_update: Self::now_secs(),
+            ir_led: false,
+            tone: 0,
+        }
+    }
+
+    #[inline]
+    fn now_secs() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64
+    }
+
+    fn rom_bank_count(&self) -> usize {
+        (self.rom.len() / 0x4000).max(1)
+    }
+
+    /// Avança o relógio com base no tempo do host
+    fn update_rtc(&mut self) {
+        let now = Self::now_secs();
+        let delta = now.saturating_sub(self.rtc_last_update);
+        if delta > 0 {
+            self.add_rtc_seconds(delta as u64);
+        }
+        self.rtc_last_update = now;
+    }
+
+    /// Soma `seconds` ao relógio: minutos voltam a 0 a cada 1440, dias têm 16 bits
+    fn add_rtc_seconds(&mut self, seconds: u64) {
+        let total = self.seconds as u64 + seconds;
+        self.seconds = (total % 60) as u8;
+        let minutes = self.minutes as u64 + total / 60;
+        self.minutes = (minutes % MINUTES_PER_DAY as u64) as u16;
+        let days = self.days as u64 + minutes / MINUTES_PER_DAY as u64;
+        self.days = days as u16;
+    }
+
+    /// Executa um comando escrito em A000 no modo 0xB
+    fn command(&mut self, value: u8) {
+        let arg = value & 0x0F;
+        match value >> 4 {
+            // Lê o nibble no endereço atual e avança
+            0x1 => {
+                self.response = 0x10 | self.rtc_mem[self.rtc_address as usize];
+                self.rtc_address = self.rtc_address.wrapping_add(1);
+            }
+            // Escreve o nibble (0x3 também avança o endereço)
+            0x2 | 0x3 => {
+                self.rtc_mem[self.rtc_address as usize] = arg;
+                if value >> 4 == 0x3 {
+                    self.rtc_address = self.rtc_address.wrapping_add(1);
+                }
+            }
+            0x4 => self.rtc_address = (self.rtc_address & 0xF0) | arg,
+            0x5 => self.rtc_address = (self.rtc_address & 0x0F) | (arg << 4),
+            0x6 => self.extended_command(arg),
+            _ => {}
+        }
+    }
+
+    fn extended_command(&mut self, arg: u8) {
+        match arg {
+            // Copia o horário atual para 0x00-0x06
+            0x0 => {
+                self.update_rtc();
+                for i in 0..3 {
+                    self.rtc_mem[i] = ((self.minutes >> (i * 4)) & 0x0F) as u8;
+                }
+                for i in 0..4 {
+                    self.rtc_mem[3 + i] = ((self.days >> (i * 4)) & 0x0F) as u8;
+                }
+            }
+            // Grava 0x00-0x06 no relógio
+            0x1 => {
+                let nibble = |i: usize| self.rtc_mem[i] as u16;
+                let minutes = nibble(0) | (nibble(1) << 4) | (nibble(2) << 8);
+                let days = nibble(3) | (nibble(4) << 4) | (nibble(5) << 8) | (nibble(6) << 12);
+                self.minutes = minutes % MINUTES_PER_DAY as u16;
+                self.days = days;
+                self.seconds = 0;
+                self.rtc_last_update = Self::now_secs();
+            }
+            // Status: o microcontrolador sempre responde "pronto"
+            0x2 => self.response = 0x61,
+            // Demais (tom do alto-falante etc.): só registra
+            _ => self.tone = arg,
+        }
+    }
+
+    fn ram_index(&self, address: u16) -> usize {
+        (self.ram_bank as usize) * 0x2000 + ((address - 0xA000) as usize)
+    }
+}
+
+impl MBC for HuC3 {
+    fn read_rom(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x3FFF => self.rom.get(address as usize).copied().unwrap_or(0xFF),
+            0x4000..=0x7FFF => {
+                let bank = self.rom_bank as usize % self.rom_bank_count();
+                let idx = bank * 0x4000 + ((address - 0x4000) as usize);
+                self.rom.get(idx).copied().unwrap_or(0xFF)
+            }
+            _ => 0xFF,
+        }
+    }
+
+    fn write_register(&mut self, address: u16, value: u8) {
+        match address {
+            // Modo de A000-BFFF: 0x0 RAM só leitura, 0xA RAM, 0xB comando,
+            // 0xC resposta, 0xD semáforo, 0xE IR
+            0x0000..=0x1FFF => self.mode = value & 0x0F,
+            0x2000..=0x3FFF => self.rom_bank = (value & 0x7F).max(1),
+            0x4000..=0x5FFF => self.ram_bank = value & 0x03,
+            _ => {}
+        }
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        match self.mode {
+            0x0 | 0xA => self
+                .ram
+                .get(self.ram_index(address))
+                .copied()
+                .unwrap_or(0xFF),
+            0xC => self.response,
+            // Semáforo: bit 0 = 1 indica que o comando terminou
+            0xD => 0x01,
+            // Nenhuma luz recebida
+            0xE => 0xC0,
+            _ => 0xFF,
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        match self.mode {
+            0xA => {
+                let idx = self.ram_index(address);
+                if idx < self.ram.len() {
+                    self.ram[idx] = value;
+                }
+            }
+            0xB => self.command(value),
+            0xE => self.ir_led = value & 0x01 != 0,
+            _ => {}
+        }
+    }
+
+    fn save_ram(&self) -> Option<Vec<u8>> {
+        if self.ram.is_empty() {
+            return None;
+        }
+        // Cópia do relógio avançada até agora, sem mexer no estado
+        let mut clock = HuC3::new(Vec::new(), 0);
+        clock.minutes = self.minutes;
+        clock.days = self.days;
+        clock.seconds = self.seconds;
+        clock.rtc_last_update = self.rtc_last_update;
+        clock.update_rtc();
+
+        let mut buf = self.ram.clone();
+        buf.extend_from_slice(&clock.minutes.to_le_bytes());
+        buf.extend_from_slice(&clock.days.to_le_bytes());
+        buf.extend_from_slice(&clock.rtc_last_update.to_le_bytes());
+        Some(buf)
+    }
+
+    fn load_ram(&mut self, data: &[u8]) {
+        let ram_len = self.ram.len();
+        let len = data.len().min(ram_len);
+        self.ram[..len].copy_from_slice(&data[..len]);
+
+        // Relógio (minutos + dias) e timestamp do host depois da RAM
+        let now = Self::now_secs();
+        if let Some(tail) = data.get(ram_len..ram_len + 12) {
+            self.minutes = u16::from_le_bytes([tail[0], tail[1]]) % MINUTES_PER_DAY as u16;
+            self.days = u16::from_le_bytes([tail[2], tail[3]]);
+            self.seconds = 0;
+            let mut ts_bytes = [0u8; 8];
+            ts_bytes.copy_from_slice(&tail[4..12]);
+            let saved_ts = i64::from_le_bytes(ts_bytes);
+            if saved_ts > 0 && now > saved_ts {
+                // Avança o relógio pelo tempo em que o emulador ficou fechado
+                self.add_rtc_seconds((now - saved_ts) as u64);
+            }
+        }
+        self.rtc_last_update = now;
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.mode);
+        w.write_u8(self.rom_bank);
+        w.write_u8(self.ram_bank);
+        w.write_bytes(&self.rtc_mem);
+        w.write_u8(self.rtc_address);
+        w.write_u8(self.response);
+        w.write_u16(self.minutes);
+        w.write_u16(self.days);
+        w.write_u8(self.seconds);
+        w.write_i64(self.rtc_last_update);
+        w.write_bool(self.ir_led);
+        w.write_u8(self.tone);
+        w.write_vec(&self.ram);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.mode = r.read_u8()?;
+        self.rom_bank = r.read_u8()?;
+        self.ram_bank = r.read_u8()? & 0x03;
+        r.read_bytes(&mut self.rtc_mem)?;
+        self.rtc_address = r.read_u8()?;
+        self.response = r.read_u8()?;
+        self.minutes = r.read_u16()?;
+        self.days = r.read_u16()?;
+        self.seconds = r.read_u8()?;
+        self.rtc_last_update = r.read_i64()?;
+        self.ir_led = r.read_bool()?;
+        self.tone = r.read_u8()?;
+        r.read_vec_into(&mut self.ram)
+    }
+}
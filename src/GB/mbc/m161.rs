@@ -0,0 +1,69 @@
+use super::MBC;
+use crate::GB::savestate::{StateReader, StateWriter};
+
+/// M161 (multicart "Mani 4 in 1"): a primeira escrita em 4000-5FFF escolhe
+/// um de 8 bancos de 32KB e trava o mapeamento até o próximo reset
+pub struct M161 {
+    rom: Vec<u8>,
+    bank: u8,
+    locked: bool,
+}
+
+impl M161 {
+    pub fn new(rom: Vec<u8>) -> Self {
+        Self {
+            rom,
+            bank: 0,
+            locked: false,
+        }
+    }
+
+    /// O cartucho declara MBC3 (0x10) com o título do menu
+    pub fn detect(rom: &[u8]) -> bool {
+        rom.get(0x0147) == Some(&0x10) && rom.get(0x0134..0x013E) == Some(b"TETRIS SET")
+    }
+
+    fn bank_count(&self) -> usize {
+        (self.rom.len() / 0x8000).max(1)
+    }
+}
+
+impl MBC for M161 {
+    fn read_rom(&self, address: u16) -> u8 {
+        let bank = self.bank as usize % self.bank_count();
+        self.rom
+            .get(bank * 0x8000 + address as usize)
+            .copied()
+            .unwrap_or(0xFF)
+    }
+
+    fn write_register(&mut self, address: u16, value: u8) {
+        if (0x4000..=0x5FFF).contains(&address) && !self.locked {
+            self.bank = value & 0x07;
+            self.locked = true;
+        }
+    }
+
+    fn read_ram(&self, _address: u16) -> u8 {
+        0xFF
+    }
+
+    fn write_ram(&mut self, _address: u16, _value: u8) {}
+
+    fn save_ram(&self) -> Option<Vec<u8>> {
+        None
+    }
+
+    fn load_ram(&mut self, _data: &[u8]) {}
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.bank);
+        w.write_bool(self.locked);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.bank = r.read_u8()? & 0x07;
+        self.locked = r.read_bool()?;
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use super::MBC;
+use crate::GB::savestate::{StateReader, StateWriter};
 
 pub struct MBC1 {
     rom: Vec<u8>,
@@ -152,4 +153,20 @@ impl MBC for MBC1 {
         let len = data.len().min(self.ram.len());
         self.ram[..len].copy_from_slice(&data[..len]);
     }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_bool(self.ram_enabled);
+        w.write_u8(self.bank_reg1);
+        w.write_u8(self.bank_reg2);
+        w.write_u8(self.mode);
+        w.write_vec(&self.ram);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.ram_enabled = r.read_bool()?;
+        self.bank_reg1 = r.read_u8()?;
+        self.bank_reg2 = r.read_u8()?;
+        self.mode = r.read_u8()?;
+        r.read_vec_into(&mut self.ram)
+    }
 }
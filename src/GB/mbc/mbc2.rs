@@ -1,4 +1,5 @@
 use super::MBC;
+use crate::GB::savestate::{StateReader, StateWriter};
 
 pub struct MBC2 {
     rom: Vec<u8>,
@@ -66,4 +67,14 @@ impl MBC for MBC2 {
         let len = data.len().min(512);
         self.ram[..len].copy_from_slice(&data[..len]);
     }
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_bool(self.ram_enabled);
+        w.write_u8(self.rom_bank);
+        w.write_bytes(&self.ram);
+    }
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.ram_enabled = r.read_bool()?;
+        self.rom_bank = r.read_u8()?;
+        r.read_bytes(&mut self.ram)
+    }
 }
@@ -1,10 +1,15 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::MBC;
+use crate::GB::savestate::{StateReader, StateWriter};
 
+/// MBC3 e MBC30 (Pokémon Crystal JP). O MBC30 usa o mesmo cabeçalho, mas tem
+/// 8 bits de banco de ROM (até 4MB) e 8 bancos de RAM (64KB); é detectado
+/// pelo tamanho da ROM ou da RAM declarada
 pub struct MBC3 {
     rom: Vec<u8>,
     ram: Vec<u8>,
+    mbc30: bool,
     ram_enabled: bool,
     rom_bank: u8,
     ram_bank: u8,
@@ -22,9 +27,11 @@ pub struct MBC3 {
 impl MBC3 {
     pub fn new(rom: Vec<u8>, ram_size: usize) -> Self {
         let now = Self::now_secs();
+        let mbc30 = rom.len() > 2 * 1024 * 1024 || ram_size > 32 * 1024;
         Self {
             rom,
             ram: vec![0; ram_size],
+            mbc30,
             ram_enabled: false,
             rom_bank: 1,
             ram_bank: 0,
@@ -35,6 +42,11 @@ impl MBC3 {
         }
     }
 
+    /// Último banco de RAM endereçável (0x04-0x07 só existem no MBC30)
+    fn last_ram_bank(&self) -> u8 {
+        if self.mbc30 { 0x07 } else { 0x03 }
+    }
+
     #[inline]
     fn now_secs() -> i64 {
         SystemTime::now()
@@ -131,7 +143,7 @@ impl MBC for MBC3 {
 
             // ROM bank select
             0x2000..=0x3FFF => {
-                let mut bank = value & 0x7F;
+                let mut bank = if self.mbc30 { value } else { value & 0x7F };
                 if bank == 0 {
                     bank = 1;
                 }
@@ -164,8 +176,8 @@ impl MBC for MBC3 {
 
         match self.ram_bank {
             // RAM normal
-            0x00..=0x03 => {
-                let idx = (self.ram_bank as usize) * 0x2000 + ((address - 0xA000) as usize);
+            bank if bank <= self.last_ram_bank() => {
+                let idx = (bank as usize) * 0x2000 + ((address - 0xA000) as usize);
                 self.ram.get(idx).copied().unwrap_or(0xFF)
             }
 
@@ -186,8 +198,8 @@ impl MBC for MBC3 {
 
         match self.ram_bank {
             // RAM normal
-            0x00..=0x03 => {
-                let idx = (self.ram_bank as usize) * 0x2000 + ((address - 0xA000) as usize);
+            bank if bank <= self.last_ram_bank() => {
+                let idx = (bank as usize) * 0x2000 + ((address - 0xA000) as usize);
                 if idx < self.ram.len() {
                     self.ram[idx] = value;
                 }
@@ -286,6 +298,28 @@ impl MBC for MBC3 {
         // Atualiza latch para ficar consistente
         self.rtc_latch.copy_from_slice(&self.rtc);
     }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_bool(self.ram_enabled);
+        w.write_u8(self.rom_bank);
+        w.write_u8(self.ram_bank);
+        w.write_bytes(&self.rtc);
+        w.write_bytes(&self.rtc_latch);
+        w.write_u8(self.rtc_latch_state);
+        w.write_i64(self.rtc_last_update);
+        w.write_vec(&self.ram);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.ram_enabled = r.read_bool()?;
+        self.rom_bank = r.read_u8()?;
+        self.ram_bank = r.read_u8()?;
+        r.read_bytes(&mut self.rtc)?;
+        r.read_bytes(&mut self.rtc_latch)?;
+        self.rtc_latch_state = r.read_u8()?;
+        self.rtc_last_update = r.read_i64()?;
+        r.read_vec_into(&mut self.ram)
+    }
 }
 
 impl MBC3 {
@@ -294,6 +328,7 @@ impl MBC3 {
         MBC3 {
             rom: Vec::new(), // ROM não é usada no save_ram
             ram: self.ram.clone(),
+            mbc30: self.mbc30,
             ram_enabled: self.ram_enabled,
             rom_bank: self.rom_bank,
             ram_bank: self.ram_bank,
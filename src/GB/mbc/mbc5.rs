@@ -1,4 +1,5 @@
 use super::MBC;
+use crate::GB::savestate::{StateReader, StateWriter};
 
 pub struct MBC5 {
     rom: Vec<u8>,
@@ -6,16 +7,28 @@ pub struct MBC5 {
     ram_enabled: bool,
     rom_bank: u16,
     ram_bank: u8,
+
+    // Cartuchos 0x1C-0x1E: bit 3 do registrador de banco de RAM liga o motor
+    rumble: bool,
+    motor_on: bool,
+    // Ciclos com o motor ligado / ciclos totais desde a última leitura do duty
+    motor_cycles: u32,
+    duty_cycles: u32,
 }
 
 impl MBC5 {
     pub fn new(rom: Vec<u8>, ram_size: usize) -> Self {
+        let rumble = matches!(rom.get(0x0147), Some(0x1C..=0x1E));
         Self {
             rom,
             ram: vec![0; ram_size],
             ram_enabled: false,
             rom_bank: 1,
             ram_bank: 0,
+            rumble,
+            motor_on: false,
+            motor_cycles: 0,
+            duty_cycles: 0,
         }
     }
 
@@ -45,6 +58,10 @@ impl MBC for MBC5 {
             0x3000..=0x3FFF => {
                 self.rom_bank = (self.rom_bank & 0xFF) | (((value & 0x01) as u16) << 8)
             }
+            0x4000..=0x5FFF if self.rumble => {
+                self.ram_bank = value & 0x07;
+                self.motor_on = value & 0x08 != 0;
+            }
             0x4000..=0x5FFF => self.ram_bank = value & 0x0F,
             _ => {}
         }
@@ -78,4 +95,44 @@ impl MBC for MBC5 {
         let len = data.len().min(self.ram.len());
         self.ram[..len].copy_from_slice(&data[..len]);
     }
+    fn tick(&mut self, cycles: u32) {
+        if !self.rumble {
+            return;
+        }
+        self.duty_cycles = self.duty_cycles.saturating_add(cycles);
+        if self.motor_on {
+            self.motor_cycles = self.motor_cycles.saturating_add(cycles);
+        }
+    }
+    /// Fração do tempo com o motor ligado desde a última chamada; sem ciclos
+    /// decorridos, vale o estado atual do motor
+    fn take_rumble_duty(&mut self) -> Option<f32> {
+        if !self.rumble {
+            return None;
+        }
+        let duty = if self.duty_cycles == 0 {
+            if self.motor_on { 1.0 } else { 0.0 }
+        } else {
+            self.motor_cycles as f32 / self.duty_cycles as f32
+        };
+        self.motor_cycles = 0;
+        self.duty_cycles = 0;
+        Some(duty)
+    }
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_bool(self.ram_enabled);
+        w.write_u16(self.rom_bank);
+        w.write_u8(self.ram_bank);
+        w.write_bool(self.motor_on);
+        w.write_vec(&self.ram);
+    }
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.ram_enabled = r.read_bool()?;
+        self.rom_bank = r.read_u16()?;
+        self.ram_bank = r.read_u8()?;
+        self.motor_on = r.read_bool()?;
+        self.motor_cycles = 0;
+        self.duty_cycles = 0;
+        r.read_vec_into(&mut self.ram)
+    }
 }
@@ -0,0 +1,264 @@
+use super::MBC;
+use crate::GB::savestate::{StateReader, StateWriter};
+
+const RAM_SIZE: usize = 32 * 1024;
+const FLASH_SIZE: usize = 1024 * 1024;
+/// Setor apagado pelo comando 0x30 (setores uniformes do MX29F008)
+const FLASH_SECTOR_SIZE: usize = 64 * 1024;
+/// Identificação do chip no modo 0x90 (Macronix MX29F008TC)
+const FLASH_MANUFACTURER_ID: u8 = 0xC2;
+const FLASH_DEVICE_ID: u8 = 0x81;
+
+/// Estado do protocolo de comandos da flash (sequências AA/55 nos
+/// endereços 0x5555/0x2AAA do chip)
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FlashMode {
+    Read,
+    Unlock1,
+    Unlock2,
+    Program,
+    EraseSetup,
+    EraseUnlock1,
+    EraseUnlock2,
+}
+
+impl FlashMode {
+    fn to_u8(self) -> u8 {
+        match self {
+            FlashMode::Read => 0,
+            FlashMode::Unlock1 => 1,
+            FlashMode::Unlock2 => 2,
+            FlashMode::Program => 3,
+            FlashMode::EraseSetup => 4,
+            FlashMode::EraseUnlock1 => 5,
+            FlashMode::EraseUnlock2 => 6,
+        }
+    }
+
+    fn from_u8(value: u8) -> Result<Self, String> {
+        Ok(match value {
+            0 => FlashMode::Read,
+            1 => FlashMode::Unlock1,
+            2 => FlashMode::Unlock2,
+            3 => FlashMode::Program,
+            4 => FlashMode::EraseSetup,
+            5 => FlashMode::EraseUnlock1,
+            6 => FlashMode::EraseUnlock2,
+            _ => return Err(format!("Modo de flash inválido: {}", value)),
+        })
+    }
+}
+
+/// MBC6 (Net de Get): duas janelas de 8KB em 4000-5FFF e 6000-7FFF, cada uma
+/// mapeando ROM ou flash de forma independente, e RAM em dois bancos de 4KB
+pub struct MBC6 {
+    rom: Vec<u8>,
+    ram: Vec<u8>,
+    flash: Vec<u8>,
+    ram_enabled: bool,
+    ram_bank_a: u8,
+    ram_bank_b: u8,
+    rom_bank_a: u8,
+    rom_bank_b: u8,
+    flash_a: bool,
+    flash_b: bool,
+    flash_enabled: bool,
+    flash_write_enabled: bool,
+    flash_mode: FlashMode,
+    flash_id_mode: bool,
+}
+
+impl MBC6 {
+    pub fn new(rom: Vec<u8>) -> Self {
+        Self {
+            rom,
+            ram: vec![0; RAM_SIZE],
+            flash: vec![0xFF; FLASH_SIZE],
+            ram_enabled: false,
+            ram_bank_a: 0,
+            ram_bank_b: 0,
+            rom_bank_a: 2,
+            rom_bank_b: 3,
+            flash_a: false,
+            flash_b: false,
+            flash_enabled: false,
+            flash_write_enabled: false,
+            flash_mode: FlashMode::Read,
+            flash_id_mode: false,
+        }
+    }
+
+    /// (flash?, banco de 8KB) mapeado na janela do endereço
+    fn window(&self, address: u16) -> (bool, usize) {
+        if address < 0x6000 {
+            (self.flash_a, self.rom_bank_a as usize)
+        } else {
+            (self.flash_b, self.rom_bank_b as usize)
+        }
+    }
+
+    fn ram_index(&self, address: u16) -> usize {
+        let bank = if address < 0xB000 {
+            self.ram_bank_a
+        } else {
+            self.ram_bank_b
+        };
+        (bank as usize * 0x1000 + (address & 0x0FFF) as usize) % self.ram.len()
+    }
+
+    fn flash_command(&mut self, chip_address: usize, value: u8) {
+        // 0xF0 em qualquer ponto volta para leitura
+        if value == 0xF0 {
+            self.flash_mode = FlashMode::Read;
+            self.flash_id_mode = false;
+            return;
+        }
+        let command_address = chip_address & 0x7FFF;
+        self.flash_mode = match (self.flash_mode, command_address, value) {
+            (FlashMode::Read, 0x5555, 0xAA) => FlashMode::Unlock1,
+            (FlashMode::Unlock1, 0x2AAA, 0x55) => FlashMode::Unlock2,
+            (FlashMode::Unlock2, 0x5555, 0x90) => {
+                self.flash_id_mode = true;
+                FlashMode::Read
+            }
+            (FlashMode::Unlock2, 0x5555, 0xA0) => FlashMode::Program,
+            (FlashMode::Unlock2, 0x5555, 0x80) => FlashMode::EraseSetup,
+            (FlashMode::EraseSetup, 0x5555, 0xAA) => FlashMode::EraseUnlock1,
+            (FlashMode::EraseUnlock1, 0x2AAA, 0x55) => FlashMode::EraseUnlock2,
+            (FlashMode::EraseUnlock2, 0x5555, 0x10) => {
+                if self.flash_write_enabled {
+                    self.flash.fill(0xFF);
+                }
+                FlashMode::Read
+            }
+            (FlashMode::EraseUnlock2, _, 0x30) => {
+                if self.flash_write_enabled {
+                    let start = chip_address / FLASH_SECTOR_SIZE * FLASH_SECTOR_SIZE;
+                    self.flash[start..start + FLASH_SECTOR_SIZE].fill(0xFF);
+                }
+                FlashMode::Read
+            }
+            (FlashMode::Program, _, _) => {
+                // Programar só consegue derrubar bits (1 -> 0)
+                if self.flash_write_enabled {
+                    self.flash[chip_address] &= value;
+                }
+                FlashMode::Read
+            }
+            _ => FlashMode::Read,
+        };
+    }
+}
+
+impl MBC for MBC6 {
+    fn read_rom(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x3FFF => self.rom.get(address as usize).copied().unwrap_or(0xFF),
+            0x4000..=0x7FFF => {
+                let (flash, bank) = self.window(address);
+                let offset = (address & 0x1FFF) as usize;
+                if flash {
+                    if self.flash_id_mode {
+                        return match offset & 0xFF {
+                            0 => FLASH_MANUFACTURER_ID,
+                            1 => FLASH_DEVICE_ID,
+                            _ => 0x00,
+                        };
+                    }
+                    self.flash[(bank * 0x2000 + offset) % FLASH_SIZE]
+                } else {
+                    let banks = (self.rom.len() / 0x2000).max(1);
+                    let idx = (bank % banks) * 0x2000 + offset;
+                    self.rom.get(idx).copied().unwrap_or(0xFF)
+                }
+            }
+            _ => 0xFF,
+        }
+    }
+
+    fn write_register(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x03FF => self.ram_enabled = (value & 0x0F) == 0x0A,
+            0x0400..=0x07FF => self.ram_bank_a = value & 0x07,
+            0x0800..=0x0BFF => self.ram_bank_b = value & 0x07,
+            0x0C00..=0x0FFF => self.flash_enabled = value & 0x01 != 0,
+            0x1000..=0x1FFF => self.flash_write_enabled = value & 0x01 != 0,
+            0x2000..=0x27FF => self.rom_bank_a = value & 0x7F,
+            0x2800..=0x2FFF => self.flash_a = value & 0x08 != 0,
+            0x3000..=0x37FF => self.rom_bank_b = value & 0x7F,
+            0x3800..=0x3FFF => self.flash_b = value & 0x08 != 0,
+            0x4000..=0x7FFF => {
+                // Escritas nas janelas só importam quando mapeiam a flash
+                let (flash, bank) = self.window(address);
+                if flash && self.flash_enabled {
+                    let chip_address = (bank * 0x2000 + (address & 0x1FFF) as usize) % FLASH_SIZE;
+                    self.flash_command(chip_address, value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        if !self.ram_enabled {
+            return 0xFF;
+        }
+        self.ram[self.ram_index(address)]
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if !self.ram_enabled {
+            return;
+        }
+        let idx = self.ram_index(address);
+        self.ram[idx] = value;
+    }
+
+    /// RAM seguida da flash inteira
+    fn save_ram(&self) -> Option<Vec<u8>> {
+        let mut buf = self.ram.clone();
+        buf.extend_from_slice(&self.flash);
+        Some(buf)
+    }
+
+    fn load_ram(&mut self, data: &[u8]) {
+        let len = data.len().min(RAM_SIZE);
+        self.ram[..len].copy_from_slice(&data[..len]);
+        if let Some(flash) = data.get(RAM_SIZE..) {
+            let len = flash.len().min(FLASH_SIZE);
+            self.flash[..len].copy_from_slice(&flash[..len]);
+        }
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_bool(self.ram_enabled);
+        w.write_u8(self.ram_bank_a);
+        w.write_u8(self.ram_bank_b);
+        w.write_u8(self.rom_bank_a);
+        w.write_u8(self.rom_bank_b);
+        w.write_bool(self.flash_a);
+        w.write_bool(self.flash_b);
+        w.write_bool(self.flash_enabled);
+        w.write_bool(self.flash_write_enabled);
+        w.write_u8(self.flash_mode.to_u8());
+        w.write_bool(self.flash_id_mode);
+        w.write_vec(&self.ram);
+        w.write_vec(&self.flash);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.ram_enabled = r.read_bool()?;
+        self.ram_bank_a = r.read_u8()? & 0x07;
+        self.ram_bank_b = r.read_u8()? & 0x07;
+        self.rom_bank_a = r.read_u8()?;
+        self.rom_bank_b = r.read_u8()?;
+        self.flash_a = r.read_bool()?;
+        self.flash_b = r.read_bool()?;
+        self.flash_enabled = r.read_bool()?;
+        self.flash_write_enabled = r.read_bool()?;
+        self.flash_mode = FlashMode::from_u8(r.read_u8()?)?;
+        self.flash_id_mode = r.read_bool()?;
+        r.read_vec_into(&mut self.ram)?;
+        r.read_vec_into(&mut self.flash)
+    }
+}
@@ -0,0 +1,360 @@
+use super::MBC;
+use crate::GB::savestate::{StateReader, StateWriter};
+
+/// Leitura do acelerômetro com o cartucho nivelado
+const ACCEL_CENTER: f32 = 0x81D0 as f32;
+/// Variação da leitura por 1 g de inclinação
+const ACCEL_PER_G: f32 = 0x70 as f32;
+/// Valor dos registradores X/Y depois de apagar o latch
+const ACCEL_ERASED: u16 = 0x8000;
+
+// ========== EEPROM 93LC56 ==========
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EepromMode {
+    /// Esperando o bit de início
+    Idle,
+    /// Recebendo opcode (2 bits) + endereço (8 bits)
+    Command,
+    /// Enviando palavras em DO (leitura sequencial)
+    Read,
+    /// Recebendo os 16 bits de um WRITE
+    Write,
+    /// Recebendo os 16 bits de um WRAL
+    WriteAll,
+    /// Comando concluído: ignora o clock até CS descer
+    Done,
+}
+
+impl EepromMode {
+    fn to_u8(self) -> u8 {
+        match self {
+            EepromMode::Idle => 0,
+            EepromMode::Command => 1,
+            EepromMode::Read => 2,
+            EepromMode::Write => 3,
+            EepromMode::WriteAll => 4,
+            EepromMode::Done => 5,
+        }
+    }
+
+    fn from_u8(value: u8) -> Result<Self, String> {
+        Ok(match value {
+            0 => EepromMode::Idle,
+            1 => EepromMode::Command,
+            2 => EepromMode::Read,
+            3 => EepromMode::Write,
+            4 => EepromMode::WriteAll,
+            5 => EepromMode::Done,
+            _ => return Err(format!("Modo de EEPROM inválido: {}", value)),
+        })
+    }
+}
+
+/// EEPROM serial de 128 palavras de 16 bits, controlada bit a bit pelos
+/// pinos CS/CLK/DI/DO do registrador Ax8x
+struct Eeprom {
+    data: Vec<u8>,
+    cs: bool,
+    clk: bool,
+    di: bool,
+    do_: bool,
+    mode: EepromMode,
+    shift: u16,
+    bits: u8,
+    address: u8,
+    write_enabled: bool,
+}
+
+impl Eeprom {
+    fn new() -> Self {
+        Self {
+            data: vec![0xFF; 256],
+            cs: false,
+            clk: false,
+            di: false,
+            do_: true,
+            mode: EepromMode::Idle,
+            shift: 0,
+            bits: 0,
+            address: 0,
+            write_enabled: false,
+        }
+    }
+
+    fn word(&self, address: u8) -> u16 {
+        let idx = (address & 0x7F) as usize * 2;
+        u16::from_le_bytes([self.data[idx], self.data[idx + 1]])
+    }
+
+    fn set_word(&mut self, address: u8, value: u16) {
+        if !self.write_enabled {
+            return;
+        }
+        let idx = (address & 0x7F) as usize * 2;
+        self.data[idx..idx + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn read(&self) -> u8 {
+        ((self.cs as u8) << 7) | ((self.clk as u8) << 6) | ((self.di as u8) << 1) | self.do_ as u8
+    }
+
+    fn write(&mut self, value: u8) {
+        let cs = value & 0x80 != 0;
+        let clk = value & 0x40 != 0;
+        self.di = value & 0x02 != 0;
+        if !cs {
+            // CS baixo aborta o comando em andamento; DO volta para "pronto"
+            self.mode = EepromMode::Idle;
+            self.do_ = true;
+        } else if self.cs && clk && !self.clk {
+            self.clock(self.di);
+        }
+        self.cs = cs;
+        self.clk = clk;
+    }
+
+    /// Borda de subida do CLK com CS alto
+    fn clock(&mut self, bit: bool) {
+        match self.mode {
+            EepromMode::Idle => {
+                if bit {
+                    self.mode = EepromMode::Command;
+                    self.shift = 0;
+                    self.bits = 0;
+                }
+            }
+            EepromMode::Command => {
+                self.shift = (self.shift << 1) | bit as u16;
+                self.bits += 1;
+                if self.bits == 10 {
+                    self.execute();
+                }
+            }
+            EepromMode::Read => {
+                self.do_ = self.shift & 0x8000 != 0;
+                self.shift <<= 1;
+                self.bits -= 1;
+                if self.bits == 0 {
+                    self.address = (self.address + 1) & 0x7F;
+                    self.shift = self.word(self.address);
+                    self.bits = 16;
+                }
+            }
+            EepromMode::Write | EepromMode::WriteAll => {
+                self.shift = (self.shift << 1) | bit as u16;
+                self.bits += 1;
+                if self.bits == 16 {
+                    if self.mode == EepromMode::Write {
+                        self.set_word(self.address, self.shift);
+                    } else {
+                        for address in 0..128 {
+                            self.set_word(address, self.shift);
+                        }
+                    }
+                    self.mode = EepromMode::Done;
+                    self.do_ = true;
+                }
+            }
+            EepromMode::Done => {}
+        }
+    }
+
+    fn execute(&mut self) {
+        self.address = (self.shift & 0x7F) as u8;
+        self.mode = EepromMode::Done;
+        match (self.shift >> 8) & 0x03 {
+            // READ: um bit 0 de preâmbulo e depois a palavra, MSB primeiro
+            0b10 => {
+                self.mode = EepromMode::Read;
+                self.shift = self.word(self.address);
+                self.bits = 16;
+                self.do_ = false;
+            }
+            0b01 => {
+                self.mode = EepromMode::Write;
+                self.shift = 0;
+                self.bits = 0;
+            }
+            // ERASE
+            0b11 => self.set_word(self.address, 0xFFFF),
+            // Comandos estendidos: os 2 bits altos do endereço escolhem
+            _ => match (self.shift >> 6) & 0x03 {
+                0b11 => self.write_enabled = true,
+                0b00 => self.write_enabled = false,
+                0b10 => {
+                    if self.write_enabled {
+                        self.data.fill(0xFF);
+                    }
+                }
+                _ => {
+                    self.mode = EepromMode::WriteAll;
+                    self.shift = 0;
+                    self.bits = 0;
+                }
+            },
+        }
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_bytes(&self.data);
+        w.write_bool(self.cs);
+        w.write_bool(self.clk);
+        w.write_bool(self.di);
+        w.write_bool(self.do_);
+        w.write_u8(self.mode.to_u8());
+        w.write_u16(self.shift);
+        w.write_u8(self.bits);
+        w.write_u8(self.address);
+        w.write_bool(self.write_enabled);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        r.read_bytes(&mut self.data)?;
+        self.cs = r.read_bool()?;
+        self.clk = r.read_bool()?;
+        self.di = r.read_bool()?;
+        self.do_ = r.read_bool()?;
+        self.mode = EepromMode::from_u8(r.read_u8()?)?;
+        self.shift = r.read_u16()?;
+        self.bits = r.read_u8()?;
+        self.address = r.read_u8()? & 0x7F;
+        self.write_enabled = r.read_bool()?;
+        Ok(())
+    }
+}
+
+// ========== MBC7 ==========
+
+pub struct MBC7 {
+    rom: Vec<u8>,
+    rom_bank: u8,
+    ram_enable1: bool,
+    ram_enable2: bool,
+    /// Inclinação atual do host em g (x > 0: direita, y > 0: para baixo)
+    tilt_x: f32,
+    tilt_y: f32,
+    accel_x: u16,
+    accel_y: u16,
+    latch_ready: bool,
+    eeprom: Eeprom,
+}
+
+impl MBC7 {
+    pub fn new(rom: Vec<u8>) -> Self {
+        Self {
+            rom,
+            rom_bank: 1,
+            ram_enable1: false,
+            ram_enable2: false,
+            tilt_x: 0.0,
+            tilt_y: 0.0,
+            accel_x: ACCEL_ERASED,
+            accel_y: ACCEL_ERASED,
+            latch_ready: false,
+            eeprom: Eeprom::new(),
+        }
+    }
+
+    fn rom_bank_count(&self) -> usize {
+        (self.rom.len() / 0x4000).max(1)
+    }
+
+    fn accel_value(tilt: f32) -> u16 {
+        (ACCEL_CENTER - ACCEL_PER_G * tilt.clamp(-2.0, 2.0)) as u16
+    }
+}
+
+impl MBC for MBC7 {
+    fn read_rom(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x3FFF => self.rom.get(address as usize).copied().unwrap_or(0xFF),
+            0x4000..=0x7FFF => {
+                let bank = self.rom_bank as usize % self.rom_bank_count();
+                let idx = bank * 0x4000 + ((address - 0x4000) as usize);
+                self.rom.get(idx).copied().unwrap_or(0xFF)
+            }
+            _ => 0xFF,
+        }
+    }
+
+    fn write_register(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1FFF => self.ram_enable1 = (value & 0x0F) == 0x0A,
+            0x2000..=0x3FFF => self.rom_bank = value,
+            0x4000..=0x5FFF => self.ram_enable2 = value == 0x40,
+            _ => {}
+        }
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        // Os registradores só respondem com os dois enables e em A000-AFFF
+        if !self.ram_enable1 || !self.ram_enable2 || address >= 0xB000 {
+            return 0xFF;
+        }
+        match (address >> 4) & 0x0F {
+            0x2 => self.accel_x as u8,
+            0x3 => (self.accel_x >> 8) as u8,
+            0x4 => self.accel_y as u8,
+            0x5 => (self.accel_y >> 8) as u8,
+            0x6 => 0x00,
+            0x8 => self.eeprom.read(),
+            _ => 0xFF,
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if !self.ram_enable1 || !self.ram_enable2 || address >= 0xB000 {
+            return;
+        }
+        match (address >> 4) & 0x0F {
+            // 0x55 em Ax0x apaga o latch; 0xAA em Ax1x captura a inclinação
+            0x0 if value == 0x55 => {
+                self.accel_x = ACCEL_ERASED;
+                self.accel_y = ACCEL_ERASED;
+                self.latch_ready = true;
+            }
+            0x1 if value == 0xAA && self.latch_ready => {
+                self.accel_x = Self::accel_value(self.tilt_x);
+                self.accel_y = Self::accel_value(self.tilt_y);
+                self.latch_ready = false;
+            }
+            0x8 => self.eeprom.write(value),
+            _ => {}
+        }
+    }
+
+    fn save_ram(&self) -> Option<Vec<u8>> {
+        Some(self.eeprom.data.clone())
+    }
+
+    fn load_ram(&mut self, data: &[u8]) {
+        let len = data.len().min(self.eeprom.data.len());
+        self.eeprom.data[..len].copy_from_slice(&data[..len]);
+    }
+
+    fn set_tilt(&mut self, x: f32, y: f32) {
+        self.tilt_x = x;
+        self.tilt_y = y;
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.rom_bank);
+        w.write_bool(self.ram_enable1);
+        w.write_bool(self.ram_enable2);
+        w.write_u16(self.accel_x);
+        w.write_u16(self.accel_y);
+        w.write_bool(self.latch_ready);
+        self.eeprom.save_state(w);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.rom_bank = r.read_u8()?;
+        self.ram_enable1 = r.read_bool()?;
+        self.ram_enable2 = r.read_bool()?;
+        self.accel_x = r.read_u16()?;
+        self.accel_y = r.read_u16()?;
+        self.latch_ready = r.read_bool()?;
+        self.eeprom.load_state(r)
+    }
+}
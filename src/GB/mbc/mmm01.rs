@@ -0,0 +1,223 @@
+use super::MBC;
+use crate::GB::cartridge;
+use crate::GB::savestate::{StateReader, StateWriter};
+
+/// Cartucho multijogo MMM01: liga mostrando o menu (últimos 32KB da ROM).
+/// O menu programa o banco base e as máscaras e, ao escrever o bit 6 em
+/// 0000-1FFF, trava o mapeamento; daí em diante o jogo escolhido enxerga
+/// um MBC1 comum dentro da sua fatia da ROM.
+pub struct MMM01 {
+    rom: Vec<u8>,
+    menu_bank: usize, // primeiro banco do menu (32KB)
+    ram: Vec<u8>,
+    ram_enabled: bool,
+    locked: bool,
+
+    rom_bank_low: u8,  // bits 0-4 do banco de ROM
+    rom_bank_mid: u8,  // bits 5-6 (só antes de travar)
+    rom_bank_high: u8, // bits 7-8 (só antes de travar)
+    ram_bank_low: u8,
+    ram_bank_high: u8, // só antes de travar
+
+    // Bits setados nas máscaras ficam fixos (fazem parte do banco base)
+    rom_bank_mask: u8, // protege os bits 1-4 de rom_bank_low
+    ram_bank_mask: u8, // protege os bits 0-1 de ram_bank_low
+
+    mbc1_mode: bool,
+    mbc1_mode_locked: bool,
+    // Troca os papéis de 4000-5FFF e dos bits 5-6 de 2000-3FFF
+    multiplex: bool,
+}
+
+impl MMM01 {
+    /// `menu`: offset do cabeçalho do menu na ROM (ver `detect`)
+    pub fn new(rom: Vec<u8>, ram_size: usize, menu: usize) -> Self {
+        Self {
+            rom,
+            menu_bank: menu / 0x4000,
+            ram: vec![0; ram_size],
+            ram_enabled: false,
+            locked: false,
+            rom_bank_low: 0,
+            rom_bank_mid: 0,
+            rom_bank_high: 0,
+            ram_bank_low: 0,
+            ram_bank_high: 0,
+            rom_bank_mask: 0,
+            ram_bank_mask: 0,
+            mbc1_mode: false,
+            mbc1_mode_locked: false,
+            multiplex: false,
+        }
+    }
+
+    /// Offset do cabeçalho MMM01: no banco 0 ou no menu, nos últimos 32KB
+    /// (como a maioria dos dumps guarda esses cartuchos; aí o banco 0 tem o
+    /// header do primeiro jogo). O menu no fim precisa de logo e checksum de
+    /// header válidos: um byte solto no fim de uma ROM MBC1/3/5 não basta
+    pub fn detect(rom: &[u8]) -> Option<usize> {
+        let is_mmm01 = |t: u8| (0x0B..=0x0D).contains(&t);
+        if is_mmm01(*rom.get(0x0147)?) {
+            return Some(0);
+        }
+        let menu = rom.len().checked_sub(0x8000).filter(|&m| m > 0)?;
+        let header = &rom[menu..];
+        (is_mmm01(header[0x0147]) && cartridge::validate_header(header).is_ok()).then_some(menu)
+    }
+
+    fn rom_bank_count(&self) -> usize {
+        (self.rom.len() / 0x4000).max(1)
+    }
+
+    fn ram_bank_count(&self) -> usize {
+        (self.ram.len() / 0x2000).max(1)
+    }
+
+    /// Bancos mapeados em (0000-3FFF, 4000-7FFF)
+    fn rom_banks(&self) -> (usize, usize) {
+        if !self.locked {
+            // Menu: os 32KB com o cabeçalho MMM01
+            let count = self.rom_bank_count();
+            return (self.menu_bank % count, (self.menu_bank + 1) % count);
+        }
+        let low = self.rom_bank_low as usize;
+        let fixed_low = low & ((self.rom_bank_mask as usize) << 1);
+        let high = (self.rom_bank_high as usize) << 7;
+        let (bank0_mid, mid) = if self.multiplex {
+            let mid = self.ram_bank_low as usize;
+            (if self.mbc1_mode { mid } else { 0 }, mid)
+        } else {
+            (self.rom_bank_mid as usize, self.rom_bank_mid as usize)
+        };
+        let bank0 = fixed_low | (bank0_mid << 5) | high;
+        let mut bank = low | (mid << 5) | high;
+        // Igual ao MBC1: o banco base nunca aparece em 4000-7FFF
+        if bank == bank0 {
+            bank += 1;
+        }
+        (bank0 % self.rom_bank_count(), bank % self.rom_bank_count())
+    }
+
+    fn ram_bank(&self) -> usize {
+        let bank = if self.multiplex {
+            self.rom_bank_mid | (self.ram_bank_high << 2)
+        } else {
+            let low = if self.mbc1_mode { self.ram_bank_low } else { 0 };
+            low | (self.ram_bank_high << 2)
+        };
+        bank as usize % self.ram_bank_count()
+    }
+}
+
+impl MBC for MMM01 {
+    fn read_rom(&self, address: u16) -> u8 {
+        let (bank0, bank) = self.rom_banks();
+        let idx = match address {
+            0x0000..=0x3FFF => bank0 * 0x4000 + address as usize,
+            0x4000..=0x7FFF => bank * 0x4000 + (address - 0x4000) as usize,
+            _ => return 0xFF,
+        };
+        self.rom.get(idx).copied().unwrap_or(0xFF)
+    }
+
+    fn write_register(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1FFF => {
+                self.ram_enabled = (value & 0x0F) == 0x0A;
+                if !self.locked {
+                    self.ram_bank_mask = (value >> 4) & 0x03;
+                    self.locked = value & 0x40 != 0;
+                }
+            }
+            0x2000..=0x3FFF => {
+                if !self.locked {
+                    self.rom_bank_mid = (value >> 5) & 0x03;
+                }
+                let protect = self.rom_bank_mask << 1;
+                self.rom_bank_low = (self.rom_bank_low & protect) | (value & 0x1F & !protect);
+            }
+            0x4000..=0x5FFF => {
+                let protect = self.ram_bank_mask;
+                self.ram_bank_low = (self.ram_bank_low & protect) | (value & 0x03 & !protect);
+                if !self.locked {
+                    self.ram_bank_high = (value >> 2) & 0x03;
+                    self.rom_bank_high = (value >> 4) & 0x03;
+                    self.mbc1_mode_locked = value & 0x40 != 0;
+                }
+            }
+            0x6000..=0x7FFF => {
+                if !self.mbc1_mode_locked {
+                    self.mbc1_mode = value & 0x01 != 0;
+                }
+                if !self.locked {
+                    self.rom_bank_mask = (value >> 2) & 0x0F;
+                    self.multiplex = value & 0x40 != 0;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        if !self.ram_enabled {
+            return 0xFF;
+        }
+        let idx = self.ram_bank() * 0x2000 + (address - 0xA000) as usize;
+        self.ram.get(idx).copied().unwrap_or(0xFF)
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if !self.ram_enabled {
+            return;
+        }
+        let idx = self.ram_bank() * 0x2000 + (address - 0xA000) as usize;
+        if idx < self.ram.len() {
+            self.ram[idx] = value;
+        }
+    }
+
+    fn save_ram(&self) -> Option<Vec<u8>> {
+        if self.ram.is_empty() {
+            None
+        } else {
+            Some(self.ram.clone())
+        }
+    }
+
+    fn load_ram(&mut self, data: &[u8]) {
+        let len = data.len().min(self.ram.len());
+        self.ram[..len].copy_from_slice(&data[..len]);
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_bool(self.ram_enabled);
+        w.write_bool(self.locked);
+        w.write_u8(self.rom_bank_low);
+        w.write_u8(self.rom_bank_mid);
+        w.write_u8(self.rom_bank_high);
+        w.write_u8(self.ram_bank_low);
+        w.write_u8(self.ram_bank_high);
+        w.write_u8(self.rom_bank_mask);
+        w.write_u8(self.ram_bank_mask);
+        w.write_bool(self.mbc1_mode);
+        w.write_bool(self.mbc1_mode_locked);
+        w.write_bool(self.multiplex);
+        w.write_vec(&self.ram);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.ram_enabled = r.read_bool()?;
+        self.locked = r.read_bool()?;
+        self.rom_bank_low = r.read_u8()?;
+        self.rom_bank_mid = r.read_u8()?;
+        self.rom_bank_high = r.read_u8()?;
+        self.ram_bank_low = r.read_u8()?;
+        self.ram_bank_high = r.read_u8()?;
+        self.rom_bank_mask = r.read_u8()?;
+        self.ram_bank_mask = r.read_u8()?;
+        self.mbc1_mode = r.read_bool()?;
+        self.mbc1_mode_locked = r.read_bool()?;
+        self.multiplex = r.read_bool()?;
+        r.read_vec_into(&mut self.ram)
+    }
+}
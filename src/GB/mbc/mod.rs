@@ -1,22 +1,155 @@
+pub mod bbd;
+pub mod camera;
+pub mod huc1;
+pub mod huc3;
+pub mod m161;
 pub mod mbc1;
 pub mod mbc2;
 pub mod mbc3;
 pub mod mbc5;
+pub mod mbc6;
+pub mod mbc7;
+pub mod mmm01;
 pub mod none;
+pub mod sachen;
+pub mod tama5;
+pub mod wisdom_tree;
 
+use crate::GB::cartdb::{CartInfo, MapperOverride};
+use crate::GB::infrared::IrPeer;
+use crate::GB::savestate::{StateReader, StateWriter};
+use camera::CameraSource;
+
+/// Mapper pelo header da ROM (sem banco de cartuchos)
 pub fn create_mbc(rom: Vec<u8>) -> Box<dyn MBC + Send> {
-    let cart_type = rom.get(0x0147).copied().unwrap_or(0x00);
-    let ram_size = get_ram_size_for_type(&rom, cart_type);
+    create_mbc_for(rom, None)
+}
+
+/// Como `create_mbc`, aplicando as correções de uma entrada do banco de
+/// cartuchos (mapper e tamanho de RAM) no lugar do que o header declara
+pub fn create_mbc_for(rom: Vec<u8>, info: Option<&CartInfo>) -> Box<dyn MBC + Send> {
+    let ram_override = info.and_then(|i| i.ram_size);
+    let cart_type = match info.and_then(|i| i.mapper) {
+        Some(MapperOverride::Unlicensed(mapper)) => {
+            let ram_size = ram_override.unwrap_or_else(|| header_ram_size(&rom));
+            return mapper.build(rom, ram_size);
+        }
+        Some(MapperOverride::CartType(cart_type)) => cart_type,
+        None => {
+            // MMM01: vale o cabeçalho do menu, que costuma ficar no fim da ROM
+            if let Some(header) = mmm01::MMM01::detect(&rom) {
+                let cart_type = rom[header + 0x0147];
+                let ram_size = ram_override
+                    .unwrap_or_else(|| get_ram_size_for_type(&rom[header..], cart_type));
+                return Box::new(mmm01::MMM01::new(rom, ram_size, header));
+            }
+
+            // Sem licença: o cabeçalho mente, então vêm antes do tipo declarado
+            if let Some(mapper) = Unlicensed::detect(&rom) {
+                return mapper.create(rom);
+            }
+            rom.get(0x0147).copied().unwrap_or(0x00)
+        }
+    };
+
+    let ram_size = ram_override.unwrap_or_else(|| get_ram_size_for_type(&rom, cart_type));
     match cart_type {
         0x00 => Box::new(none::NoMBC::new(rom)),
         0x01..=0x03 => Box::new(mbc1::MBC1::new(rom, ram_size)),
         0x05..=0x06 => Box::new(mbc2::MBC2::new(rom)),
+        0x0B..=0x0D => {
+            // Tipo forçado pelo banco de cartuchos: menu nos últimos 32KB
+            let menu = rom.len().saturating_sub(0x8000);
+            Box::new(mmm01::MMM01::new(rom, ram_size, menu))
+        }
         0x0F..=0x13 => Box::new(mbc3::MBC3::new(rom, ram_size)),
         0x19..=0x1E => Box::new(mbc5::MBC5::new(rom, ram_size)),
+        0x20 => Box::new(mbc6::MBC6::new(rom)),
+        0x22 => Box::new(mbc7::MBC7::new(rom)),
+        0xFC => Box::new(camera::Camera::new(rom)),
+        0xFD => Box::new(tama5::TAMA5::new(rom)),
+        0xFE => Box::new(huc3::HuC3::new(rom, ram_size)),
+        0xFF => Box::new(huc1::HuC1::new(rom, ram_size)),
         _ => Box::new(none::NoMBC::new(rom)),
     }
 }
 
+/// Mappers sem licença e bootlegs, que o byte de tipo do cabeçalho não
+/// identifica. Detectados por heurística em `create_mbc` ou escolhidos com
+/// `--mapper`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unlicensed {
+    WisdomTree,
+    M161,
+    SachenMmc1,
+    SachenMmc2,
+    Bbd,
+    Hitek,
+}
+
+impl Unlicensed {
+    pub const ALL: [Unlicensed; 6] = [
+        Unlicensed::WisdomTree,
+        Unlicensed::M161,
+        Unlicensed::SachenMmc1,
+        Unlicensed::SachenMmc2,
+        Unlicensed::Bbd,
+        Unlicensed::Hitek,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Unlicensed> {
+        Self::ALL
+            .into_iter()
+            .find(|m| m.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Unlicensed::WisdomTree => "wisdom-tree",
+            Unlicensed::M161 => "m161",
+            Unlicensed::SachenMmc1 => "sachen-mmc1",
+            Unlicensed::SachenMmc2 => "sachen-mmc2",
+            Unlicensed::Bbd => "bbd",
+            Unlicensed::Hitek => "hitek",
+        }
+    }
+
+    pub fn detect(rom: &[u8]) -> Option<Unlicensed> {
+        if wisdom_tree::WisdomTree::detect(rom) {
+            return Some(Unlicensed::WisdomTree);
+        }
+        if m161::M161::detect(rom) {
+            return Some(Unlicensed::M161);
+        }
+        if let Some(variant) = bbd::Bbd::detect(rom) {
+            return Some(match variant {
+                bbd::BbdVariant::Bbd => Unlicensed::Bbd,
+                bbd::BbdVariant::Hitek => Unlicensed::Hitek,
+            });
+        }
+        sachen::Sachen::detect(rom).map(|model| match model {
+            sachen::SachenModel::Mmc1 => Unlicensed::SachenMmc1,
+            sachen::SachenModel::Mmc2 => Unlicensed::SachenMmc2,
+        })
+    }
+
+    pub fn create(self, rom: Vec<u8>) -> Box<dyn MBC + Send> {
+        let ram_size = header_ram_size(&rom);
+        self.build(rom, ram_size)
+    }
+
+    fn build(self, rom: Vec<u8>, ram_size: usize) -> Box<dyn MBC + Send> {
+        match self {
+            Unlicensed::WisdomTree => Box::new(wisdom_tree::WisdomTree::new(rom)),
+            Unlicensed::M161 => Box::new(m161::M161::new(rom)),
+            Unlicensed::SachenMmc1 => Box::new(sachen::Sachen::new(rom, sachen::SachenModel::Mmc1)),
+            Unlicensed::SachenMmc2 => Box::new(sachen::Sachen::new(rom, sachen::SachenModel::Mmc2)),
+            Unlicensed::Bbd => Box::new(bbd::Bbd::new(rom, ram_size, bbd::BbdVariant::Bbd)),
+            Unlicensed::Hitek => Box::new(bbd::Bbd::new(rom, ram_size, bbd::BbdVariant::Hitek)),
+        }
+    }
+}
+
 fn cart_type_has_ram(cart_type: u8) -> bool {
     matches!(
         cart_type,
@@ -33,10 +166,15 @@ fn cart_type_has_ram(cart_type: u8) -> bool {
             | 0x1D
             | 0x1E
             | 0x22
+            | 0xFE
             | 0xFF
     )
 }
 
+fn header_ram_size(rom: &[u8]) -> usize {
+    get_ram_size_for_type(rom, rom.get(0x0147).copied().unwrap_or(0x00))
+}
+
 fn get_ram_size_for_type(rom: &[u8], cart_type: u8) -> usize {
     let size = match rom.get(0x0149).copied().unwrap_or(0x00) {
         0x00 => 0,
@@ -73,4 +211,31 @@ pub trait MBC: Send {
 
     /// Carrega RAM de arquivo
     fn load_ram(&mut self, data: &[u8]);
+
+    /// Inclinação do host em g para cartuchos com acelerômetro (MBC7)
+    fn set_tilt(&mut self, _x: f32, _y: f32) {}
+
+    /// Conecta o LED/sensor infravermelho do cartucho (HuC1) a outro emulador
+    fn set_ir_peer(&mut self, _peer: Option<Box<dyn IrPeer>>) {}
+
+    /// Início direto no estado pós-boot, sem boot ROM (destrava a Sachen)
+    fn skip_boot(&mut self) {}
+
+    /// Avança o hardware do cartucho que depende do clock (captura da câmera)
+    fn tick(&mut self, _cycles: u32) {}
+
+    /// Define a imagem vista pelo sensor da Game Boy Camera
+    fn set_camera_source(&mut self, _source: Option<CameraSource>) {}
+
+    /// Duty do motor de vibração (0.0-1.0) desde a última leitura; `None` se
+    /// o cartucho não tem motor. O frontend chama uma vez por frame
+    fn take_rumble_duty(&mut self) -> Option<f32> {
+        None
+    }
+
+    /// Serializa registradores e RAM do mapper (a ROM não entra no save state)
+    fn save_state(&self, w: &mut StateWriter);
+
+    /// Restaura registradores e RAM do mapper a partir de um save state
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String>;
 }
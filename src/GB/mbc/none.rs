@@ -1,4 +1,5 @@
 use super::MBC;
+use crate::GB::savestate::{StateReader, StateWriter};
 
 pub struct NoMBC {
     rom: Vec<u8>,
@@ -23,4 +24,8 @@ impl MBC for NoMBC {
         None
     }
     fn load_ram(&mut self, _data: &[u8]) {}
+    fn save_state(&self, _w: &mut StateWriter) {}
+    fn load_state(&mut self, _r: &mut StateReader) -> Result<(), String> {
+        Ok(())
+    }
 }
@@ -0,0 +1,202 @@
+use std::cell::Cell;
+
+use super::MBC;
+use crate::GB::savestate::{StateReader, StateWriter};
+
+/// Revisão do mapper: MMC2 acrescenta uma segunda trava para a boot ROM CGB
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SachenModel {
+    Mmc1,
+    Mmc2,
+}
+
+/// Estado da trava do logo
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Lock {
+    Dmg,
+    Cgb,
+    Unlocked,
+}
+
+impl Lock {
+    fn to_u8(self) -> u8 {
+        match self {
+            Lock::Dmg => 0,
+            Lock::Cgb => 1,
+            Lock::Unlocked => 2,
+        }
+    }
+
+    fn from_u8(value: u8) -> Result<Self, String> {
+        Ok(match value {
+            0 => Lock::Dmg,
+            1 => Lock::Cgb,
+            2 => Lock::Unlocked,
+            _ => return Err(format!("Trava Sachen inválida: {}", value)),
+        })
+    }
+}
+
+/// Leituras da página 0x01xx até a trava abrir (logo lido pela boot ROM)
+const LOGO_READS: u8 = 0x30;
+
+/// A página 0x0100-0x01FF da ROM tem as linhas A0/A1/A4/A6 trocadas
+pub fn unscramble(address: u16) -> u16 {
+    (address & 0xFFAC)
+        | ((address & 0x40) >> 6)
+        | ((address & 0x10) >> 3)
+        | ((address & 0x02) << 3)
+        | ((address & 0x01) << 6)
+}
+
+/// Sachen MMC1/MMC2. O header fica embaralhado e, enquanto travado, as
+/// leituras da página 0x01xx saem com A7 setado: a boot ROM desenha o logo
+/// da Sachen e, depois de 0x30 leituras, a trava abre e a comparação
+/// enxerga o logo da Nintendo. Fora isso é um mapper de banco base + máscara
+/// (o MMC2 também exige a segunda passada da boot ROM CGB; a saída antecipada
+/// por leitura de WRAM não é vista pelo mapper)
+pub struct Sachen {
+    rom: Vec<u8>,
+    model: SachenModel,
+    base_bank: u8,
+    mask: u8,
+    unmasked_bank: u8,
+    // Leituras mudam a trava, e read_rom recebe &self
+    lock: Cell<Lock>,
+    transition: Cell<u8>,
+}
+
+impl Sachen {
+    pub fn new(rom: Vec<u8>, model: SachenModel) -> Self {
+        Self {
+            rom,
+            model,
+            base_bank: 0,
+            mask: 0,
+            unmasked_bank: 1,
+            lock: Cell::new(Lock::Dmg),
+            transition: Cell::new(0),
+        }
+    }
+
+    /// Logo da Nintendo embaralhado na página 0x01xx (MMC1) ou na sua metade
+    /// alta, só visível com a trava (MMC2)
+    pub fn detect(rom: &[u8]) -> Option<SachenModel> {
+        let logo_at = |start: usize| {
+            rom.get(start) == Some(&0xCE)
+                && rom.get(start + 0x40) == Some(&0xED)
+                && rom.get(start + 0x10) == Some(&0x66)
+        };
+        if logo_at(0x0104) {
+            Some(SachenModel::Mmc1)
+        } else if logo_at(0x0184) {
+            Some(SachenModel::Mmc2)
+        } else {
+            None
+        }
+    }
+
+    fn rom_bank_count(&self) -> usize {
+        (self.rom.len() / 0x4000).max(1)
+    }
+
+    /// (banco em 0000-3FFF, banco em 4000-7FFF)
+    fn rom_banks(&self) -> (usize, usize) {
+        let bank0 = self.base_bank & self.mask;
+        let bank = (self.unmasked_bank & !self.mask) | bank0;
+        let count = self.rom_bank_count();
+        (bank0 as usize % count, bank as usize % count)
+    }
+
+    /// Os registradores de base e máscara só aceitam escrita com os bits 4-5
+    /// do banco selecionado em 1
+    fn config_unlocked(&self) -> bool {
+        self.unmasked_bank & 0x30 == 0x30
+    }
+
+    /// Conta as leituras da boot ROM na página do logo e aplica a trava
+    fn locked_address(&self, mut address: u16) -> u16 {
+        let page_mask = match self.model {
+            SachenModel::Mmc1 => 0xFF00,
+            SachenModel::Mmc2 => 0x8700,
+        };
+        let lock = self.lock.get();
+        if lock != Lock::Unlocked && address & page_mask == 0x0100 {
+            let transition = self.transition.get() + 1;
+            if transition > LOGO_READS {
+                let next = match (self.model, lock) {
+                    (SachenModel::Mmc2, Lock::Dmg) => Lock::Cgb,
+                    _ => Lock::Unlocked,
+                };
+                self.lock.set(next);
+                self.transition.set(0);
+            } else {
+                self.transition.set(transition);
+                address |= 0x80;
+            }
+        }
+        if address & 0xFF00 == 0x0100 {
+            if self.model == SachenModel::Mmc2 && self.lock.get() == Lock::Cgb {
+                address |= 0x80;
+            }
+            address = unscramble(address);
+        }
+        address
+    }
+}
+
+impl MBC for Sachen {
+    fn read_rom(&self, address: u16) -> u8 {
+        let address = self.locked_address(address);
+        let (bank0, bank) = self.rom_banks();
+        let idx = match address {
+            0x0000..=0x3FFF => bank0 * 0x4000 + address as usize,
+            0x4000..=0x7FFF => bank * 0x4000 + (address - 0x4000) as usize,
+            _ => return 0xFF,
+        };
+        self.rom.get(idx).copied().unwrap_or(0xFF)
+    }
+
+    fn write_register(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1FFF if self.config_unlocked() => self.base_bank = value,
+            0x2000..=0x3FFF => self.unmasked_bank = value.max(1),
+            0x4000..=0x5FFF if self.config_unlocked() => self.mask = value,
+            _ => {}
+        }
+    }
+
+    fn read_ram(&self, _address: u16) -> u8 {
+        0xFF
+    }
+
+    fn write_ram(&mut self, _address: u16, _value: u8) {}
+
+    fn save_ram(&self) -> Option<Vec<u8>> {
+        None
+    }
+
+    fn load_ram(&mut self, _data: &[u8]) {}
+
+    /// Sem boot ROM ninguém lê o logo: o jogo já começa destravado
+    fn skip_boot(&mut self) {
+        self.lock.set(Lock::Unlocked);
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.base_bank);
+        w.write_u8(self.mask);
+        w.write_u8(self.unmasked_bank);
+        w.write_u8(self.lock.get().to_u8());
+        w.write_u8(self.transition.get());
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.base_bank = r.read_u8()?;
+        self.mask = r.read_u8()?;
+        self.unmasked_bank = r.read_u8()?;
+        self.lock.set(Lock::from_u8(r.read_u8()?)?);
+        self.transition.set(r.read_u8()?);
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::MBC;
+use crate::GB::savestate::{StateReader, StateWriter};
+
+const EEPROM_SIZE: usize = 32;
+
+// Registradores de 4 bits (índice escrito em A001, valor em A000)
+const REG_BANK_LO: u8 = 0x0;
+const REG_BANK_HI: u8 = 0x1;
+const REG_WRITE_LO: u8 = 0x4;
+const REG_WRITE_HI: u8 = 0x5;
+const REG_COMMAND: u8 = 0x6;
+const REG_ADDR_LO: u8 = 0x7;
+const REG_READ_LO: u8 = 0xC;
+const REG_READ_HI: u8 = 0xD;
+
+// Comandos (bits 1-3 do registrador 6), executados ao escrever REG_ADDR_LO
+const CMD_EEPROM_WRITE: u8 = 0;
+const CMD_EEPROM_READ: u8 = 1;
+const CMD_RTC_WRITE: u8 = 2;
+const CMD_RTC_READ: u8 = 3;
+
+// Campos do relógio: [segundo, minuto, hora, dia da semana, dia, mês, ano]
+const SECOND: usize = 0;
+const MINUTE: usize = 1;
+const HOUR: usize = 2;
+const WEEKDAY: usize = 3;
+const DAY: usize = 4;
+const MONTH: usize = 5;
+const YEAR: usize = 6;
+
+/// Registradores do RTC (TC8521) em BCD: (campo, dezena?)
+const RTC_NIBBLES: [(usize, bool); 13] = [
+    (SECOND, false),
+    (SECOND, true),
+    (MINUTE, false),
+    (MINUTE, true),
+    (HOUR, false),
+    (HOUR, true),
+    (WEEKDAY, false),
+    (DAY, false),
+    (DAY, true),
+    (MONTH, false),
+    (MONTH, true),
+    (YEAR, false),
+    (YEAR, true),
+];
+
+/// TAMA5 (Tamagotchi): tudo passa por A000/A001 em nibbles. O índice do
+/// registrador vai em A001 e o valor em A000; escrever o endereço executa o
+/// comando de EEPROM (32 bytes) ou RTC selecionado
+pub struct TAMA5 {
+    rom: Vec<u8>,
+    eeprom: [u8; EEPROM_SIZE],
+    registers: [u8; 8],
+    selected: u8,
+    time: [u8; 7],
+    // Timestamp do host (segundos desde UNIX_EPOCH) para avanço do RTC
+    rtc_last_update: i64,
+}
+
+impl TAMA5 {
+    pub fn new(rom: Vec<u8>) -> Self {
+        Self {
+            rom,
+            eeprom: [0; EEPROM_SIZE],
+            registers: [0; 8],
+            selected: 0,
+            // 1º de janeiro de 2000
+            time: [0, 0, 0, 0, 1, 1, 0],
+            rtc_last_update: Self::now_secs(),
+        }
+    }
+
+    #[inline]
+    fn now_secs() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64
+    }
+
+    fn rom_bank_count(&self) -> usize {
+        (self.rom.len() / 0x4000).max(1)
+    }
+
+    fn rom_bank(&self) -> usize {
+        let bank =
+            self.registers[REG_BANK_LO as usize] | (self.registers[REG_BANK_HI as usize] << 4);
+        bank as usize % self.rom_bank_count()
+    }
+
+    fn command(&self) -> u8 {
+        self.registers[REG_COMMAND as usize] >> 1
+    }
+
+    /// Endereço de 5 bits da EEPROM (bit 4 vem do registrador de comando)
+    fn eeprom_address(&self) -> usize {
+        (((self.registers[REG_COMMAND as usize] & 0x01) << 4)
+            | self.registers[REG_ADDR_LO as usize]) as usize
+    }
+
+    fn update_rtc(&mut self) {
+        let now = Self::now_secs();
+        let delta = now.saturating_sub(self.rtc_last_update);
+        if delta > 0 {
+            self.add_rtc_seconds(delta as u64);
+        }
+        self.rtc_last_update = now;
+    }
+
+    fn days_in_month(&self) -> u8 {
+        match self.time[MONTH] {
+            2 if self.time[YEAR].is_multiple_of(4) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    /// Soma `seconds` ao relógio com calendário (meses e anos bissextos)
+    fn add_rtc_seconds(&mut self, seconds: u64) {
+        let total = self.time[SECOND] as u64 + seconds;
+        self.time[SECOND] = (total % 60) as u8;
+        let minutes = self.time[MINUTE] as u64 + total / 60;
+        self.time[MINUTE] = (minutes % 60) as u8;
+        let hours = self.time[HOUR] as u64 + minutes / 60;
+        self.time[HOUR] = (hours % 24) as u8;
+
+        for _ in 0..hours / 24 {
+            self.time[WEEKDAY] = (self.time[WEEKDAY] + 1) % 7;
+            if self.time[DAY] >= self.days_in_month() {
+                self.time[DAY] = 1;
+                if self.time[MONTH] >= 12 {
+                    self.time[MONTH] = 1;
+                    self.time[YEAR] = (self.time[YEAR] + 1) % 100;
+                } else {
+                    self.time[MONTH] += 1;
+                }
+            } else {
+                self.time[DAY] += 1;
+            }
+        }
+    }
+
+    /// Relógio vindo de um .sav ou save state corrompido: traz cada campo para
+    /// a faixa válida antes de `add_rtc_seconds` fazer contas com ele
+    fn clamp_time(&mut self) {
+        self.time[SECOND] = self.time[SECOND].min(59);
+        self.time[MINUTE] = self.time[MINUTE].min(59);
+        self.time[HOUR] = self.time[HOUR].min(23);
+        self.time[WEEKDAY] = self.time[WEEKDAY].min(6);
+        self.time[DAY] = self.time[DAY].clamp(1, 31);
+        self.time[MONTH] = self.time[MONTH].clamp(1, 12);
+        self.time[YEAR] = self.time[YEAR].min(99);
+    }
+
+    fn rtc_nibble(&self, index: u8) -> u8 {
+        match RTC_NIBBLES.get(index as usize) {
+            Some(&(field, true)) => self.time[field] / 10,
+            Some(&(field, false)) => self.time[field] % 10,
+            None => 0,
+        }
+    }
+
+    fn set_rtc_nibble(&mut self, index: u8, value: u8) {
+        let Some(&(field, tens)) = RTC_NIBBLES.get(index as usize) else {
+            return;
+        };
+        let current = self.time[field];
+        self.time[field] = if tens {
+            value * 10 + current % 10
+        } else {
+            current / 10 * 10 + value
+        };
+        self.rtc_last_update = Self::now_secs();
+    }
+
+    /// Escrever o endereço baixo executa o comando selecionado
+    fn execute(&mut self) {
+        let value =
+            self.registers[REG_WRITE_LO as usize] | (self.registers[REG_WRITE_HI as usize] << 4);
+        match self.command() {
+            CMD_EEPROM_WRITE => {
+                let address = self.eeprom_address();
+                self.eeprom[address] = value;
+            }
+            CMD_RTC_WRITE => {
+                self.update_rtc();
+                self.set_rtc_nibble(self.registers[REG_ADDR_LO as usize], value & 0x0F);
+            }
+            _ => {}
+        }
+    }
+
+    /// Byte exposto por REG_READ_LO/HI para o comando atual
+    fn read_value(&self) -> u8 {
+        match self.command() {
+            CMD_EEPROM_READ => self.eeprom[self.eeprom_address()],
+            CMD_RTC_READ => self.rtc_nibble(self.registers[REG_ADDR_LO as usize]),
+            _ => 0x00,
+        }
+    }
+}
+
+impl MBC for TAMA5 {
+    fn read_rom(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x3FFF => self.rom.get(address as usize).copied().unwrap_or(0xFF),
+            0x4000..=0x7FFF => {
+                let idx = self.rom_bank() * 0x4000 + ((address - 0x4000) as usize);
+                self.rom.get(idx).copied().unwrap_or(0xFF)
+            }
+            _ => 0xFF,
+        }
+    }
+
+    fn write_register(&mut self, _address: u16, _value: u8) {}
+
+    fn read_ram(&self, address: u16) -> u8 {
+        if address != 0xA000 {
+            return 0xFF;
+        }
+        match self.selected {
+            REG_READ_LO => 0xF0 | (self.read_value() & 0x0F),
+            REG_READ_HI => 0xF0 | (self.read_value() >> 4),
+            // 0xA (ativo) e demais: chip pronto
+            _ => 0xF1,
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        match address {
+            0xA000 if self.selected < 8 => {
+                self.registers[self.selected as usize] = value & 0x0F;
+                if self.selected == REG_ADDR_LO {
+                    self.execute();
+                }
+            }
+            0xA001 => self.selected = value & 0x0F,
+            _ => {}
+        }
+    }
+
+    /// EEPROM seguida do relógio e do timestamp do host
+    fn save_ram(&self) -> Option<Vec<u8>> {
+        let mut clock = TAMA5::new(Vec::new());
+        clock.time = self.time;
+        clock.rtc_last_update = self.rtc_last_update;
+        clock.update_rtc();
+
+        let mut buf = self.eeprom.to_vec();
+        buf.extend_from_slice(&clock.time);
+        buf.extend_from_slice(&clock.rtc_last_update.to_le_bytes());
+        Some(buf)
+    }
+
+    fn load_ram(&mut self, data: &[u8]) {
+        let len = data.len().min(EEPROM_SIZE);
+        self.eeprom[..len].copy_from_slice(&data[..len]);
+
+        let now = Self::now_secs();
+        if let Some(tail) = data.get(EEPROM_SIZE..EEPROM_SIZE + 15) {
+            self.time.copy_from_slice(&tail[..7]);
+            self.clamp_time();
+            let mut ts_bytes = [0u8; 8];
+            ts_bytes.copy_from_slice(&tail[7..15]);
+            let saved_ts = i64::from_le_bytes(ts_bytes);
+            if saved_ts > 0 && now > saved_ts {
+                // O bichinho continua vivendo com o emulador fechado
+                self.add_rtc_seconds((now - saved_ts) as u64);
+            }
+        }
+        self.rtc_last_update = now;
+    }
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_bytes(&self.eeprom);
+        w.write_bytes(&self.registers);
+        w.write_u8(self.selected);
+        w.write_bytes(&self.time);
+        w.write_i64(self.rtc_last_update);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        r.read_bytes(&mut self.eeprom)?;
+        r.read_bytes(&mut self.registers)?;
+        for reg in self.registers.iter_mut() {
+            *reg &= 0x0F;
+        }
+        self.selected = r.read_u8()?;
+        r.read_bytes(&mut self.time)?;
+        self.clamp_time();
+        self.rtc_last_update = r.read_i64()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use super::MBC;
+use crate::GB::savestate::{StateReader, StateWriter};
+
+/// Wisdom Tree: sem RAM e sem registradores de verdade. Qualquer escrita em
+/// 0000-3FFF troca os 32KB inteiros de 0000-7FFF pelo banco indicado nos
+/// bits baixos do endereço (o valor escrito é ignorado)
+pub struct WisdomTree {
+    rom: Vec<u8>,
+    bank: u8,
+}
+
+impl WisdomTree {
+    pub fn new(rom: Vec<u8>) -> Self {
+        Self { rom, bank: 0 }
+    }
+
+    /// Cabeçalho zerado (checksum 0xE7) e a string "WISDOM TREE" no código
+    pub fn detect(rom: &[u8]) -> bool {
+        if rom.len() <= 0x8000 || rom.get(0x0147) != Some(&0x00) {
+            return false;
+        }
+        let blank = |range: std::ops::Range<usize>| rom[range].iter().all(|&b| b == 0);
+        if !blank(0x0134..0x014C) || !blank(0x00F0..0x0100) || rom[0x014D] != 0xE7 {
+            return false;
+        }
+        rom[0x0300..]
+            .windows(11)
+            .any(|w| &w[..6] == b"WISDOM" && &w[7..] == b"TREE")
+    }
+
+    fn bank_count(&self) -> usize {
+        (self.rom.len() / 0x8000).max(1)
+    }
+}
+
+impl MBC for WisdomTree {
+    fn read_rom(&self, address: u16) -> u8 {
+        let bank = self.bank as usize % self.bank_count();
+        self.rom
+            .get(bank * 0x8000 + address as usize)
+            .copied()
+            .unwrap_or(0xFF)
+    }
+
+    fn write_register(&mut self, address: u16, _value: u8) {
+        if address < 0x4000 {
+            self.bank = address as u8;
+        }
+    }
+
+    fn read_ram(&self, _address: u16) -> u8 {
+        0xFF
+    }
+
+    fn write_ram(&mut self, _address: u16, _value: u8) {}
+
+    fn save_ram(&self) -> Option<Vec<u8>> {
+        None
+    }
+
+    fn load_ram(&mut self, _data: &[u8]) {}
+
+    fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.bank);
+    }
+
+    fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.bank = r.read_u8()?;
+        Ok(())
+    }
+}
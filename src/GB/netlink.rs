@@ -0,0 +1,336 @@
+//! Cabo link entre dois processos por TCP ou socket Unix (`unix:caminho`)
+//!
+//! Cada mensagem tem 10 bytes: tipo, valor e o relógio serial de quem enviou
+//! (u64 little-endian). Os dois lados rodam em lockstep: nenhum passa mais de
+//! `MAX_AHEAD` ciclos do último relógio recebido do outro, e o master só dá
+//! um pulso depois que o slave alcançou o instante dele
+
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use crate::GB::serial::LinkEndpoint;
+
+/// Intervalo entre anúncios do relógio local
+const SYNC_INTERVAL: u64 = 256;
+/// Quanto um lado pode correr à frente do outro (um bit no clock normal)
+const MAX_AHEAD: u64 = 512;
+
+const MSG_SYNC: u8 = 0;
+/// Slave armado com o byte de SB
+const MSG_ARM: u8 = 1;
+/// Fora de transferência; valor = bit na saída
+const MSG_IDLE: u8 = 2;
+/// Pulso de clock do master; valor = bit enviado
+const MSG_CLOCK: u8 = 3;
+
+enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Stream {
+    fn try_clone(&self) -> io::Result<Stream> {
+        match self {
+            Stream::Tcp(s) => s.try_clone().map(Stream::Tcp),
+            #[cfg(unix)]
+            Stream::Unix(s) => s.try_clone().map(Stream::Unix),
+        }
+    }
+
+    fn shutdown(&self) {
+        let _ = match self {
+            Stream::Tcp(s) => s.shutdown(Shutdown::Both),
+            #[cfg(unix)]
+            Stream::Unix(s) => s.shutdown(Shutdown::Both),
+        };
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(s) => s.read(buf),
+            #[cfg(unix)]
+            Stream::Unix(s) => s.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(s) => s.write(buf),
+            #[cfg(unix)]
+            Stream::Unix(s) => s.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stream::Tcp(s) => s.flush(),
+            #[cfg(unix)]
+            Stream::Unix(s) => s.flush(),
+        }
+    }
+}
+
+/// Saída do outro lado vista daqui
+enum Remote {
+    Idle(bool),
+    /// Slave armado: os bits saem de `sb` em ordem, um por pulso
+    Armed {
+        sb: u8,
+        clocked: u8,
+    },
+}
+
+struct Shared {
+    remote: Remote,
+    remote_now: u64,
+    pulses: VecDeque<(bool, u64)>,
+    closed: bool,
+}
+
+type SharedState = Arc<(Mutex<Shared>, Condvar)>;
+
+/// Ponta do cabo ligada a outro processo
+pub struct NetLink {
+    stream: Stream,
+    shared: SharedState,
+    last_sent: u64,
+    // Último (armado, MSB) anunciado
+    output: Option<(bool, bool)>,
+}
+
+/// Endereço de escuta de `--link-listen`
+pub enum LinkListener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener, String),
+}
+
+impl LinkListener {
+    pub fn bind(addr: &str) -> Result<LinkListener, String> {
+        if let Some(path) = addr.strip_prefix("unix:") {
+            #[cfg(unix)]
+            return UnixListener::bind(path)
+                .map(|l| LinkListener::Unix(l, path.to_string()))
+                .map_err(|e| format!("{}: {}", addr, e));
+            #[cfg(not(unix))]
+            return Err(format!("Socket Unix indisponível: {}", path));
+        }
+        TcpListener::bind(addr)
+            .map(LinkListener::Tcp)
+            .map_err(|e| format!("{}: {}", addr, e))
+    }
+
+    /// Endereço efetivo (com a porta escolhida pelo sistema se era 0)
+    pub fn local_addr(&self) -> String {
+        match self {
+            LinkListener::Tcp(l) => l.local_addr().map(|a| a.to_string()).unwrap_or_default(),
+            #[cfg(unix)]
+            LinkListener::Unix(_, path) => format!("unix:{}", path),
+        }
+    }
+
+    /// Espera o outro processo conectar
+    pub fn accept(&self) -> Result<NetLink, String> {
+        let stream = match self {
+            LinkListener::Tcp(l) => {
+                let (s, _) = l.accept().map_err(|e| e.to_string())?;
+                let _ = s.set_nodelay(true);
+                Stream::Tcp(s)
+            }
+            #[cfg(unix)]
+            LinkListener::Unix(l, path) => {
+                let (s, _) = l.accept().map_err(|e| e.to_string())?;
+                // Conectado: o arquivo do socket não serve mais
+                let _ = std::fs::remove_file(path);
+                Stream::Unix(s)
+            }
+        };
+        NetLink::new(stream)
+    }
+}
+
+impl NetLink {
+    pub fn listen(addr: &str) -> Result<NetLink, String> {
+        LinkListener::bind(addr)?.accept()
+    }
+
+    pub fn connect(addr: &str) -> Result<NetLink, String> {
+        let stream = if let Some(path) = addr.strip_prefix("unix:") {
+            #[cfg(unix)]
+            {
+                Stream::Unix(UnixStream::connect(path).map_err(|e| format!("{}: {}", addr, e))?)
+            }
+            #[cfg(not(unix))]
+            return Err(format!("Socket Unix indisponível: {}", path));
+        } else {
+            let s = TcpStream::connect(addr).map_err(|e| format!("{}: {}", addr, e))?;
+            let _ = s.set_nodelay(true);
+            Stream::Tcp(s)
+        };
+        NetLink::new(stream)
+    }
+
+    fn new(stream: Stream) -> Result<NetLink, String> {
+        let reader = stream.try_clone().map_err(|e| e.to_string())?;
+        let shared: SharedState = Arc::new((
+            Mutex::new(Shared {
+                // Linha em repouso fica em 1
+                remote: Remote::Idle(true),
+                remote_now: 0,
+                pulses: VecDeque::new(),
+                closed: false,
+            }),
+            Condvar::new(),
+        ));
+        let thread_shared = shared.clone();
+        thread::spawn(move || read_loop(reader, thread_shared));
+        Ok(NetLink {
+            stream,
+            shared,
+            last_sent: 0,
+            output: None,
+        })
+    }
+
+    fn send(&mut self, kind: u8, value: u8, now: u64) {
+        let mut msg = [0u8; 10];
+        msg[0] = kind;
+        msg[1] = value;
+        msg[2..].copy_from_slice(&now.to_le_bytes());
+        if self.stream.write_all(&msg).is_err() {
+            let (lock, cvar) = &*self.shared;
+            lock.lock().unwrap().closed = true;
+            cvar.notify_all();
+        }
+        self.last_sent = now;
+    }
+
+    /// Bloqueia até o relógio do outro lado chegar a `target`. Anuncia o
+    /// relógio local antes, senão os dois lados poderiam esperar um pelo outro
+    fn wait_for(&mut self, now: u64, target: u64) {
+        let shared = self.shared.clone();
+        let (lock, cvar) = &*shared;
+        {
+            let s = lock.lock().unwrap();
+            if s.closed || s.remote_now >= target {
+                return;
+            }
+        }
+        if self.last_sent != now {
+            self.send(MSG_SYNC, 0, now);
+        }
+        let mut s = lock.lock().unwrap();
+        while !s.closed && s.remote_now < target {
+            s = cvar.wait(s).unwrap();
+        }
+    }
+}
+
+fn read_loop(mut stream: Stream, shared: SharedState) {
+    let (lock, cvar) = &*shared;
+    let mut msg = [0u8; 10];
+    while stream.read_exact(&mut msg).is_ok() {
+        let mut ts = [0u8; 8];
+        ts.copy_from_slice(&msg[2..]);
+        let ts = u64::from_le_bytes(ts);
+
+        let mut s = lock.lock().unwrap();
+        s.remote_now = s.remote_now.max(ts);
+        match msg[0] {
+            MSG_ARM => {
+                s.remote = Remote::Armed {
+                    sb: msg[1],
+                    clocked: 0,
+                }
+            }
+            MSG_IDLE => s.remote = Remote::Idle(msg[1] != 0),
+            MSG_CLOCK => s.pulses.push_back((msg[1] != 0, ts)),
+            _ => {}
+        }
+        cvar.notify_all();
+    }
+    // Cabo desconectado: ninguém mais espera pelo outro lado
+    lock.lock().unwrap().closed = true;
+    cvar.notify_all();
+}
+
+impl LinkEndpoint for NetLink {
+    fn clock_out(&mut self, bit: bool, now: u64) -> bool {
+        // O slave precisa ter processado tudo até este instante (inclusive
+        // ter armado) antes de saber o bit que ele tem na saída
+        self.wait_for(now, now);
+        let bit_in = {
+            let mut s = self.shared.0.lock().unwrap();
+            let closed = s.closed;
+            match &mut s.remote {
+                _ if closed => true,
+                Remote::Idle(out) => *out,
+                Remote::Armed { sb, clocked } => {
+                    let out = (*sb << *clocked) & 0x80 != 0;
+                    *clocked += 1;
+                    if *clocked == 8 {
+                        // O slave completa e anuncia a nova saída
+                        s.remote = Remote::Idle(true);
+                    }
+                    out
+                }
+            }
+        };
+        self.send(MSG_CLOCK, bit as u8, now);
+        bit_in
+    }
+
+    fn clock_in(&mut self, now: u64) -> Option<bool> {
+        let mut s = self.shared.0.lock().unwrap();
+        match s.pulses.front() {
+            Some(&(bit, ts)) if ts <= now => {
+                s.pulses.pop_front();
+                Some(bit)
+            }
+            _ => None,
+        }
+    }
+
+    fn set_output(&mut self, sb: u8, armed: bool, now: u64) {
+        let msb = sb & 0x80 != 0;
+        // Armado, os bits já vão todos no ARM; shifts não precisam de aviso
+        let changed = match self.output {
+            Some((was_armed, _)) if armed => !was_armed,
+            Some((was_armed, was_msb)) => was_armed || was_msb != msb,
+            None => true,
+        };
+        if !changed {
+            return;
+        }
+        self.output = Some((armed, msb));
+        if armed {
+            self.send(MSG_ARM, sb, now);
+        } else {
+            self.send(MSG_IDLE, msb as u8, now);
+        }
+    }
+
+    fn sync(&mut self, now: u64) {
+        if now >= self.last_sent + SYNC_INTERVAL {
+            self.send(MSG_SYNC, 0, now);
+        }
+        self.wait_for(now, now.saturating_sub(MAX_AHEAD));
+    }
+}
+
+impl Drop for NetLink {
+    fn drop(&mut self) {
+        // Acorda a thread de leitura dos dois lados
+        self.stream.shutdown();
+    }
+}
@@ -0,0 +1,390 @@
+//! Game Boy Printer: fica no cabo link como slave e recebe pacotes
+//! 88 33 | comando | compressão | tamanho (LE) | dados | checksum (LE) | 00 00,
+//! respondendo 0x81 (vivo) e o status nos dois últimos bytes
+
+use crate::GB::cartridge::crc32;
+use crate::GB::serial::LinkEndpoint;
+
+pub const PRINTER_WIDTH: usize = 160;
+/// Faixa de 2 linhas de tiles (20 tiles de 16 bytes cada)
+const BAND_SIZE: usize = 20 * 16 * 2;
+/// RAM da impressora: 9 pacotes de dados por impressão
+const BUFFER_SIZE: usize = BAND_SIZE * 9;
+/// Linhas de papel por unidade de margem (uma faixa do cabeçote)
+const MARGIN_LINES: usize = 16;
+/// Tempo de impressão por faixa (~0.1s)
+const BAND_PRINT_CYCLES: u64 = 4_194_304 / 10;
+
+const CMD_INIT: u8 = 0x01;
+const CMD_PRINT: u8 = 0x02;
+const CMD_DATA: u8 = 0x04;
+const CMD_STATUS: u8 = 0x0F;
+
+const ALIVE: u8 = 0x81;
+
+// Bits do status
+const STATUS_CHECKSUM_ERROR: u8 = 0x01;
+const STATUS_PRINTING: u8 = 0x02;
+const STATUS_FULL: u8 = 0x04;
+const STATUS_UNPROCESSED: u8 = 0x08;
+
+/// Imagem impressa: um tom de 0 (branco) a 3 (preto) por pixel
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrintedImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl PrintedImage {
+    fn grey_levels(&self) -> Vec<u8> {
+        self.pixels.iter().map(|&shade| 255 - shade * 85).collect()
+    }
+
+    /// PGM binário (P5) com 256 níveis
+    pub fn to_pgm(&self) -> Vec<u8> {
+        let mut out = format!("P5\n{} {}\n255\n", self.width, self.height).into_bytes();
+        out.extend_from_slice(&self.grey_levels());
+        out
+    }
+
+    /// PNG em tons de cinza de 8 bits, com deflate sem compressão
+    pub fn to_png(&self) -> Vec<u8> {
+        let grey = self.grey_levels();
+        let mut raw = Vec::with_capacity((self.width + 1) * self.height);
+        for row in grey.chunks(self.width.max(1)) {
+            raw.push(0); // filtro None
+            raw.extend_from_slice(row);
+        }
+
+        let mut ihdr = Vec::new();
+        ihdr.extend_from_slice(&(self.width as u32).to_be_bytes());
+        ihdr.extend_from_slice(&(self.height as u32).to_be_bytes());
+        ihdr.extend_from_slice(&[8, 0, 0, 0, 0]); // 8 bits, cinza
+
+        let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut out, b"IHDR", &ihdr);
+        png_chunk(&mut out, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut out, b"IEND", &[]);
+        out
+    }
+
+    /// Grava em PNG ou PGM conforme a extensão do arquivo
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let data = if path.to_ascii_lowercase().ends_with(".pgm") {
+            self.to_pgm()
+        } else {
+            self.to_png()
+        };
+        std::fs::write(path, data).map_err(|e| format!("{}: {}", path, e))
+    }
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Stream zlib só com blocos "stored" (sem compressão)
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xFFFF).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[0x01, 0x00, 0x00, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        out.push(blocks.peek().is_none() as u8);
+        let len = block.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    out.extend_from_slice(&((b << 16) | a).to_be_bytes());
+    out
+}
+
+/// Recebe cada imagem pronta (a página acaba numa margem inferior não nula)
+pub type PrintCallback = Box<dyn FnMut(&PrintedImage) + Send>;
+
+/// Callback que grava cada página em `dir/print_NNN.png`
+pub fn save_to_directory(dir: &str) -> PrintCallback {
+    let dir = dir.to_string();
+    let mut count = 0;
+    Box::new(move |image: &PrintedImage| {
+        count += 1;
+        let path = format!("{}/print_{:03}.png", dir, count);
+        match image.save(&path) {
+            Ok(()) => println!("🖨️ Impressão salva em {}", path),
+            Err(e) => eprintln!("⚠️ Erro ao salvar impressão: {}", e),
+        }
+    })
+}
+
+/// Descompressão RLE dos pacotes de dados: bit 7 do byte de controle liga
+/// uma repetição de (n & 0x7F) + 2 cópias do byte seguinte; sem ele vêm
+/// n + 1 bytes literais
+pub fn decompress(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < data.len() {
+        let control = data[i];
+        i += 1;
+        if control & 0x80 != 0 {
+            let Some(&value) = data.get(i) else { break };
+            out.extend(std::iter::repeat_n(value, (control & 0x7F) as usize + 2));
+            i += 1;
+        } else {
+            let end = (i + control as usize + 1).min(data.len());
+            out.extend_from_slice(&data[i..end]);
+            i = end;
+        }
+    }
+    out
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Magic1,
+    Magic2,
+    Command,
+    Compression,
+    LengthLo,
+    LengthHi,
+    Data,
+    ChecksumLo,
+    ChecksumHi,
+    Alive,
+    Status,
+}
+
+pub struct Printer {
+    stage: Stage,
+    // Shift register do lado da impressora
+    in_byte: u8,
+    out_byte: u8,
+    bits: u8,
+
+    command: u8,
+    compressed: bool,
+    length: u16,
+    data: Vec<u8>,
+    checksum: u16,
+    received_checksum: u16,
+
+    buffer: Vec<u8>,
+    status: u8,
+    busy_until: u64,
+    /// Página em andamento (impressões sem margem inferior se emendam)
+    page: Vec<u8>,
+    output: PrintCallback,
+}
+
+impl Printer {
+    pub fn new(output: PrintCallback) -> Self {
+        Self {
+            stage: Stage::Magic1,
+            in_byte: 0,
+            out_byte: 0,
+            bits: 0,
+            command: 0,
+            compressed: false,
+            length: 0,
+            data: Vec::new(),
+            checksum: 0,
+            received_checksum: 0,
+            buffer: Vec::new(),
+            status: 0,
+            busy_until: 0,
+            page: Vec::new(),
+            output,
+        }
+    }
+
+    fn status(&self, now: u64) -> u8 {
+        let mut status = self.status;
+        if now < self.busy_until {
+            status |= STATUS_PRINTING;
+        }
+        status
+    }
+
+    /// Trata um byte recebido e devolve o que sai no próximo
+    fn receive(&mut self, byte: u8, now: u64) -> u8 {
+        match self.stage {
+            Stage::Magic1 => {
+                if byte == 0x88 {
+                    self.stage = Stage::Magic2;
+                }
+            }
+            Stage::Magic2 => {
+                self.stage = if byte == 0x33 {
+                    Stage::Command
+                } else {
+                    Stage::Magic1
+                };
+            }
+            Stage::Command => {
+                self.command = byte;
+                self.checksum = byte as u16;
+                self.stage = Stage::Compression;
+            }
+            Stage::Compression => {
+                self.compressed = byte & 0x01 != 0;
+                self.checksum = self.checksum.wrapping_add(byte as u16);
+                self.stage = Stage::LengthLo;
+            }
+            Stage::LengthLo => {
+                self.length = byte as u16;
+                self.checksum = self.checksum.wrapping_add(byte as u16);
+                self.stage = Stage::LengthHi;
+            }
+            Stage::LengthHi => {
+                self.length |= (byte as u16) << 8;
+                self.checksum = self.checksum.wrapping_add(byte as u16);
+                self.data.clear();
+                self.stage = if self.length == 0 {
+                    Stage::ChecksumLo
+                } else {
+                    Stage::Data
+                };
+            }
+            Stage::Data => {
+                self.data.push(byte);
+                self.checksum = self.checksum.wrapping_add(byte as u16);
+                if self.data.len() == self.length as usize {
+                    self.stage = Stage::ChecksumLo;
+                }
+            }
+            Stage::ChecksumLo => {
+                self.received_checksum = byte as u16;
+                self.stage = Stage::ChecksumHi;
+            }
+            Stage::ChecksumHi => {
+                self.received_checksum |= (byte as u16) << 8;
+                self.stage = Stage::Alive;
+                return ALIVE;
+            }
+            Stage::Alive => {
+                if self.received_checksum == self.checksum {
+                    self.status &= !STATUS_CHECKSUM_ERROR;
+                    self.execute(now);
+                } else {
+                    self.status |= STATUS_CHECKSUM_ERROR;
+                }
+                self.stage = Stage::Status;
+                return self.status(now);
+            }
+            Stage::Status => self.stage = Stage::Magic1,
+        }
+        0x00
+    }
+
+    fn execute(&mut self, now: u64) {
+        match self.command {
+            CMD_INIT => {
+                self.buffer.clear();
+                self.status = 0;
+            }
+            CMD_DATA => {
+                let data = if self.compressed {
+                    decompress(&self.data)
+                } else {
+                    std::mem::take(&mut self.data)
+                };
+                let room = BUFFER_SIZE - self.buffer.len();
+                self.buffer.extend_from_slice(&data[..data.len().min(room)]);
+                if !self.buffer.is_empty() {
+                    self.status |= STATUS_UNPROCESSED;
+                }
+                if self.buffer.len() >= BUFFER_SIZE {
+                    self.status |= STATUS_FULL;
+                }
+            }
+            CMD_PRINT if self.data.len() >= 4 => {
+                let (sheets, margins, palette) = (self.data[0], self.data[1], self.data[2]);
+                // A exposição (data[3]) só escurece o papel térmico; não muda os tons
+                let bands = self.buffer.len() / BAND_SIZE;
+                self.print(sheets, margins, palette);
+                self.buffer.clear();
+                self.status &= !(STATUS_UNPROCESSED | STATUS_FULL);
+                self.busy_until = now + BAND_PRINT_CYCLES * (bands.max(1) * sheets as usize) as u64;
+            }
+            CMD_STATUS => {}
+            _ => {}
+        }
+    }
+
+    /// Converte o buffer (tiles 2bpp, 20 por linha) em tons pela paleta,
+    /// no formato do BGP
+    fn render(&self, palette: u8) -> Vec<u8> {
+        let rows = self.buffer.len() / (20 * 16);
+        let mut pixels = vec![0u8; PRINTER_WIDTH * rows * 8];
+        for (tile_index, tile) in self.buffer.chunks_exact(16).enumerate() {
+            let (tile_x, tile_y) = (tile_index % 20, tile_index / 20);
+            for y in 0..8 {
+                let (lo, hi) = (tile[y * 2], tile[y * 2 + 1]);
+                for x in 0..8 {
+                    let bit = 7 - x;
+                    let color = ((lo >> bit) & 1) | (((hi >> bit) & 1) << 1);
+                    let shade = (palette >> (color * 2)) & 0x03;
+                    pixels[(tile_y * 8 + y) * PRINTER_WIDTH + tile_x * 8 + x] = shade;
+                }
+            }
+        }
+        pixels
+    }
+
+    /// Margens em unidades de faixa: nibble alto antes, baixo depois.
+    /// Sem margem inferior o papel fica na impressora esperando a próxima
+    fn print(&mut self, sheets: u8, margins: u8, palette: u8) {
+        let (before, after) = ((margins >> 4) as usize, (margins & 0x0F) as usize);
+        self.page
+            .resize(self.page.len() + before * MARGIN_LINES * PRINTER_WIDTH, 0);
+        let image = self.render(palette);
+        for _ in 0..sheets {
+            self.page.extend_from_slice(&image);
+        }
+        if after == 0 {
+            return;
+        }
+        self.page
+            .resize(self.page.len() + after * MARGIN_LINES * PRINTER_WIDTH, 0);
+        let page = PrintedImage {
+            width: PRINTER_WIDTH,
+            height: self.page.len() / PRINTER_WIDTH,
+            pixels: std::mem::take(&mut self.page),
+        };
+        (self.output)(&page);
+    }
+}
+
+impl LinkEndpoint for Printer {
+    /// A impressora é sempre slave: cada pulso do Game Boy troca um bit
+    fn clock_out(&mut self, bit: bool, now: u64) -> bool {
+        let out = self.out_byte & 0x80 != 0;
+        self.out_byte <<= 1;
+        self.in_byte = (self.in_byte << 1) | bit as u8;
+        self.bits += 1;
+        if self.bits == 8 {
+            self.bits = 0;
+            self.out_byte = self.receive(self.in_byte, now);
+        }
+        out
+    }
+
+    fn clock_in(&mut self, _now: u64) -> Option<bool> {
+        None
+    }
+
+    fn set_output(&mut self, _sb: u8, _armed: bool, _now: u64) {}
+}
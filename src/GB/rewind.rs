@@ -0,0 +1,199 @@
+//! Rewind: ring buffer de save states comprimidos por delta
+//!
+//! Só o snapshot mais recente fica inteiro na memória. Cada snapshot
+//! anterior é guardado como a diferença (XOR + RLE de zeros) para o
+//! snapshot seguinte, então voltar no tempo é desfazer um delta por vez
+//! e descartar o mais antigo é só remover a frente da fila.
+
+use std::collections::VecDeque;
+
+/// Configuração do buffer de rewind
+#[derive(Clone, Copy, Debug)]
+pub struct RewindConfig {
+    /// Frames entre snapshots
+    pub interval_frames: u32,
+    /// Limite de memória (bytes) para todo o histórico
+    pub max_bytes: usize,
+}
+
+impl Default for RewindConfig {
+    fn default() -> Self {
+        Self {
+            interval_frames: 10,
+            max_bytes: 32 * 1024 * 1024,
+        }
+    }
+}
+
+pub struct RewindBuffer {
+    config: RewindConfig,
+    /// Snapshot mais recente, sem compressão
+    latest: Option<Vec<u8>>,
+    /// Deltas do mais antigo (frente) ao mais novo (fundo)
+    deltas: VecDeque<Vec<u8>>,
+    deltas_bytes: usize,
+    frame_counter: u32,
+}
+
+impl RewindBuffer {
+    pub fn new(config: RewindConfig) -> Self {
+        Self {
+            config: RewindConfig {
+                interval_frames: config.interval_frames.max(1),
+                ..config
+            },
+            latest: None,
+            deltas: VecDeque::new(),
+            deltas_bytes: 0,
+            frame_counter: 0,
+        }
+    }
+
+    /// Chamado uma vez por frame; retorna true quando é hora de um snapshot
+    pub fn frame_tick(&mut self) -> bool {
+        self.frame_counter += 1;
+        if self.frame_counter >= self.config.interval_frames {
+            self.frame_counter = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Adiciona um snapshot (saída de `CPU::save_state`) ao histórico
+    pub fn push(&mut self, state: Vec<u8>) {
+        if let Some(prev) = self.latest.take() {
+            let delta = encode_delta(&state, &prev);
+            self.deltas_bytes += delta.len();
+            self.deltas.push_back(delta);
+        }
+        self.latest = Some(state);
+
+        // Descarta os snapshots mais antigos até caber no limite
+        while self.memory_usage() > self.config.max_bytes {
+            match self.deltas.pop_front() {
+                Some(old) => self.deltas_bytes -= old.len(),
+                None => break,
+            }
+        }
+    }
+
+    /// Remove e retorna o snapshot mais recente
+    pub fn pop(&mut self) -> Option<Vec<u8>> {
+        let current = self.latest.take()?;
+        if let Some(delta) = self.deltas.pop_back() {
+            self.deltas_bytes -= delta.len();
+            self.latest = Some(apply_delta(&current, &delta));
+        }
+        self.frame_counter = 0;
+        Some(current)
+    }
+
+    /// Quantidade de snapshots disponíveis
+    pub fn len(&self) -> usize {
+        match self.latest {
+            Some(_) => self.deltas.len() + 1,
+            None => 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.latest.is_none()
+    }
+
+    pub fn clear(&mut self) {
+        self.latest = None;
+        self.deltas.clear();
+        self.deltas_bytes = 0;
+        self.frame_counter = 0;
+    }
+
+    /// Bytes ocupados pelo histórico (snapshot inteiro + deltas)
+    pub fn memory_usage(&self) -> usize {
+        self.latest.as_ref().map_or(0, |s| s.len()) + self.deltas_bytes
+    }
+}
+
+// ========== DELTA (XOR + RLE) ==========
+// Formato: tamanho do alvo (varint), depois pares
+// [zeros pulados (varint)] [n literais (varint)] [n bytes XOR]
+
+fn write_varint(out: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(data: &[u8], pos: &mut usize) -> usize {
+    let mut value = 0usize;
+    let mut shift = 0;
+    while let Some(&byte) = data.get(*pos) {
+        *pos += 1;
+        value |= ((byte & 0x7F) as usize) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    value
+}
+
+/// Codifica `target` como diferença em relação a `base`
+fn encode_delta(base: &[u8], target: &[u8]) -> Vec<u8> {
+    let xor_at = |i: usize| target[i] ^ base.get(i).copied().unwrap_or(0);
+    let mut out = Vec::new();
+    write_varint(&mut out, target.len());
+
+    let mut i = 0;
+    while i < target.len() {
+        let run_start = i;
+        while i < target.len() && xor_at(i) == 0 {
+            i += 1;
+        }
+        if i == target.len() {
+            break;
+        }
+        let zeros = i - run_start;
+
+        // Literais terminam em uma sequência de 4+ zeros (vale mais a pena pular)
+        let lit_start = i;
+        let mut zero_streak = 0;
+        while i < target.len() && zero_streak < 4 {
+            if xor_at(i) == 0 {
+                zero_streak += 1;
+            } else {
+                zero_streak = 0;
+            }
+            i += 1;
+        }
+        i -= zero_streak;
+
+        write_varint(&mut out, zeros);
+        write_varint(&mut out, i - lit_start);
+        out.extend((lit_start..i).map(xor_at));
+    }
+    out
+}
+
+/// Reconstrói o alvo a partir de `base` e de um delta de `encode_delta`
+fn apply_delta(base: &[u8], delta: &[u8]) -> Vec<u8> {
+    let mut pos = 0;
+    let len = read_varint(delta, &mut pos);
+    let mut out: Vec<u8> = (0..len)
+        .map(|i| base.get(i).copied().unwrap_or(0))
+        .collect();
+
+    let mut i = 0;
+    while pos < delta.len() {
+        i += read_varint(delta, &mut pos);
+        let literals = read_varint(delta, &mut pos);
+        for &byte in &delta[pos..pos + literals] {
+            out[i] ^= byte;
+            i += 1;
+        }
+        pos += literals;
+    }
+    out
+}
@@ -0,0 +1,163 @@
+//! Formato binário de save state
+//! Cabeçalho: magic "GBSS" + versão (u16).
+//! Cada componente serializa seus campos em ordem fixa (little-endian).
+
+pub const SAVE_STATE_MAGIC: [u8; 4] = *b"GBSS";
+pub const SAVE_STATE_VERSION: u16 = 17;
+
+/// Acumula os bytes de um save state
+#[derive(Default)]
+pub struct StateWriter {
+    buf: Vec<u8>,
+}
+
+impl StateWriter {
+    pub fn new() -> Self {
+        Self { buf: Vec::new() }
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.buf
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.buf.push(value as u8);
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_i32(&mut self, value: i32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_i64(&mut self, value: i64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_opt_u16(&mut self, value: Option<u16>) {
+        self.write_bool(value.is_some());
+        self.write_u16(value.unwrap_or(0));
+    }
+
+    /// Bloco de tamanho fixo (o leitor precisa conhecer o tamanho)
+    pub fn write_bytes(&mut self, data: &[u8]) {
+        self.buf.extend_from_slice(data);
+    }
+
+    /// Bloco de tamanho variável, prefixado pelo comprimento (u32)
+    pub fn write_vec(&mut self, data: &[u8]) {
+        self.write_u32(data.len() as u32);
+        self.buf.extend_from_slice(data);
+    }
+}
+
+/// Lê os campos de um save state na mesma ordem em que foram escritos
+pub struct StateReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> StateReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if self.remaining() < len {
+            return Err(format!(
+                "Save state truncado: faltam {} bytes na posição {}",
+                len - self.remaining(),
+                self.pos
+            ));
+        }
+        let slice = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(slice)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, String> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, String> {
+        let mut bytes = [0u8; 2];
+        bytes.copy_from_slice(self.take(2)?);
+        Ok(u16::from_le_bytes(bytes))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, String> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32, String> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(i32::from_le_bytes(bytes))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, String> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    pub fn read_i64(&mut self) -> Result<i64, String> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(i64::from_le_bytes(bytes))
+    }
+
+    pub fn read_opt_u16(&mut self) -> Result<Option<u16>, String> {
+        let present = self.read_bool()?;
+        let value = self.read_u16()?;
+        Ok(if present { Some(value) } else { None })
+    }
+
+    /// Preenche `out` com um bloco de tamanho fixo
+    pub fn read_bytes(&mut self, out: &mut [u8]) -> Result<(), String> {
+        out.copy_from_slice(self.take(out.len())?);
+        Ok(())
+    }
+
+    pub fn read_vec(&mut self) -> Result<Vec<u8>, String> {
+        let len = self.read_u32()? as usize;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    /// Lê um bloco variável que precisa ter exatamente o tamanho de `out`
+    pub fn read_vec_into(&mut self, out: &mut [u8]) -> Result<(), String> {
+        let len = self.read_u32()? as usize;
+        if len != out.len() {
+            return Err(format!(
+                "Save state incompatível: bloco de {} bytes, esperado {}",
+                len,
+                out.len()
+            ));
+        }
+        self.read_bytes(out)
+    }
+}
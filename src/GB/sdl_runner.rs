@@ -3,6 +3,8 @@
 
 use crate::GB::CPU::CPU;
 use crate::GB::debugger::{DebugCommand, DebugResponse, Debugger};
+use crate::GB::rewind::{RewindBuffer, RewindConfig};
+use crate::GB::sgb::{SGB_HEIGHT, SGB_WIDTH};
 use std::collections::VecDeque;
 use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use std::sync::mpsc::{self, Receiver, Sender};
@@ -10,9 +12,13 @@ use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
+use sdl3::GamepadSubsystem;
 use sdl3::audio::{AudioCallback, AudioSpec, AudioStream};
 use sdl3::event::Event;
+use sdl3::gamepad::Gamepad;
 use sdl3::keyboard::Keycode;
+use sdl3::mouse::MouseButton;
+use sdl3::pixels::Color;
 use sdl3::rect::Rect;
 
 // Constantes do Game Boy
@@ -27,8 +33,9 @@ const SAMPLE_RATE: u32 = 44_100;
 // TRIPLE BUFFER
 // =============================================================================
 
+/// Frames em RGB555 (PPU::rgb_framebuffer, ou a saída 256×224 do SGB)
 struct TripleBuffer {
-    buffers: [Mutex<Vec<u8>>; 3],
+    buffers: [Mutex<Vec<u16>>; 3],
     write_idx: AtomicU8,
     ready_idx: AtomicU8,
     read_idx: AtomicU8,
@@ -36,12 +43,12 @@ struct TripleBuffer {
 }
 
 impl TripleBuffer {
-    fn new() -> Self {
+    fn new(pixels: usize) -> Self {
         Self {
             buffers: [
-                Mutex::new(vec![0u8; GB_WIDTH * GB_HEIGHT]),
-                Mutex::new(vec![0u8; GB_WIDTH * GB_HEIGHT]),
-                Mutex::new(vec![0u8; GB_WIDTH * GB_HEIGHT]),
+                Mutex::new(vec![0u16; pixels]),
+                Mutex::new(vec![0u16; pixels]),
+                Mutex::new(vec![0u16; pixels]),
             ],
             write_idx: AtomicU8::new(0),
             ready_idx: AtomicU8::new(1),
@@ -50,7 +57,7 @@ impl TripleBuffer {
         }
     }
 
-    fn submit_frame(&self, framebuffer: &[u8; GB_WIDTH * GB_HEIGHT]) {
+    fn submit_frame(&self, framebuffer: &[u16]) {
         let write_idx = self.write_idx.load(Ordering::Acquire) as usize;
         {
             let mut buf = self.buffers[write_idx].lock().unwrap();
@@ -61,7 +68,7 @@ impl TripleBuffer {
         self.new_frame_available.store(true, Ordering::Release);
     }
 
-    fn get_frame(&self) -> Option<Vec<u8>> {
+    fn get_frame(&self) -> Option<Vec<u16>> {
         if !self.new_frame_available.swap(false, Ordering::AcqRel) {
             return None;
         }
@@ -84,22 +91,33 @@ struct SharedState {
     running: AtomicBool,
     paused: AtomicBool,
     debug_requested: AtomicBool,
+    rewinding: AtomicBool,
     joypad_pressed: AtomicU8,
     joypad_released: AtomicU8,
+    /// Setas seguradas (bits do D-pad), usadas como inclinação no MBC7
+    tilt_keys: AtomicU8,
+    /// Inclinação pelo mouse enquanto o botão esquerdo está pressionado
+    mouse_tilt: Mutex<Option<(f32, f32)>>,
+    /// Duty do motor de vibração no último frame (None sem motor no cartucho)
+    rumble_duty: Mutex<Option<f32>>,
     emu_fps: Mutex<f64>,
     audio_buffer_size: Mutex<usize>,
 }
 
 impl SharedState {
-    fn new() -> Self {
+    fn new(pixels: usize) -> Self {
         Self {
-            frame_buffer: TripleBuffer::new(),
+            frame_buffer: TripleBuffer::new(pixels),
             audio_buffer: Mutex::new(VecDeque::with_capacity(SAMPLE_RATE as usize)),
             running: AtomicBool::new(true),
             paused: AtomicBool::new(false),
             debug_requested: AtomicBool::new(false),
+            rewinding: AtomicBool::new(false),
             joypad_pressed: AtomicU8::new(0),
             joypad_released: AtomicU8::new(0),
+            tilt_keys: AtomicU8::new(0),
+            mouse_tilt: Mutex::new(None),
+            rumble_duty: Mutex::new(None),
             emu_fps: Mutex::new(0.0),
             audio_buffer_size: Mutex::new(0),
         }
@@ -149,6 +167,7 @@ fn emulation_thread(
     state: Arc<SharedState>,
     cmd_rx: Receiver<DebugCommand>,
     resp_tx: Sender<DebugResponse>,
+    rewind_config: RewindConfig,
 ) {
     let cycles_per_sample = GB_CPU_HZ as f64 / SAMPLE_RATE as f64;
     let target_frame_time = Duration::from_secs_f64(1.0 / GB_FPS);
@@ -162,6 +181,10 @@ fn emulation_thread(
     // Debugger com breakpoints
     let mut debugger = Debugger::new();
 
+    // Histórico para rewind
+    let mut rewind = RewindBuffer::new(rewind_config);
+    let mut was_rewinding = false;
+
     // Pré-buffer de áudio (~80ms)
     {
         let mut buf = state.audio_buffer.lock().unwrap();
@@ -199,8 +222,34 @@ fn emulation_thread(
             continue;
         }
 
+        // Rewind: volta um snapshot por frame enquanto a tecla estiver segurada
+        if state.rewinding.load(Ordering::Relaxed) {
+            if let Some(snapshot) = rewind.pop() {
+                if let Err(e) = cpu.load_state(&snapshot) {
+                    eprintln!("⚠️ Erro no rewind: {}", e);
+                    rewind.clear();
+                }
+                submit_frame(cpu, &state);
+            }
+            state.audio_buffer.lock().unwrap().clear();
+            was_rewinding = true;
+            frame_cycle_accum = 0;
+            apu_cycle_accum = 0.0;
+            pace_frame(frame_start, target_frame_time);
+            continue;
+        }
+
+        if was_rewinding {
+            was_rewinding = false;
+            let mut buf = state.audio_buffer.lock().unwrap();
+            for _ in 0..2048 {
+                buf.push_back((0.0, 0.0));
+            }
+        }
+
         // Processa input do joypad
         process_joypad_input(cpu, &state);
+        process_tilt_input(cpu, &state);
 
         // Roda um frame completo de emulação
         while frame_cycle_accum < CYCLES_PER_FRAME {
@@ -212,7 +261,12 @@ fn emulation_thread(
             }
 
             let (cycles, _) = cpu.execute_next();
-            let c = cycles as u64;
+            // Em velocidade dupla cada ciclo de CPU dura metade do tempo real
+            let c = if cpu.bus.cgb_speed {
+                cycles / 2
+            } else {
+                cycles
+            };
 
             frame_cycle_accum += c;
             apu_cycle_accum += c as f64;
@@ -234,7 +288,13 @@ fn emulation_thread(
 
         if cpu.bus.ppu.frame_ready {
             cpu.bus.ppu.frame_ready = false;
-            state.frame_buffer.submit_frame(&cpu.bus.ppu.framebuffer);
+            submit_frame(cpu, &state);
+        }
+
+        *state.rumble_duty.lock().unwrap() = cpu.bus.take_rumble_duty();
+
+        if rewind.frame_tick() {
+            rewind.push(cpu.save_state());
         }
 
         if fps_timer.elapsed() >= Duration::from_secs(1) {
@@ -244,21 +304,39 @@ fn emulation_thread(
             fps_timer = Instant::now();
         }
 
-        let elapsed = frame_start.elapsed();
-        if elapsed < target_frame_time {
-            let sleep_time = target_frame_time - elapsed;
-            if sleep_time > Duration::from_micros(1500) {
-                thread::sleep(sleep_time - Duration::from_micros(1000));
-            }
-            while frame_start.elapsed() < target_frame_time {
-                std::hint::spin_loop();
-            }
-        }
+        pace_frame(frame_start, target_frame_time);
     }
 
     println!("🛑 Emulation thread finalizada após {} frames", frame_count);
 }
 
+/// Envia o frame atual; no SGB monta antes a imagem com borda
+fn submit_frame(cpu: &mut CPU, state: &SharedState) {
+    match cpu.bus.sgb.as_mut() {
+        Some(sgb) => {
+            sgb.render(&cpu.bus.ppu.framebuffer);
+            state.frame_buffer.submit_frame(&sgb.framebuffer);
+        }
+        None => state
+            .frame_buffer
+            .submit_frame(&cpu.bus.ppu.rgb_framebuffer),
+    }
+}
+
+/// Espera até completar o tempo de um frame
+fn pace_frame(frame_start: Instant, target_frame_time: Duration) {
+    let elapsed = frame_start.elapsed();
+    if elapsed < target_frame_time {
+        let sleep_time = target_frame_time - elapsed;
+        if sleep_time > Duration::from_micros(1500) {
+            thread::sleep(sleep_time - Duration::from_micros(1000));
+        }
+        while frame_start.elapsed() < target_frame_time {
+            std::hint::spin_loop();
+        }
+    }
+}
+
 fn process_joypad_input(cpu: &mut CPU, state: &Arc<SharedState>) {
     let pressed = state.joypad_pressed.swap(0, Ordering::AcqRel);
     if pressed != 0 {
@@ -350,6 +428,53 @@ fn setup_audio(
     audio_device
 }
 
+/// Inclinação em g para o acelerômetro: o mouse tem prioridade sobre as setas
+fn process_tilt_input(cpu: &mut CPU, state: &Arc<SharedState>) {
+    let (x, y) = match *state.mouse_tilt.lock().unwrap() {
+        Some(tilt) => tilt,
+        None => {
+            let keys = state.tilt_keys.load(Ordering::Acquire);
+            let axis =
+                |pos: u8, neg: u8| (keys & pos != 0) as i8 as f32 - (keys & neg != 0) as i8 as f32;
+            (axis(0x01, 0x02), axis(0x08, 0x04))
+        }
+    };
+    cpu.bus.set_tilt(x, y);
+}
+
+/// Abre o primeiro gamepad conectado (para a vibração dos cartuchos com motor)
+fn open_gamepad(gamepads: &GamepadSubsystem) -> Option<Gamepad> {
+    let id = *gamepads.gamepads().ok()?.first()?;
+    gamepads.open(id).ok()
+}
+
+/// Repassa o motor ao gamepad; sem gamepad, desenha um indicador no canto
+fn present_rumble(
+    state: &SharedState,
+    gamepad: &mut Option<Gamepad>,
+    canvas: &mut sdl3::render::WindowCanvas,
+    scale: u32,
+) {
+    let Some(duty) = *state.rumble_duty.lock().unwrap() else {
+        return;
+    };
+    if let Some(pad) = gamepad.as_mut() {
+        let strength = (duty.clamp(0.0, 1.0) * u16::MAX as f32) as u16;
+        // Duração curta: se a emulação parar, o motor desliga sozinho
+        if pad.set_rumble(strength, strength, 100).is_ok() {
+            return;
+        }
+    }
+    if duty > 0.0 {
+        let size = 4 * scale;
+        canvas.set_draw_color(Color::RGB(96 + (duty * 159.0) as u8, 32, 32));
+        let _ = canvas.fill_rect(Some(
+            Rect::new(scale as i32, scale as i32, size, size).into(),
+        ));
+        canvas.set_draw_color(Color::RGB(0, 0, 0));
+    }
+}
+
 fn keycode_to_button(keycode: Keycode) -> Option<u8> {
     match keycode {
         Keycode::Right => Some(0x01),
@@ -370,7 +495,16 @@ enum InputResult {
     Debug,
 }
 
-fn handle_input(state: &Arc<SharedState>, event: &Event) -> InputResult {
+/// Posição do mouse na janela para inclinação em g (bordas = ±1 g)
+fn mouse_to_tilt(x: f32, y: f32, view: (f32, f32)) -> (f32, f32) {
+    let (w, h) = view;
+    (
+        (x / w * 2.0 - 1.0).clamp(-1.0, 1.0),
+        (y / h * 2.0 - 1.0).clamp(-1.0, 1.0),
+    )
+}
+
+fn handle_input(state: &Arc<SharedState>, event: &Event, view: (f32, f32)) -> InputResult {
     match event {
         Event::Quit { .. } => InputResult::Quit,
         Event::KeyDown {
@@ -382,6 +516,20 @@ fn handle_input(state: &Arc<SharedState>, event: &Event) -> InputResult {
             repeat: false,
             ..
         } => InputResult::Debug,
+        Event::KeyDown {
+            keycode: Some(Keycode::R),
+            ..
+        } => {
+            state.rewinding.store(true, Ordering::Relaxed);
+            InputResult::Continue
+        }
+        Event::KeyUp {
+            keycode: Some(Keycode::R),
+            ..
+        } => {
+            state.rewinding.store(false, Ordering::Relaxed);
+            InputResult::Continue
+        }
         Event::KeyDown {
             keycode: Some(k),
             repeat: false,
@@ -389,6 +537,7 @@ fn handle_input(state: &Arc<SharedState>, event: &Event) -> InputResult {
         } => {
             if let Some(button) = keycode_to_button(*k) {
                 state.joypad_pressed.fetch_or(button, Ordering::Release);
+                state.tilt_keys.fetch_or(button & 0x0F, Ordering::Release);
             }
             InputResult::Continue
         }
@@ -399,9 +548,33 @@ fn handle_input(state: &Arc<SharedState>, event: &Event) -> InputResult {
         } => {
             if let Some(button) = keycode_to_button(*k) {
                 state.joypad_released.fetch_or(button, Ordering::Release);
+                state.tilt_keys.fetch_and(!button, Ordering::Release);
             }
             InputResult::Continue
         }
+        // Mouse com o botão esquerdo: distância ao centro da janela vira inclinação
+        Event::MouseButtonDown {
+            mouse_btn: MouseButton::Left,
+            x,
+            y,
+            ..
+        } => {
+            *state.mouse_tilt.lock().unwrap() = Some(mouse_to_tilt(*x, *y, view));
+            InputResult::Continue
+        }
+        Event::MouseMotion {
+            mousestate, x, y, ..
+        } if mousestate.left() => {
+            *state.mouse_tilt.lock().unwrap() = Some(mouse_to_tilt(*x, *y, view));
+            InputResult::Continue
+        }
+        Event::MouseButtonUp {
+            mouse_btn: MouseButton::Left,
+            ..
+        } => {
+            *state.mouse_tilt.lock().unwrap() = None;
+            InputResult::Continue
+        }
         _ => InputResult::Continue,
     }
 }
@@ -410,14 +583,28 @@ fn handle_input(state: &Arc<SharedState>, event: &Event) -> InputResult {
 // ENTRY POINT
 // =============================================================================
 
-pub fn run(cpu: &mut CPU) {
+pub fn run(cpu: &mut CPU, rewind_config: RewindConfig) {
     println!("🎮 Iniciando modo gráfico SDL3 (threaded)");
-    println!("   ESC = sair | F12 = debugger");
+    println!("   ESC = sair | F12 = debugger | R (segurar) = rewind");
+    println!("   Setas ou mouse (botão esquerdo) = inclinação em cartuchos MBC7");
+    println!("   Cartuchos com motor vibram o gamepad (ou acendem um indicador no canto)");
+    println!(
+        "   Rewind: snapshot a cada {} frames, até {} MB",
+        rewind_config.interval_frames.max(1),
+        rewind_config.max_bytes / (1024 * 1024)
+    );
 
     let sdl_ctx = init_sdl().expect("Falha ao inicializar SDL3");
     let video = sdl_ctx.video().expect("Falha subsistema de vídeo");
 
-    let state = Arc::new(SharedState::new());
+    // SGB: tela 256×224 com a borda
+    let (width, height) = if cpu.bus.sgb.is_some() {
+        (SGB_WIDTH, SGB_HEIGHT)
+    } else {
+        (GB_WIDTH, GB_HEIGHT)
+    };
+
+    let state = Arc::new(SharedState::new(width * height));
     let _audio_device = setup_audio(&sdl_ctx, state.clone());
 
     // Canais para debug
@@ -426,7 +613,7 @@ pub fn run(cpu: &mut CPU) {
 
     let scale = 3u32;
     let mut window = video
-        .window("GB Emulator", 160 * scale, 144 * scale)
+        .window("GB Emulator", width as u32 * scale, height as u32 * scale)
         .position_centered()
         .build()
         .expect("Falha ao criar janela");
@@ -440,15 +627,27 @@ pub fn run(cpu: &mut CPU) {
 
     let texture_creator = canvas.texture_creator();
     let mut texture = texture_creator
-        .create_texture_streaming(sdl3::pixels::PixelFormat::RGB24, 160, 144)
+        .create_texture_streaming(
+            sdl3::pixels::PixelFormat::RGB24,
+            width as u32,
+            height as u32,
+        )
         .expect("Falha texture");
 
     let mut event_pump = sdl_ctx.event_pump().expect("Falha event pump");
 
+    // Gamepad opcional, só para a vibração
+    let gamepads = sdl_ctx.gamepad().ok();
+    let mut gamepad = gamepads.as_ref().and_then(open_gamepad);
+    let view = (
+        (width as u32 * scale) as f32,
+        (height as u32 * scale) as f32,
+    );
+
     thread::scope(|scope| {
         let state_clone = state.clone();
         let _emu_handle = scope.spawn(move || {
-            emulation_thread(cpu, state_clone, cmd_rx, resp_tx);
+            emulation_thread(cpu, state_clone, cmd_rx, resp_tx, rewind_config);
         });
 
         let mut render_frame_count: u64 = 0;
@@ -458,7 +657,18 @@ pub fn run(cpu: &mut CPU) {
             let events: Vec<_> = event_pump.poll_iter().collect();
 
             for event in events {
-                match handle_input(&state, &event) {
+                match event {
+                    Event::ControllerDeviceAdded { which, .. } if gamepad.is_none() => {
+                        gamepad = gamepads.as_ref().and_then(|g| g.open(which).ok());
+                    }
+                    Event::ControllerDeviceRemoved { .. }
+                        if gamepad.as_ref().is_some_and(|pad| !pad.connected()) =>
+                    {
+                        gamepad = gamepads.as_ref().and_then(open_gamepad);
+                    }
+                    _ => {}
+                }
+                match handle_input(&state, &event, view) {
                     InputResult::Quit => {
                         state.running.store(false, Ordering::Relaxed);
                         println!(
@@ -491,17 +701,14 @@ pub fn run(cpu: &mut CPU) {
             if let Some(framebuffer) = state.frame_buffer.get_frame() {
                 texture
                     .with_lock(None, |buf: &mut [u8], _pitch| {
-                        for i in 0..(144 * 160) {
-                            let shade = match framebuffer[i] {
-                                0 => 0xFF,
-                                1 => 0xAA,
-                                2 => 0x55,
-                                _ => 0x00,
-                            };
+                        for i in 0..(width * height) {
+                            // RGB555 → RGB24 (replica os bits altos nos baixos)
+                            let color = framebuffer[i];
+                            let expand = |c: u16| ((c << 3) | (c >> 2)) as u8;
                             let off = i * 3;
-                            buf[off] = shade;
-                            buf[off + 1] = shade;
-                            buf[off + 2] = shade;
+                            buf[off] = expand(color & 0x1F);
+                            buf[off + 1] = expand((color >> 5) & 0x1F);
+                            buf[off + 2] = expand((color >> 10) & 0x1F);
                         }
                     })
                     .unwrap();
@@ -514,9 +721,10 @@ pub fn run(cpu: &mut CPU) {
                 .copy(
                     &texture,
                     None,
-                    Some(Rect::new(0, 0, 160 * scale, 144 * scale).into()),
+                    Some(Rect::new(0, 0, width as u32 * scale, height as u32 * scale).into()),
                 )
                 .unwrap();
+            present_rumble(&state, &mut gamepad, &mut canvas, scale);
             canvas.present();
 
             if stats_timer.elapsed() >= Duration::from_secs(2) {
@@ -0,0 +1,317 @@
+//! Porta serial (FF01/FF02) e o cabo link entre dois emuladores
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::GB::CPU::CPU;
+use crate::GB::savestate::{StateReader, StateWriter};
+
+/// Internal clock: 8192 Hz = 512 ciclos de CPU por bit
+const SERIAL_CYCLES_PER_BIT: u32 = 512;
+/// Fast clock do CGB: 262144 Hz = 16 ciclos por bit
+const SERIAL_FAST_CYCLES_PER_BIT: u32 = 16;
+
+/// Uma ponta do cabo link. Os dois lados trocam um bit por pulso de clock:
+/// quem tem o clock interno gera o pulso, o outro lado só responde a ele.
+/// `now` é o relógio da porta serial em ciclos de CPU
+pub trait LinkEndpoint: Send {
+    /// Master: pulso de clock levando `bit`; devolve o bit na saída do outro lado
+    fn clock_out(&mut self, bit: bool, now: u64) -> bool;
+
+    /// Slave: próximo pulso recebido do outro lado (com o bit que veio nele)
+    fn clock_in(&mut self, now: u64) -> Option<bool>;
+
+    /// Conteúdo de SB (o MSB fica na saída) e se este lado está armado
+    /// esperando clock externo
+    fn set_output(&mut self, sb: u8, armed: bool, now: u64);
+
+    /// Chamado a cada passo da porta serial; links entre processos seguram
+    /// aqui o lado que está adiantado
+    fn sync(&mut self, _now: u64) {}
+}
+
+#[derive(Default)]
+struct Wire {
+    // Pulsos pendentes para cada lado e o bit de saída de cada lado
+    inbox: [VecDeque<bool>; 2],
+    out: [bool; 2],
+}
+
+/// Cabo entre duas instâncias no mesmo processo
+pub struct LocalLink {
+    wire: Arc<Mutex<Wire>>,
+    side: usize,
+}
+
+impl LocalLink {
+    pub fn pair() -> (LocalLink, LocalLink) {
+        let wire = Arc::new(Mutex::new(Wire {
+            // Linha em repouso fica em 1
+            out: [true, true],
+            ..Wire::default()
+        }));
+        (
+            LocalLink {
+                wire: wire.clone(),
+                side: 0,
+            },
+            LocalLink { wire, side: 1 },
+        )
+    }
+}
+
+impl LinkEndpoint for LocalLink {
+    fn clock_out(&mut self, bit: bool, _now: u64) -> bool {
+        let mut wire = self.wire.lock().unwrap();
+        let other = 1 - self.side;
+        wire.inbox[other].push_back(bit);
+        wire.out[other]
+    }
+
+    fn clock_in(&mut self, _now: u64) -> Option<bool> {
+        self.wire.lock().unwrap().inbox[self.side].pop_front()
+    }
+
+    fn set_output(&mut self, sb: u8, _armed: bool, _now: u64) {
+        self.wire.lock().unwrap().out[self.side] = sb & 0x80 != 0;
+    }
+}
+
+/// Registradores SB/SC e o shift register da porta serial
+pub struct SerialPort {
+    sb: u8,                     // FF01 - Serial Transfer Data
+    sc: u8,                     // FF02 - Serial Transfer Control
+    transfer_active: bool,      // Transferência em andamento (ou aguardando clock externo)
+    transfer_cycles: u32,       // Ciclos acumulados da transferência
+    bits_transferred: u8,       // Bits já deslocados no byte atual
+    clock_source: bool,         // true = internal clock (master), false = external clock (slave)
+    last_transmitted: u8,       // Último byte transmitido (para debug/testes)
+    clock: u64,                 // Ciclos desde que o cabo foi ligado
+    pub output_buffer: Vec<u8>, // Bytes capturados ao completar transferências
+    link: Option<Box<dyn LinkEndpoint>>,
+}
+
+impl Default for SerialPort {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SerialPort {
+    pub fn new() -> Self {
+        Self {
+            sb: 0x00,
+            sc: 0x7E, // bits não usados em 1
+            transfer_active: false,
+            transfer_cycles: 0,
+            bits_transferred: 0,
+            clock_source: false,
+            last_transmitted: 0x00,
+            clock: 0,
+            output_buffer: Vec::new(),
+            link: None,
+        }
+    }
+
+    /// Conecta o cabo (None desconecta: o master passa a receber 0xFF)
+    pub fn set_link(&mut self, link: Option<Box<dyn LinkEndpoint>>) {
+        self.link = link;
+        self.clock = 0;
+        self.publish_output();
+    }
+
+    pub fn read_sb(&self) -> u8 {
+        self.sb
+    }
+
+    /// Bit 7: Transfer Start Flag; bits 2-6 leem 1; bit 1 (clock speed) só
+    /// existe no CGB e lê 1 no DMG; bit 0: clock source
+    pub fn read_sc(&self, cgb_mode: bool) -> u8 {
+        let transfer_flag = if self.transfer_active { 0x80 } else { 0x00 };
+        let speed_bit = if cgb_mode { self.sc & 0x02 } else { 0x02 };
+        transfer_flag | 0x7C | speed_bit | (self.clock_source as u8)
+    }
+
+    pub fn write_sb(&mut self, value: u8) {
+        // SB pode ser escrito mesmo durante transferência (mas não é recomendado)
+        self.sb = value;
+        // Guarda o último byte escrito para uso em testes/debug
+        if !self.transfer_active {
+            self.last_transmitted = value;
+        }
+        self.publish_output();
+    }
+
+    /// `div_counter` alinha a fase do clock interno ao divisor do sistema
+    pub fn write_sc(&mut self, value: u8, cgb_mode: bool, div_counter: u16) {
+        let old_transfer_start = (self.sc & 0x80) != 0;
+        let new_transfer_start = (value & 0x80) != 0;
+
+        self.clock_source = (value & 0x01) != 0;
+        let mask = if cgb_mode { 0b1000_0011 } else { 0b1000_0001 };
+        self.sc = value & mask;
+
+        // Inicia transferência se bit 7 mudou de 0 para 1
+        if !old_transfer_start && new_transfer_start {
+            self.start_transfer(cgb_mode, div_counter);
+        } else if !new_transfer_start {
+            self.transfer_active = false;
+        }
+        self.publish_output();
+    }
+
+    fn start_transfer(&mut self, cgb_mode: bool, div_counter: u16) {
+        self.transfer_active = true;
+        self.bits_transferred = 0;
+        // Guarda o byte que será transmitido
+        self.last_transmitted = self.sb;
+        if self.clock_source {
+            // Fase inicial alinhada ao clock do bit (512 ou 16 ciclos)
+            let phase_mask = self.cycles_per_bit(cgb_mode) as u16 - 1;
+            self.transfer_cycles = (div_counter & phase_mask) as u32;
+        }
+    }
+
+    /// SC bit 1 no CGB: clock interno 32x mais rápido
+    fn cycles_per_bit(&self, cgb_mode: bool) -> u32 {
+        if cgb_mode && (self.sc & 0x02) != 0 {
+            SERIAL_FAST_CYCLES_PER_BIT
+        } else {
+            SERIAL_CYCLES_PER_BIT
+        }
+    }
+
+    fn publish_output(&mut self) {
+        let armed = self.transfer_active && !self.clock_source;
+        if let Some(link) = self.link.as_mut() {
+            link.set_output(self.sb, armed, self.clock);
+        }
+    }
+
+    /// Desloca um bit: sai o MSB de SB, entra `bit_in` no LSB.
+    /// Devolve true quando o byte termina (pede a interrupção serial)
+    fn shift(&mut self, bit_in: bool) -> bool {
+        self.sb = (self.sb << 1) | bit_in as u8;
+        self.bits_transferred += 1;
+        let done = self.bits_transferred == 8;
+        if done {
+            self.complete_transfer();
+        }
+        self.publish_output();
+        done
+    }
+
+    /// Avança a transferência. Internal clock: um pulso a cada 512 ciclos
+    /// (16 no fast clock do CGB), levando o MSB ao cabo; external clock:
+    /// consome os pulsos que o outro lado mandou. Devolve true ao pedir a
+    /// interrupção serial
+    pub fn step(&mut self, cycles: u32, cgb_mode: bool) -> bool {
+        self.clock += cycles as u64;
+        if let Some(link) = self.link.as_mut() {
+            link.sync(self.clock);
+        }
+        if !self.clock_source {
+            return self.step_external();
+        }
+        if !self.transfer_active {
+            return false;
+        }
+
+        let cycles_per_bit = self.cycles_per_bit(cgb_mode);
+        self.transfer_cycles = self.transfer_cycles.saturating_add(cycles);
+        let mut irq = false;
+        while self.transfer_active
+            && self.transfer_cycles >= (self.bits_transferred as u32 + 1) * cycles_per_bit
+        {
+            let bit_out = self.sb & 0x80 != 0;
+            // Sem cabo a linha de entrada fica em 1
+            // Instante exato do pulso dentro deste passo
+            let late = self.transfer_cycles - (self.bits_transferred as u32 + 1) * cycles_per_bit;
+            let now = self.clock.saturating_sub(late as u64);
+            let bit_in = match self.link.as_mut() {
+                Some(link) => link.clock_out(bit_out, now),
+                None => true,
+            };
+            irq |= self.shift(bit_in);
+        }
+        irq
+    }
+
+    fn step_external(&mut self) -> bool {
+        let mut irq = false;
+        let now = self.clock;
+        while let Some(bit_in) = self.link.as_mut().and_then(|link| link.clock_in(now)) {
+            // Pulsos com a transferência parada não deslocam nada
+            if self.transfer_active {
+                irq |= self.shift(bit_in);
+            }
+        }
+        irq
+    }
+
+    /// Completa a transferência: SB já tem o byte recebido
+    fn complete_transfer(&mut self) {
+        // Reset do bit 7 (Transfer Start Flag)
+        self.sc &= !0x80;
+        self.transfer_active = false;
+        self.transfer_cycles = 0;
+        self.bits_transferred = 0;
+
+        // Captura o byte transmitido no buffer antes que o ROM possa limpar IF
+        self.output_buffer.push(self.last_transmitted);
+    }
+
+    pub fn save_state(&self, w: &mut StateWriter) {
+        w.write_u8(self.sb);
+        w.write_u8(self.sc);
+        w.write_bool(self.transfer_active);
+        w.write_u32(self.transfer_cycles);
+        w.write_u8(self.bits_transferred);
+        w.write_bool(self.clock_source);
+        w.write_u8(self.last_transmitted);
+    }
+
+    pub fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.sb = r.read_u8()?;
+        self.sc = r.read_u8()?;
+        self.transfer_active = r.read_bool()?;
+        self.transfer_cycles = r.read_u32()?;
+        self.bits_transferred = r.read_u8()? & 0x07;
+        self.clock_source = r.read_bool()?;
+        self.last_transmitted = r.read_u8()?;
+        self.publish_output();
+        Ok(())
+    }
+}
+
+/// Liga duas CPUs com um `LocalLink`
+pub fn connect(a: &mut CPU, b: &mut CPU) {
+    let (link_a, link_b) = LocalLink::pair();
+    a.bus.set_link_cable(Some(Box::new(link_a)));
+    b.bus.set_link_cable(Some(Box::new(link_b)));
+}
+
+/// Roda duas CPUs ligadas em lockstep por `cycles` ciclos de 4MHz, sempre
+/// executando uma instrução na que está atrasada. Assim o slave processa
+/// cada pulso antes do próximo pulso do master
+pub fn run_linked(a: &mut CPU, b: &mut CPU, cycles: u64) {
+    run_lockstep(&mut [a, b], cycles, |_| {});
+}
+
+/// Escalonador dos cabos locais: executa uma instrução por vez na CPU mais
+/// atrasada (empate: a primeira) até todas passarem de `cycles` ciclos de
+/// 4MHz. `sync` recebe o tempo da mais atrasada antes de cada instrução e
+/// `cycles` no fim, para quem dá o clock de fora (adaptador de 4 jogadores)
+pub fn run_lockstep(cpus: &mut [&mut CPU], cycles: u64, mut sync: impl FnMut(u64)) {
+    let mut times = vec![0u64; cpus.len()];
+    while let Some((i, &min)) = times.iter().enumerate().min_by_key(|&(_, t)| *t)
+        && min < cycles
+    {
+        sync(min);
+        let cpu = &mut *cpus[i];
+        let (c, _) = cpu.execute_next();
+        // Em velocidade dupla cada ciclo de CPU dura metade do tempo real
+        times[i] += if cpu.bus.cgb_speed { c / 2 } else { c };
+    }
+    sync(cycles);
+}
@@ -0,0 +1,582 @@
+//! Super Game Boy: pacotes de comando via P1, paletas, atributos, borda e multiplayer
+//!
+//! O jogo envia pacotes de 16 bytes pulsando P14/P15 em FF00: um pulso com
+//! os dois em 0 inicia o pacote, P14=0 é um bit 0, P15=0 é um bit 1 (LSB
+//! primeiro) e cada pulso volta para P14=P15=1. Depois de 128 bits vem um
+//! bit 0 de parada. O primeiro byte traz o comando (bits 7-3) e quantos
+//! pacotes ele ocupa (bits 2-0).
+//!
+//! A saída é uma imagem 256×224: a borda do SNES com a tela do Game Boy
+//! colorida no centro (48, 40).
+
+use crate::GB::PPU::PPU;
+use crate::GB::savestate::{StateReader, StateWriter};
+
+pub const SGB_WIDTH: usize = 256;
+pub const SGB_HEIGHT: usize = 224;
+
+const GAME_X: usize = 48;
+const GAME_Y: usize = 40;
+
+const PACKET_BITS: usize = 16 * 8;
+const MAX_PACKETS: usize = 7;
+
+// Tamanhos das transferências pela VRAM (4KB cada)
+const SYSTEM_PALETTES_SIZE: usize = 512 * 4 * 2;
+const ATTR_FILES: usize = 45;
+const ATTR_FILE_SIZE: usize = 90;
+const BORDER_TILES_SIZE: usize = 256 * 32;
+const BORDER_DATA_SIZE: usize = 0x880; // mapa 32×28 (0x700) + paletas 4-7 em 0x800
+
+/// Paleta padrão do SGB (1-A) antes de qualquer comando
+const DEFAULT_PALETTE: [u16; 4] = [0x67BF, 0x265B, 0x10B5, 0x2866];
+
+// Comandos implementados
+const PAL01: u8 = 0x00;
+const PAL23: u8 = 0x01;
+const PAL03: u8 = 0x02;
+const PAL12: u8 = 0x03;
+const ATTR_BLK: u8 = 0x04;
+const ATTR_LIN: u8 = 0x05;
+const ATTR_DIV: u8 = 0x06;
+const ATTR_CHR: u8 = 0x07;
+const PAL_SET: u8 = 0x0A;
+const PAL_TRN: u8 = 0x0B;
+const MLT_REQ: u8 = 0x11;
+const CHR_TRN: u8 = 0x13;
+const PCT_TRN: u8 = 0x14;
+const ATTR_TRN: u8 = 0x15;
+const ATTR_SET: u8 = 0x16;
+const MASK_EN: u8 = 0x17;
+
+/// MASK_EN: o que aparece no lugar da tela do jogo
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScreenMask {
+    Cancel,
+    Freeze,
+    Black,
+    Color0,
+}
+
+impl ScreenMask {
+    fn from_u8(value: u8) -> Self {
+        match value & 0x03 {
+            1 => ScreenMask::Freeze,
+            2 => ScreenMask::Black,
+            3 => ScreenMask::Color0,
+            _ => ScreenMask::Cancel,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            ScreenMask::Cancel => 0,
+            ScreenMask::Freeze => 1,
+            ScreenMask::Black => 2,
+            ScreenMask::Color0 => 3,
+        }
+    }
+}
+
+pub struct Sgb {
+    // ===== Recepção de pacotes =====
+    command: [u8; 16 * MAX_PACKETS],
+    write_index: usize, // bits recebidos
+    ready_for_pulse: bool,
+    ready_for_write: bool,
+    ready_for_stop: bool,
+    last_p1: u8,
+
+    // ===== Multiplayer (MLT_REQ) =====
+    player_count: u8,
+    current_player: u8,
+
+    // ===== Cores =====
+    /// Paletas 0-3 usadas na tela (a cor 0 é compartilhada)
+    pub palettes: [[u16; 4]; 4],
+    system_palettes: Vec<u8>,
+    attr_files: Vec<u8>,
+    /// Paleta (0-3) de cada célula 8×8 da tela (20×18)
+    pub attr_map: [u8; 20 * 18],
+    pub mask: ScreenMask,
+
+    // ===== Borda =====
+    border_tiles: Vec<u8>,
+    border_data: Vec<u8>,
+
+    game_layer: Vec<u16>,
+    /// Imagem final 256×224 em RGB555
+    pub framebuffer: Vec<u16>,
+}
+
+impl Default for Sgb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sgb {
+    pub fn new() -> Self {
+        Self {
+            command: [0; 16 * MAX_PACKETS],
+            write_index: 0,
+            ready_for_pulse: false,
+            ready_for_write: false,
+            ready_for_stop: false,
+            last_p1: 0x30,
+            player_count: 1,
+            current_player: 0,
+            palettes: [DEFAULT_PALETTE; 4],
+            system_palettes: vec![0; SYSTEM_PALETTES_SIZE],
+            attr_files: vec![0; ATTR_FILES * ATTR_FILE_SIZE],
+            attr_map: [0; 20 * 18],
+            mask: ScreenMask::Cancel,
+            border_tiles: vec![0; BORDER_TILES_SIZE],
+            border_data: vec![0; BORDER_DATA_SIZE],
+            game_layer: vec![0; 160 * 144],
+            framebuffer: vec![0; SGB_WIDTH * SGB_HEIGHT],
+        }
+    }
+
+    pub fn player_count(&self) -> u8 {
+        self.player_count
+    }
+
+    pub fn current_player(&self) -> u8 {
+        self.current_player
+    }
+
+    // ========== P1 (FF00) ==========
+
+    /// Escrita em P1: avança o controle atual no multiplayer e decodifica
+    /// os pulsos de pacote. `ppu` é usado pelas transferências via VRAM.
+    pub fn write_p1(&mut self, value: u8, ppu: &PPU) {
+        let old = self.last_p1;
+        self.last_p1 = value & 0x30;
+
+        // P15 subindo troca o controle lido no modo multiplayer
+        if value & 0x20 != 0 && old & 0x20 == 0 && self.player_count > 1 {
+            self.current_player = (self.current_player + 1) & (self.player_count - 1);
+        }
+
+        let packets = match self.command[0] & 0x07 {
+            0 => 1,
+            n => n as usize,
+        };
+        let command_bits = packets * PACKET_BITS;
+
+        match (value >> 4) & 0x03 {
+            // P14=P15=1: fim do pulso
+            3 => self.ready_for_pulse = true,
+            // P14=0: bit 0 (ou bit de parada)
+            2 => {
+                if !self.ready_for_pulse || !self.ready_for_write {
+                    return;
+                }
+                if self.ready_for_stop {
+                    if self.write_index == command_bits {
+                        self.execute(ppu);
+                        self.write_index = 0;
+                        self.command.fill(0);
+                    }
+                    self.ready_for_pulse = false;
+                    self.ready_for_write = false;
+                    self.ready_for_stop = false;
+                } else {
+                    self.push_bit(false);
+                }
+            }
+            // P15=0: bit 1
+            1 => {
+                if !self.ready_for_pulse || !self.ready_for_write {
+                    return;
+                }
+                if self.ready_for_stop {
+                    // Bit de parada precisa ser 0: pacote corrompido
+                    self.ready_for_pulse = false;
+                    self.ready_for_write = false;
+                    self.write_index = 0;
+                    self.command.fill(0);
+                } else {
+                    self.push_bit(true);
+                }
+            }
+            // P14=P15=0: reset, início de pacote
+            _ => {
+                if !self.ready_for_pulse {
+                    return;
+                }
+                self.ready_for_write = true;
+                self.ready_for_pulse = false;
+                // Um reset no meio de um pacote descarta o comando
+                if !self.write_index.is_multiple_of(PACKET_BITS)
+                    || self.write_index == 0
+                    || self.ready_for_stop
+                {
+                    self.write_index = 0;
+                    self.command.fill(0);
+                    self.ready_for_stop = false;
+                }
+            }
+        }
+    }
+
+    fn push_bit(&mut self, bit: bool) {
+        if self.write_index >= self.command.len() * 8 {
+            return;
+        }
+        if bit {
+            self.command[self.write_index / 8] |= 1 << (self.write_index % 8);
+        }
+        self.write_index += 1;
+        self.ready_for_pulse = false;
+        if self.write_index.is_multiple_of(PACKET_BITS) {
+            self.ready_for_stop = true;
+        }
+    }
+
+    /// Leitura de P1: com MLT_REQ ativo e nenhum grupo selecionado, os
+    /// bits 0-3 trazem o controle atual (0xF = jogador 1, 0xE = jogador 2...).
+    /// Só o jogador 1 tem botões; os demais leem tudo solto.
+    pub fn read_p1(&self, value: u8) -> u8 {
+        if self.player_count == 1 {
+            return value;
+        }
+        if value & 0x30 == 0x30 {
+            (value & 0xF0) | (0x0F - self.current_player)
+        } else if self.current_player != 0 {
+            value | 0x0F
+        } else {
+            value
+        }
+    }
+
+    // ========== COMANDOS ==========
+
+    fn execute(&mut self, ppu: &PPU) {
+        let data = self.command;
+        match data[0] >> 3 {
+            PAL01 => self.set_palette_pair(&data, 0, 1),
+            PAL23 => self.set_palette_pair(&data, 2, 3),
+            PAL03 => self.set_palette_pair(&data, 0, 3),
+            PAL12 => self.set_palette_pair(&data, 1, 2),
+            ATTR_BLK => self.attr_blk(&data),
+            ATTR_LIN => self.attr_lin(&data),
+            ATTR_DIV => self.attr_div(&data),
+            ATTR_CHR => self.attr_chr(&data),
+            PAL_SET => self.pal_set(&data),
+            PAL_TRN => self.system_palettes = vram_transfer(ppu),
+            MLT_REQ => {
+                self.player_count = match data[1] & 0x03 {
+                    1 => 2,
+                    3 => 4,
+                    _ => 1,
+                };
+                self.current_player = 0;
+            }
+            CHR_TRN => {
+                let offset = if data[1] & 0x01 != 0 { 0x1000 } else { 0 };
+                self.border_tiles[offset..offset + 0x1000].copy_from_slice(&vram_transfer(ppu));
+            }
+            PCT_TRN => {
+                self.border_data
+                    .copy_from_slice(&vram_transfer(ppu)[..BORDER_DATA_SIZE]);
+            }
+            ATTR_TRN => {
+                let size = self.attr_files.len();
+                self.attr_files.copy_from_slice(&vram_transfer(ppu)[..size]);
+            }
+            ATTR_SET => {
+                self.apply_attr_file(data[1] & 0x3F);
+                if data[1] & 0x40 != 0 {
+                    self.mask = ScreenMask::Cancel;
+                }
+            }
+            MASK_EN => self.mask = ScreenMask::from_u8(data[1]),
+            _ => {}
+        }
+    }
+
+    fn set_palette_pair(&mut self, data: &[u8], a: usize, b: usize) {
+        let color = |i: usize| u16::from_le_bytes([data[i], data[i + 1]]);
+        let color0 = color(1);
+        for palette in self.palettes.iter_mut() {
+            palette[0] = color0;
+        }
+        for c in 1..4 {
+            self.palettes[a][c] = color(1 + c * 2);
+            self.palettes[b][c] = color(7 + c * 2);
+        }
+    }
+
+    fn attr_blk(&mut self, data: &[u8]) {
+        let sets = (data[1] as usize).min(18);
+        for set in data[2..].chunks(6).take(sets) {
+            let control = set[0] & 0x07;
+            let inside = set[1] & 0x03;
+            let mut border = (set[1] >> 2) & 0x03;
+            let outside = (set[1] >> 4) & 0x03;
+            // Só dentro ou só fora: a linha da borda acompanha
+            match control {
+                0x01 => border = inside,
+                0x04 => border = outside,
+                _ => {}
+            }
+            let (x1, y1) = (set[2] & 0x1F, set[3] & 0x1F);
+            let (x2, y2) = (set[4] & 0x1F, set[5] & 0x1F);
+
+            for y in 0..18u8 {
+                for x in 0..20u8 {
+                    let in_x = x > x1 && x < x2;
+                    let in_y = y > y1 && y < y2;
+                    let on_x = x >= x1 && x <= x2;
+                    let on_y = y >= y1 && y <= y2;
+                    let palette = if in_x && in_y {
+                        (control & 0x01 != 0).then_some(inside)
+                    } else if on_x && on_y {
+                        // 0x01 e 0x04 já trocaram `border` acima; 0x05
+                        // (dentro e fora, sem borda) não toca na linha
+                        (control & 0x02 != 0 || control == 0x01 || control == 0x04)
+                            .then_some(border)
+                    } else {
+                        (control & 0x04 != 0).then_some(outside)
+                    };
+                    if let Some(p) = palette {
+                        self.attr_map[y as usize * 20 + x as usize] = p;
+                    }
+                }
+            }
+        }
+    }
+
+    fn attr_lin(&mut self, data: &[u8]) {
+        let count = data[1] as usize;
+        for &line in data[2..].iter().take(count) {
+            let index = (line & 0x1F) as usize;
+            let palette = (line >> 5) & 0x03;
+            if line & 0x80 != 0 {
+                // Linha horizontal
+                if index < 18 {
+                    self.attr_map[index * 20..index * 20 + 20].fill(palette);
+                }
+            } else if index < 20 {
+                for y in 0..18 {
+                    self.attr_map[y * 20 + index] = palette;
+                }
+            }
+        }
+    }
+
+    fn attr_div(&mut self, data: &[u8]) {
+        let after = data[1] & 0x03;
+        let before = (data[1] >> 2) & 0x03;
+        let on_line = (data[1] >> 4) & 0x03;
+        let horizontal = data[1] & 0x40 != 0;
+        let split = data[2] as usize;
+        for y in 0..18 {
+            for x in 0..20 {
+                let pos = if horizontal { y } else { x };
+                self.attr_map[y * 20 + x] = match pos.cmp(&split) {
+                    std::cmp::Ordering::Less => before,
+                    std::cmp::Ordering::Equal => on_line,
+                    std::cmp::Ordering::Greater => after,
+                };
+            }
+        }
+    }
+
+    fn attr_chr(&mut self, data: &[u8]) {
+        let mut x = (data[1] as usize).min(19);
+        let mut y = (data[2] as usize).min(17);
+        let count = (u16::from_le_bytes([data[3], data[4]]) as usize).min(360);
+        let vertical = data[5] & 0x01 != 0;
+        for i in 0..count {
+            let Some(&byte) = data.get(6 + i / 4) else {
+                break;
+            };
+            let palette = (byte >> (6 - (i % 4) * 2)) & 0x03;
+            self.attr_map[y * 20 + x] = palette;
+            if vertical {
+                y += 1;
+                if y == 18 {
+                    y = 0;
+                    x = (x + 1) % 20;
+                }
+            } else {
+                x += 1;
+                if x == 20 {
+                    x = 0;
+                    y = (y + 1) % 18;
+                }
+            }
+        }
+    }
+
+    fn pal_set(&mut self, data: &[u8]) {
+        for p in 0..4 {
+            let index = (u16::from_le_bytes([data[1 + p * 2], data[2 + p * 2]]) & 0x1FF) as usize;
+            for c in 0..4 {
+                let offset = index * 8 + c * 2;
+                self.palettes[p][c] = u16::from_le_bytes([
+                    self.system_palettes[offset],
+                    self.system_palettes[offset + 1],
+                ]);
+            }
+        }
+        // A cor 0 da paleta 0 vale para todas
+        let color0 = self.palettes[0][0];
+        for palette in self.palettes.iter_mut() {
+            palette[0] = color0;
+        }
+        if data[9] & 0x80 != 0 {
+            self.apply_attr_file(data[9] & 0x3F);
+        }
+        if data[9] & 0x40 != 0 {
+            self.mask = ScreenMask::Cancel;
+        }
+    }
+
+    fn apply_attr_file(&mut self, file: u8) {
+        let file = file as usize;
+        if file >= ATTR_FILES {
+            return;
+        }
+        let atf = &self.attr_files[file * ATTR_FILE_SIZE..(file + 1) * ATTR_FILE_SIZE];
+        for (cell, slot) in self.attr_map.iter_mut().enumerate() {
+            *slot = (atf[cell / 4] >> (6 - (cell % 4) * 2)) & 0x03;
+        }
+    }
+
+    // ========== SAÍDA 256×224 ==========
+
+    /// Monta a imagem final a partir dos shades (0-3) do PPU
+    pub fn render(&mut self, game: &[u8]) {
+        let backdrop = self.palettes[0][0];
+        match self.mask {
+            ScreenMask::Cancel => {
+                for (i, pixel) in self.game_layer.iter_mut().enumerate() {
+                    let cell = (i / 160 / 8) * 20 + (i % 160) / 8;
+                    let palette = self.attr_map[cell] as usize;
+                    *pixel = self.palettes[palette][(game[i] & 0x03) as usize];
+                }
+            }
+            ScreenMask::Freeze => {}
+            ScreenMask::Black => self.game_layer.fill(0x0000),
+            ScreenMask::Color0 => self.game_layer.fill(backdrop),
+        }
+
+        self.framebuffer.fill(backdrop);
+        for y in 0..144 {
+            let dst = (GAME_Y + y) * SGB_WIDTH + GAME_X;
+            self.framebuffer[dst..dst + 160]
+                .copy_from_slice(&self.game_layer[y * 160..y * 160 + 160]);
+        }
+        self.render_border();
+    }
+
+    /// Borda: tiles 4bpp do SNES, mapa 32×28, paletas 4-7 (cor 0 transparente)
+    fn render_border(&mut self) {
+        for ty in 0..28 {
+            for tx in 0..32 {
+                let entry_offset = (ty * 32 + tx) * 2;
+                let entry = u16::from_le_bytes([
+                    self.border_data[entry_offset],
+                    self.border_data[entry_offset + 1],
+                ]);
+                let tile = (entry & 0xFF) as usize * 32;
+                let palette = ((entry >> 10) & 0x03) as usize;
+                let flip_x = entry & 0x4000 != 0;
+                let flip_y = entry & 0x8000 != 0;
+
+                for row in 0..8 {
+                    let src_row = if flip_y { 7 - row } else { row };
+                    let planes = [
+                        self.border_tiles[tile + src_row * 2],
+                        self.border_tiles[tile + src_row * 2 + 1],
+                        self.border_tiles[tile + 16 + src_row * 2],
+                        self.border_tiles[tile + 16 + src_row * 2 + 1],
+                    ];
+                    for col in 0..8 {
+                        let bit = if flip_x { col } else { 7 - col };
+                        let color = planes.iter().enumerate().fold(0usize, |acc, (p, plane)| {
+                            acc | ((((plane >> bit) & 1) as usize) << p)
+                        });
+                        if color == 0 {
+                            continue;
+                        }
+                        let offset = 0x800 + palette * 32 + color * 2;
+                        let rgb = u16::from_le_bytes([
+                            self.border_data[offset],
+                            self.border_data[offset + 1],
+                        ]);
+                        self.framebuffer[(ty * 8 + row) * SGB_WIDTH + tx * 8 + col] = rgb;
+                    }
+                }
+            }
+        }
+    }
+
+    // ========== SAVE STATE ==========
+
+    pub fn save_state(&self, w: &mut StateWriter) {
+        w.write_bytes(&self.command);
+        w.write_u32(self.write_index as u32);
+        w.write_bool(self.ready_for_pulse);
+        w.write_bool(self.ready_for_write);
+        w.write_bool(self.ready_for_stop);
+        w.write_u8(self.last_p1);
+        w.write_u8(self.player_count);
+        w.write_u8(self.current_player);
+        for palette in &self.palettes {
+            for &color in palette {
+                w.write_u16(color);
+            }
+        }
+        w.write_vec(&self.system_palettes);
+        w.write_vec(&self.attr_files);
+        w.write_bytes(&self.attr_map);
+        w.write_u8(self.mask.to_u8());
+        w.write_vec(&self.border_tiles);
+        w.write_vec(&self.border_data);
+    }
+
+    pub fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        r.read_bytes(&mut self.command)?;
+        self.write_index = r.read_u32()? as usize;
+        self.ready_for_pulse = r.read_bool()?;
+        self.ready_for_write = r.read_bool()?;
+        self.ready_for_stop = r.read_bool()?;
+        self.last_p1 = r.read_u8()?;
+        self.player_count = r.read_u8()?;
+        self.current_player = r.read_u8()?;
+        for palette in self.palettes.iter_mut() {
+            for color in palette.iter_mut() {
+                *color = r.read_u16()?;
+            }
+        }
+        r.read_vec_into(&mut self.system_palettes)?;
+        r.read_vec_into(&mut self.attr_files)?;
+        r.read_bytes(&mut self.attr_map)?;
+        self.mask = ScreenMask::from_u8(r.read_u8()?);
+        r.read_vec_into(&mut self.border_tiles)?;
+        r.read_vec_into(&mut self.border_data)?;
+        Ok(())
+    }
+}
+
+/// Transferência pela VRAM: o SGB lê os 256 primeiros tiles mostrados no
+/// mapa do BG (20 por linha), 16 bytes cada, totalizando 4KB.
+fn vram_transfer(ppu: &PPU) -> Vec<u8> {
+    let map_base = if ppu.lcdc & 0x08 != 0 { 0x1C00 } else { 0x1800 };
+    let mut out = Vec::with_capacity(0x1000);
+    for i in 0..256 {
+        let tile = ppu.vram[map_base + (i / 20) * 32 + i % 20];
+        let addr = if ppu.lcdc & 0x10 != 0 {
+            tile as usize * 16
+        } else {
+            (0x1000 + (tile as i8 as i32) * 16) as usize
+        };
+        out.extend_from_slice(&ppu.vram[addr..addr + 16]);
+    }
+    out
+}
@@ -1,9 +1,35 @@
 //! Módulo para execução de ROMs de teste (Blargg, Mooneye, etc)
 //! Suporta saída via serial (FF01/FF02) e memória ($A000)
 
-use crate::GB::CPU::CPU;
+use crate::GB::CPU::{BootModel, CPU};
 use std::io::{self, Write};
 
+/// Modelo pelo sufixo do nome da ROM (convenção do Mooneye: `-dmg0`, `-mgb`,
+/// `-S`, `-C`...). Sem sufixo, ROMs CGB-compatible ficam no caminho DMG e só
+/// as CGB-only rodam como CGB.
+pub fn infer_model_from_path(rom_path: &str, rom: &[u8]) -> BootModel {
+    let lower = rom_path.to_ascii_lowercase();
+    if lower.contains("-dmg0") {
+        BootModel::Dmg0
+    } else if lower.contains("-mgb") {
+        BootModel::Mgb
+    } else if lower.contains("-sgb2") || lower.contains("boot_div2-s") {
+        BootModel::Sgb2
+    } else if lower.contains("-sgb") || lower.ends_with("-s.gb") {
+        BootModel::Sgb
+    } else if lower.contains("-cgb0") {
+        BootModel::Cgb0
+    } else if lower.contains("-cgb") {
+        BootModel::Cgb
+    } else if lower.contains("-a.gb") {
+        BootModel::Agb
+    } else if lower.ends_with("-c.gb") || crate::GB::cartridge::is_cgb_only_rom(rom) {
+        BootModel::Cgb
+    } else {
+        BootModel::DmgAbc
+    }
+}
+
 /// Resultado de um teste
 #[derive(Debug)]
 pub enum TestResult {
@@ -101,11 +127,11 @@ impl BlarggMemoryOutput {
 }
 
 fn drain_serial_output(cpu: &mut CPU, serial_output: &mut String) -> bool {
-    if cpu.bus.serial_output_buffer.is_empty() {
+    if cpu.bus.serial.output_buffer.is_empty() {
         return false;
     }
 
-    let bytes: Vec<u8> = cpu.bus.serial_output_buffer.drain(..).collect();
+    let bytes: Vec<u8> = cpu.bus.serial.output_buffer.drain(..).collect();
     for byte in bytes {
         if (0x20..=0x7E).contains(&byte) || byte == b'\n' || byte == b'\r' {
             serial_output.push(byte as char);
@@ -305,3 +331,62 @@ pub fn run(cpu: &mut CPU) -> TestResult {
 
     TestResult::Timeout
 }
+
+/// Tons de cinza das telas de referência DMG (cor 0 a 3)
+const REFERENCE_SHADES: [u8; 4] = [0xFF, 0xAA, 0x55, 0x00];
+
+/// Testes de tela (dmg-acid2, Mealybug Tearoom): roda até o `LD B,B` que
+/// marca o fim e compara o framebuffer DMG com a referência em PGM 160x144
+/// (cinza arredondado para o tom mais próximo)
+pub fn run_screenshot(cpu: &mut CPU, reference_pgm: &[u8]) -> TestResult {
+    const MAX_INSTRUCTIONS: u64 = 100_000_000;
+
+    let reference = match crate::GB::mbc::camera::parse_pgm(reference_pgm) {
+        Ok((160, 144, pixels)) => pixels,
+        Ok((w, h, _)) => {
+            eprintln!("Referência {}x{}, esperado 160x144", w, h);
+            return TestResult::Failed(0xFF);
+        }
+        Err(e) => {
+            eprintln!("Referência inválida: {}", e);
+            return TestResult::Failed(0xFF);
+        }
+    };
+
+    cpu.bus.ppu.headless = false;
+    let mut finished = false;
+    for _ in 0..MAX_INSTRUCTIONS {
+        if cpu.bus.read(cpu.registers.get_pc()) == 0x40 {
+            finished = true;
+            break;
+        }
+        if cpu.execute_next().0 == 0 {
+            break;
+        }
+    }
+    if !finished {
+        return TestResult::Timeout;
+    }
+
+    let mismatches = cpu
+        .bus
+        .ppu
+        .framebuffer
+        .iter()
+        .zip(&reference)
+        .filter(|&(&shade, &gray)| {
+            let expected = REFERENCE_SHADES
+                .iter()
+                .enumerate()
+                .min_by_key(|&(_, &s)| s.abs_diff(gray))
+                .map_or(0, |(i, _)| i as u8);
+            shade & 0x03 != expected
+        })
+        .count();
+    if mismatches == 0 {
+        TestResult::Passed
+    } else {
+        println!("{} pixels diferentes da referência", mismatches);
+        TestResult::Failed(1)
+    }
+}
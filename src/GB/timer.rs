@@ -1,3 +1,5 @@
+use crate::GB::savestate::{StateReader, StateWriter};
+
 /// Emula o timer/divisor do Game Boy
 /// Implementação do timer seguindo o comportamento do hardware Game Boy
 /// Ref: https://gbdev.io/pandocs/Timer_Obscure_Behaviour.html
@@ -335,4 +337,31 @@ impl Timer {
     pub fn notify_tma_write(&mut self, new_tma: u8) {
         self.tma_reg = new_tma;
     }
+
+    pub fn save_state(&self, w: &mut StateWriter) {
+        w.write_u16(self.div_counter);
+        w.write_bool(self.last_div_bit);
+        w.write_u32(self.m_cycle_offset);
+        w.write_bool(self.tima_written_in_delay);
+        w.write_opt_u16(self.suppress_until);
+        w.write_bool(self.prev_tima_bit);
+        w.write_opt_u16(self.reload_pending);
+        w.write_bool(self.tima_reloading);
+        w.write_opt_u16(self.tima_reloaded_until);
+        w.write_u8(self.tma_reg);
+    }
+
+    pub fn load_state(&mut self, r: &mut StateReader) -> Result<(), String> {
+        self.div_counter = r.read_u16()?;
+        self.last_div_bit = r.read_bool()?;
+        self.m_cycle_offset = r.read_u32()?;
+        self.tima_written_in_delay = r.read_bool()?;
+        self.suppress_until = r.read_opt_u16()?;
+        self.prev_tima_bit = r.read_bool()?;
+        self.reload_pending = r.read_opt_u16()?;
+        self.tima_reloading = r.read_bool()?;
+        self.tima_reloaded_until = r.read_opt_u16()?;
+        self.tma_reg = r.read_u8()?;
+        Ok(())
+    }
 }
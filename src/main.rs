@@ -4,29 +4,46 @@ use gb_emu::GB;
 use std::env;
 use std::fs;
 
-fn infer_boot_model(rom_path: &str, cpu: &GB::CPU::CPU) -> GB::CPU::BootModel {
-    let lower = rom_path.to_ascii_lowercase();
-    if lower.contains("-dmg0") {
-        GB::CPU::BootModel::Dmg0
-    } else if lower.contains("-mgb") {
-        GB::CPU::BootModel::Mgb
-    } else if lower.contains("-sgb2") || lower.contains("boot_div2-s") {
-        GB::CPU::BootModel::Sgb2
-    } else if lower.contains("-sgb") || lower.ends_with("-s.gb") {
-        GB::CPU::BootModel::Sgb
-    } else if lower.contains("-cgb0") {
-        GB::CPU::BootModel::Cgb0
-    } else if lower.contains("-cgb") {
-        GB::CPU::BootModel::Cgb
-    } else if lower.contains("-a.gb") {
-        GB::CPU::BootModel::Agb
-    } else if lower.ends_with("-c.gb") {
-        GB::CPU::BootModel::Cgb
-    } else if cpu.bus.cgb_mode {
-        GB::CPU::BootModel::Cgb
-    } else {
-        GB::CPU::BootModel::DmgAbc
+/// Flags que recebem um valor no argumento seguinte
+const VALUE_FLAGS: &[&str] = &[
+    "--rewind-interval",
+    "--rewind-mb",
+    "--boot-rom",
+    "--model",
+    "--dmg-palette",
+    "--camera-image",
+    "--mapper",
+    "--cart-db",
+    "--link-listen",
+    "--link-connect",
+    "--printer",
+    "--ir-replay",
+    "--ir-record",
+    "--screenshot-ref",
+];
+
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|s| s.as_str())
+}
+
+fn parse_rewind_config(args: &[String]) -> GB::rewind::RewindConfig {
+    let mut config = GB::rewind::RewindConfig::default();
+    if let Some(v) = flag_value(args, "--rewind-interval") {
+        match v.parse::<u32>() {
+            Ok(n) if n > 0 => config.interval_frames = n,
+            _ => eprintln!("⚠️ --rewind-interval inválido: {}", v),
+        }
+    }
+    if let Some(v) = flag_value(args, "--rewind-mb") {
+        match v.parse::<usize>() {
+            Ok(mb) => config.max_bytes = mb * 1024 * 1024,
+            Err(_) => eprintln!("⚠️ --rewind-mb inválido: {}", v),
+        }
     }
+    config
 }
 
 fn get_sav_path(rom_path: &str) -> String {
@@ -37,8 +54,16 @@ fn get_sav_path(rom_path: &str) -> String {
         .unwrap_or_else(|| format!("{}.sav", rom_path))
 }
 
-fn run_trace(cpu: &mut GB::CPU::CPU, rom_data: &[u8]) {
-    GB::cartridge::print_info(rom_data);
+fn save_cart_ram(cpu: &GB::CPU::CPU, sav_path: &str) {
+    if let Err(e) = cpu.bus.save_cart_ram(sav_path)
+        && !e.contains("No RAM to save")
+    {
+        eprintln!("⚠️ Erro ao salvar: {}", e);
+    }
+}
+
+fn run_trace(cpu: &mut GB::CPU::CPU, rom_data: &[u8], info: Option<&GB::cartdb::CartInfo>) {
+    GB::cartridge::print_info(rom_data, info);
     GB::trace::run_with_trace(cpu, usize::MAX);
 }
 
@@ -46,17 +71,52 @@ fn main() {
     let args: Vec<String> = env::args().collect();
 
     if args.len() < 2 || args.iter().any(|a| a == "--help" || a == "-h") {
-        eprintln!("Uso: cargo run -- <rom.gb> [--trace] [--headless]");
-        eprintln!("  --trace     : Executa com trace detalhado");
-        eprintln!("  --headless  : Executa sem interface gráfica");
+        eprintln!(
+            "Uso: cargo run -- <rom.gb> [--trace] [--headless] [--ppu-fifo] [--model M] [--boot-rom arquivo] [--dmg-palette combo] [--camera-image arquivo.pgm] [--mapper nome] [--cart-db arquivo] [--link-listen end] [--link-connect end] [--printer pasta] [--ir-replay arquivo] [--ir-record arquivo] [--screenshot-ref arquivo.pgm] [--rewind-interval N] [--rewind-mb N]"
+        );
+        eprintln!("  --trace               : Executa com trace detalhado");
+        eprintln!(
+            "  --model M             : dmg0, dmg, mgb, sgb, sgb2, cgb0, cgb ou agb (antes do banco de cartuchos)"
+        );
+        eprintln!("  --boot-rom arquivo    : Boot ROM DMG/MGB/SGB (256 bytes) ou CGB (2304 bytes)");
+        eprintln!(
+            "  --dmg-palette combo   : Paleta de jogo DMG no CGB por botões (ex: up, left+a, down+b)"
+        );
+        eprintln!("  --camera-image arq    : Imagem PGM vista pelo sensor da Game Boy Camera");
+        eprintln!(
+            "  --mapper nome         : Força mapper sem licença: wisdom-tree, m161, sachen-mmc1, sachen-mmc2, bbd ou hitek"
+        );
+        eprintln!(
+            "  --cart-db arquivo     : Entradas extras do banco de cartuchos (mapper, RAM e modelo por CRC32/checksum global)"
+        );
+        eprintln!(
+            "  --link-listen end     : Espera outro gb_emu no cabo link (host:porta ou unix:caminho)"
+        );
+        eprintln!("  --link-connect end    : Conecta o cabo link a um gb_emu em --link-listen");
+        eprintln!("  --printer pasta       : Liga uma Game Boy Printer que grava PNGs na pasta");
+        eprintln!(
+            "  --ir-replay arquivo   : Luz IR gravada (linhas \"ciclo 0|1\") vista pela porta FF56 do CGB"
+        );
+        eprintln!("  --ir-record arquivo   : Grava no arquivo o LED IR (FF56) ao sair");
+        eprintln!("  --headless            : Executa sem interface gráfica");
+        eprintln!(
+            "  --screenshot-ref arq  : Com --headless, compara a tela no LD B,B com um PGM 160x144"
+        );
+        eprintln!(
+            "  --ppu-fifo            : Renderiza com fetcher + FIFO de pixels (efeitos no meio da linha)"
+        );
+        eprintln!("  --rewind-interval N   : Frames entre snapshots de rewind (padrão 10)");
+        eprintln!("  --rewind-mb N         : Memória máxima do rewind em MB (padrão 32)");
         return;
     }
 
     // Encontra o arquivo ROM (não é um flag)
     let rom_path = args
         .iter()
+        .enumerate()
         .skip(1)
-        .find(|arg| !arg.starts_with("--"))
+        .find(|(i, arg)| !arg.starts_with("--") && !VALUE_FLAGS.contains(&args[i - 1].as_str()))
+        .map(|(_, arg)| arg)
         .expect("Nenhum arquivo ROM especificado");
 
     let headless = args.iter().any(|a| a == "--headless");
@@ -66,22 +126,142 @@ fn main() {
     // Carrega ROM
     let data = fs::read(rom_path).expect("Falha ao ler ROM");
 
-    // Valida header
+    // Mapper forçado (--mapper) para dumps que a heurística não reconhece
+    let mapper = match flag_value(&args, "--mapper") {
+        Some(name) => match GB::mbc::Unlicensed::from_name(name) {
+            Some(mapper) => Some(mapper),
+            None => {
+                eprintln!("Mapper desconhecido: {}", name);
+                return;
+            }
+        },
+        None => None,
+    };
+
+    // Banco de cartuchos: embutido + --cart-db (as entradas do arquivo têm prioridade)
+    let mut cart_db = GB::cartdb::CartDb::builtin();
+    if let Some(path) = flag_value(&args, "--cart-db") {
+        match GB::cartdb::CartDb::load(path) {
+            Ok(db) => cart_db.merge(db),
+            Err(e) => eprintln!("⚠️ Erro ao ler banco de cartuchos {}: {}", path, e),
+        }
+    }
+    let cart_info = cart_db.lookup(&data).cloned();
+
+    // Valida header; bootlegs costumam ter o header inválido (logo embaralhado da Sachen)
     if let Err(e) = GB::cartridge::validate_header(&data) {
-        eprintln!("{}", e);
+        let known = cart_info.is_some() || GB::mbc::Unlicensed::detect(&data).is_some();
+        if mapper.is_none() && !known {
+            eprintln!("{}", e);
+            return;
+        }
+        eprintln!("⚠️ {}", e);
+    }
+
+    // Modelo explícito (--model)
+    let model = match flag_value(&args, "--model") {
+        Some(name) => match GB::CPU::BootModel::from_name(name) {
+            Some(model) => Some(model),
+            None => {
+                eprintln!("Modelo desconhecido: {}", name);
+                return;
+            }
+        },
+        None => None,
+    };
+
+    // A impressora ocupa a mesma porta serial do cabo link
+    let link_flag = ["--link-listen", "--link-connect"]
+        .into_iter()
+        .find(|flag| flag_value(&args, flag).is_some());
+    if let (Some(flag), Some(_)) = (link_flag, flag_value(&args, "--printer")) {
+        eprintln!(
+            "--printer não pode ser usado com {}: os dois usam a porta serial",
+            flag
+        );
         return;
     }
 
     // Inicializa CPU
-    let mut cpu = GB::CPU::CPU::new(data.clone());
+    let mbc = match mapper {
+        Some(mapper) => {
+            println!("🔧 Mapper forçado: {}", mapper.name());
+            mapper.create(data.clone())
+        }
+        None => GB::mbc::create_mbc_for(data.clone(), cart_info.as_ref()),
+    };
+    let mut cpu = GB::CPU::CPU::with_mbc(&data, mbc);
 
-    // Boot ROM ou estado pós-boot
-    if let Ok(boot_rom) = fs::read("dmg_boot.bin") {
-        cpu.bus.load_boot_rom(boot_rom);
-        cpu.registers.set_pc(0x0000);
-    } else {
-        let boot_model = infer_boot_model(rom_path, &cpu);
-        cpu.init_post_boot_model(boot_model);
+    // Boot ROM (--boot-rom ou dmg_boot.bin) ou estado pós-boot
+    let boot_rom = match flag_value(&args, "--boot-rom") {
+        Some(path) => match fs::read(path) {
+            Ok(data) => Some(data),
+            Err(e) => {
+                eprintln!("⚠️ Erro ao ler boot ROM {}: {}", path, e);
+                None
+            }
+        },
+        None => fs::read("dmg_boot.bin").ok(),
+    };
+    let booted = match boot_rom {
+        Some(rom) => {
+            // Sem --model vale o modelo do banco de cartuchos, se ele roda
+            // essa boot ROM (CGB só com uma boot ROM de 2304 bytes)
+            let db_model = cart_info
+                .as_ref()
+                .and_then(|i| i.model)
+                .filter(|m| m.is_cgb() == (rom.len() == 0x900));
+            let result = match model.or(db_model) {
+                Some(model) => cpu.load_boot_rom_as(rom, model).map(|_| model),
+                None => cpu.load_boot_rom(rom),
+            };
+            match result {
+                Ok(model) => {
+                    println!("🔌 Boot ROM carregada (modelo {})", model.name());
+                    true
+                }
+                Err(e) => {
+                    eprintln!("⚠️ {}", e);
+                    false
+                }
+            }
+        }
+        None => false,
+    };
+    if !booted {
+        // Sem --model: o test runner deduz pelo nome da ROM (Mooneye);
+        // na interface gráfica vale o banco de cartuchos e depois o header
+        let model = model.or(cart_info.as_ref().and_then(|i| i.model));
+        let model = model.unwrap_or_else(|| {
+            if headless {
+                GB::test_runner::infer_model_from_path(rom_path, &data)
+            } else {
+                GB::CPU::BootModel::for_rom(&data)
+            }
+        });
+        cpu.init_post_boot_model(model);
+    }
+
+    // Combinação de botões do logo do CGB: troca as paletas do jogo DMG
+    if let Some(combo) = flag_value(&args, "--dmg-palette") {
+        match GB::compat_palettes::palettes_for_buttons(combo) {
+            Some(_) if !cpu.bus.ppu.cgb_hardware || cpu.bus.cgb_mode => {
+                eprintln!("⚠️ --dmg-palette só vale para jogos DMG no modelo CGB");
+            }
+            Some(palettes) => cpu.bus.ppu.load_compat_palettes(&palettes),
+            None => eprintln!("⚠️ Combinação de botões desconhecida: {}", combo),
+        }
+    }
+
+    if args.iter().any(|a| a == "--ppu-fifo") {
+        cpu.bus.ppu.fifo_renderer = true;
+    }
+
+    if let Some(path) = flag_value(&args, "--camera-image") {
+        match GB::mbc::camera::CameraSource::from_pgm_file(path) {
+            Ok(source) => cpu.bus.set_camera_source(Some(source)),
+            Err(e) => eprintln!("⚠️ Erro ao ler imagem da câmera: {}", e),
+        }
     }
 
     // Carrega save
@@ -93,36 +273,90 @@ fn main() {
         }
     }
 
+    // Cabo link com outro processo; liga por último para os dois relógios
+    // partirem juntos
+    let link = match (
+        flag_value(&args, "--link-listen"),
+        flag_value(&args, "--link-connect"),
+    ) {
+        (Some(addr), _) => {
+            println!("🔌 Aguardando o outro Game Boy em {}...", addr);
+            Some(GB::netlink::NetLink::listen(addr))
+        }
+        (None, Some(addr)) => Some(GB::netlink::NetLink::connect(addr)),
+        (None, None) => None,
+    };
+    match link {
+        Some(Ok(link)) => cpu.bus.set_link_cable(Some(Box::new(link))),
+        Some(Err(e)) => eprintln!("⚠️ Erro no cabo link: {}", e),
+        None => {
+            if let Some(dir) = flag_value(&args, "--printer") {
+                let printer = GB::printer::Printer::new(GB::printer::save_to_directory(dir));
+                cpu.bus.set_link_cable(Some(Box::new(printer)));
+            }
+        }
+    }
+
+    // Infravermelho do CGB gravado em arquivo
+    let ir_replay = flag_value(&args, "--ir-replay");
+    let ir_record = flag_value(&args, "--ir-record");
+    if ir_replay.is_some() || ir_record.is_some() {
+        let stream = match ir_replay {
+            Some(path) => GB::infrared::IrStream::load(path),
+            None => Ok(GB::infrared::IrStream::new(Vec::new())),
+        };
+        match stream {
+            Ok(stream) => {
+                let stream = match ir_record {
+                    Some(path) => stream.record_to(path),
+                    None => stream,
+                };
+                cpu.bus.set_ir_peer(Some(Box::new(stream)));
+            }
+            Err(e) => eprintln!("⚠️ Erro no IR: {}", e),
+        }
+    }
+
     println!("ROM carregada: {} ({} bytes)", rom_path, data.len());
 
     // Executa
     if headless {
-        let result = GB::test_runner::run(&mut cpu);
-        match result {
+        let result = match flag_value(&args, "--screenshot-ref") {
+            Some(path) => match fs::read(path) {
+                Ok(reference) => GB::test_runner::run_screenshot(&mut cpu, &reference),
+                Err(e) => {
+                    eprintln!("⚠️ Erro ao ler referência {}: {}", path, e);
+                    GB::test_runner::TestResult::Failed(0xFF)
+                }
+            },
+            None => GB::test_runner::run(&mut cpu),
+        };
+        let exit_code = match result {
             GB::test_runner::TestResult::Passed => {
                 println!("✅ Teste passou");
-                std::process::exit(0);
+                0
             }
             GB::test_runner::TestResult::Failed(code) => {
                 println!("❌ Teste falhou com código {}", code);
-                std::process::exit(1);
+                1
             }
             GB::test_runner::TestResult::Timeout => {
                 println!("⏱️ Teste deu timeout");
-                std::process::exit(2);
+                2
             }
-        }
+        };
+        // process::exit não roda os Drop: grava o IR e a RAM (fotos da
+        // câmera, saves) antes
+        cpu.bus.flush_ir();
+        save_cart_ram(&cpu, &sav_path);
+        std::process::exit(exit_code);
     } else if trace {
-        run_trace(&mut cpu, &data);
+        run_trace(&mut cpu, &data, cart_info.as_ref());
     } else {
-        GB::cartridge::print_info(&data);
-        GB::sdl_runner::run(&mut cpu);
+        GB::cartridge::print_info(&data, cart_info.as_ref());
+        GB::sdl_runner::run(&mut cpu, parse_rewind_config(&args));
     }
 
     // Salva RAM
-    if let Err(e) = cpu.bus.save_cart_ram(&sav_path) {
-        if !e.contains("No RAM to save") {
-            eprintln!("⚠️ Erro ao salvar: {}", e);
-        }
-    }
+    save_cart_ram(&cpu, &sav_path);
 }
@@ -0,0 +1,171 @@
+// Integration tests para boot ROMs DMG/MGB/SGB/CGB
+// cargo test boot_rom_test
+
+#[cfg(test)]
+mod boot_rom_tests {
+    use gb_emu::GB::CPU::{BootModel, CPU};
+
+    fn cpu_with_flag(cgb_flag: u8) -> CPU {
+        let mut rom = vec![0x00; 32 * 1024];
+        rom[0x0143] = cgb_flag;
+        rom[0x0150] = 0xC5;
+        rom[0x0250] = 0xD2;
+        CPU::new(rom)
+    }
+
+    // Imagem de 0x100 bytes terminando com LD A,n / LDH (50),A
+    fn small_boot_rom(ff50_value: u8, sgb: bool) -> Vec<u8> {
+        let mut boot = vec![0x00; 0x100];
+        if sgb {
+            boot[0x10..0x12].copy_from_slice(&[0xE0, 0x00]);
+        }
+        boot[0xFC..0x100].copy_from_slice(&[0x3E, ff50_value, 0xE0, 0x50]);
+        boot
+    }
+
+    fn cgb_boot_rom() -> Vec<u8> {
+        let mut boot = vec![0xAB; 0x900];
+        boot[0x0000] = 0x31;
+        boot[0x0250] = 0x77;
+        boot
+    }
+
+    #[test]
+    fn test_model_detection_from_image() {
+        assert_eq!(
+            BootModel::from_boot_rom(&small_boot_rom(0x01, false)),
+            Some(BootModel::DmgAbc)
+        );
+        assert_eq!(
+            BootModel::from_boot_rom(&small_boot_rom(0xFF, false)),
+            Some(BootModel::Mgb)
+        );
+        assert_eq!(
+            BootModel::from_boot_rom(&small_boot_rom(0x01, true)),
+            Some(BootModel::Sgb)
+        );
+        assert_eq!(
+            BootModel::from_boot_rom(&small_boot_rom(0xFF, true)),
+            Some(BootModel::Sgb2)
+        );
+        assert_eq!(
+            BootModel::from_boot_rom(&cgb_boot_rom()),
+            Some(BootModel::Cgb)
+        );
+        assert_eq!(BootModel::from_boot_rom(&[0u8; 0x200]), None);
+    }
+
+    #[test]
+    fn test_known_boot_rom_hashes() {
+        // DMG0 e DMG têm o mesmo tamanho; só o hash separa as duas
+        assert_eq!(
+            BootModel::from_boot_rom_crc32(0xC2F5CC97),
+            Some(BootModel::Dmg0)
+        );
+        assert_eq!(
+            BootModel::from_boot_rom_crc32(0x59C8598E),
+            Some(BootModel::DmgAbc)
+        );
+        assert_eq!(
+            BootModel::from_boot_rom_crc32(0xFFD6B0F1),
+            Some(BootModel::Agb)
+        );
+        assert_eq!(BootModel::from_boot_rom_crc32(0x12345678), None);
+    }
+
+    #[test]
+    fn test_invalid_boot_rom_size_rejected() {
+        let mut cpu = cpu_with_flag(0x00);
+        assert!(cpu.load_boot_rom(vec![0u8; 0x200]).is_err());
+        assert!(cpu.bus.load_boot_rom(vec![0u8; 0x800]).is_err());
+        assert_eq!(cpu.bus.read(0x0150), 0xC5);
+    }
+
+    #[test]
+    fn test_cgb_boot_rom_split_mapping() {
+        let mut cpu = cpu_with_flag(0x80);
+        let model = cpu.load_boot_rom(cgb_boot_rom()).unwrap();
+        assert_eq!(model, BootModel::Cgb);
+        assert_eq!(cpu.registers.get_pc(), 0x0000);
+
+        // 0x000–0x0FF e 0x200–0x8FF da boot ROM, header do cartucho no meio
+        assert_eq!(cpu.bus.read(0x0000), 0x31);
+        assert_eq!(cpu.bus.read(0x0150), 0xC5);
+        assert_eq!(cpu.bus.read(0x0250), 0x77);
+        assert_eq!(cpu.bus.read(0x08FF), 0xAB);
+        assert_eq!(cpu.bus.read(0x0900), 0x00);
+
+        cpu.bus.write(0xFF50, 0x11);
+        assert_eq!(cpu.bus.read(0x0000), 0x00);
+        assert_eq!(cpu.bus.read(0x0250), 0xD2);
+        assert!(cpu.bus.cgb_mode);
+    }
+
+    #[test]
+    fn test_dmg_boot_rom_maps_only_first_page() {
+        let mut cpu = cpu_with_flag(0x80);
+        let model = cpu.load_boot_rom(small_boot_rom(0x01, false)).unwrap();
+        assert_eq!(model, BootModel::DmgAbc);
+        assert!(!cpu.bus.cgb_mode);
+        assert_eq!(cpu.bus.read(0x00FC), 0x3E);
+        assert_eq!(cpu.bus.read(0x0250), 0xD2);
+    }
+
+    #[test]
+    fn test_cgb_boot_rom_switches_dmg_cart_to_compat_mode() {
+        let mut cpu = cpu_with_flag(0x00);
+        cpu.load_boot_rom(cgb_boot_rom()).unwrap();
+
+        // A boot ROM CGB roda em modo CGB mesmo com cartucho DMG
+        assert!(cpu.bus.cgb_mode);
+        cpu.bus.write(0xFF4C, 0x04);
+        cpu.bus.write(0xFF50, 0x11);
+        assert!(!cpu.bus.cgb_mode);
+        assert_eq!(cpu.bus.read(0xFF4F), 0xFE);
+
+        // KEY0 fica travado depois de FF50
+        cpu.bus.write(0xFF4C, 0x80);
+        assert!(!cpu.bus.cgb_mode);
+    }
+
+    #[test]
+    fn test_cgb_boot_rom_enables_cgb_registers_for_cgb_cart() {
+        let mut cpu = cpu_with_flag(0xC0);
+        cpu.load_boot_rom(cgb_boot_rom()).unwrap();
+
+        // Durante a boot ROM
+        cpu.bus.write(0xFF4F, 0x01);
+        assert_eq!(cpu.bus.ppu.vram_bank, 1);
+        cpu.bus.write(0xFF4F, 0x00);
+
+        // Cartucho CGB: KEY0 fica em modo CGB depois de FF50
+        cpu.bus.write(0xFF4C, 0xC0);
+        cpu.bus.write(0xFF50, 0x11);
+        assert!(cpu.bus.cgb_mode);
+        cpu.bus.write(0xFF4F, 0x01);
+        assert_eq!(cpu.bus.ppu.vram_bank, 1);
+        assert_eq!(cpu.bus.read(0xFF4F), 0xFF);
+
+        // BCPS com auto-incremento: cada BCPD vai para o próximo índice
+        cpu.bus.write(0xFF68, 0x82);
+        cpu.bus.write(0xFF69, 0x1F);
+        cpu.bus.write(0xFF69, 0x7C);
+        assert_eq!(cpu.bus.ppu.bg_palette_ram[2], 0x1F);
+        assert_eq!(cpu.bus.ppu.bg_palette_ram[3], 0x7C);
+        assert_eq!(cpu.bus.read(0xFF68) & 0x3F, 0x04);
+    }
+
+    #[test]
+    fn test_boot_rom_state_survives_save_state() {
+        let mut cpu = cpu_with_flag(0x00);
+        cpu.load_boot_rom(cgb_boot_rom()).unwrap();
+        cpu.bus.write(0xFF4C, 0x04);
+        let state = cpu.save_state();
+
+        let mut other = cpu_with_flag(0x00);
+        other.load_state(&state).unwrap();
+        assert_eq!(other.bus.read(0x0250), 0x77);
+        other.bus.write(0xFF50, 0x11);
+        assert!(!other.bus.cgb_mode);
+    }
+}
@@ -0,0 +1,218 @@
+use gb_emu::GB::mbc::camera::{Camera, CameraSource, SENSOR_HEIGHT, SENSOR_WIDTH};
+use gb_emu::GB::mbc::{MBC, create_mbc};
+
+fn camera() -> Camera {
+    let mut rom = vec![0u8; 1024 * 1024];
+    rom[0x0147] = 0xFC;
+    let mut cam = Camera::new(rom);
+    cam.write_register(0x0000, 0x0A);
+    cam
+}
+
+/// Exposição neutra (0x1000), ganho 0 e limiares 0x40/0x80/0xC0 em toda a matriz
+fn setup_sensor(cam: &mut Camera) {
+    cam.write_register(0x4000, 0x10);
+    cam.write_ram(0xA001, 0x00);
+    cam.write_ram(0xA002, 0x10);
+    cam.write_ram(0xA003, 0x00);
+    cam.write_ram(0xA004, 0x00);
+    for cell in 0..16u16 {
+        cam.write_ram(0xA006 + cell * 3, 0x40);
+        cam.write_ram(0xA007 + cell * 3, 0x80);
+        cam.write_ram(0xA008 + cell * 3, 0xC0);
+    }
+}
+
+/// Metade esquerda branca, metade direita preta
+fn half_image() -> Vec<u8> {
+    let mut image = vec![0u8; SENSOR_WIDTH * SENSOR_HEIGHT];
+    for y in 0..SENSOR_HEIGHT {
+        image[y * SENSOR_WIDTH..y * SENSOR_WIDTH + SENSOR_WIDTH / 2].fill(0xFF);
+    }
+    image
+}
+
+fn shoot(cam: &mut Camera) {
+    cam.write_register(0x4000, 0x10);
+    cam.write_ram(0xA000, 0x01);
+    while cam.read_ram(0xA000) & 0x01 != 0 {
+        cam.tick(456);
+    }
+    cam.write_register(0x4000, 0x00);
+}
+
+#[test]
+fn test_camera_selected_for_cart_type_0xfc() {
+    let mut rom = vec![0u8; 1024 * 1024];
+    rom[0x0147] = 0xFC;
+    rom[0x3F * 0x4000] = 0x3F;
+    let mut mbc = create_mbc(rom);
+    mbc.write_register(0x2000, 0x3F);
+    assert_eq!(mbc.read_rom(0x4000), 0x3F);
+    assert_eq!(mbc.save_ram().map(|r| r.len()), Some(128 * 1024));
+}
+
+#[test]
+fn test_camera_ram_banks_and_register_window() {
+    let mut cam = camera();
+    cam.write_register(0x4000, 0x0F);
+    cam.write_ram(0xA000, 0x5A);
+    cam.write_register(0x4000, 0x00);
+    assert_eq!(cam.read_ram(0xA000), 0x00);
+    cam.write_register(0x4000, 0x0F);
+    assert_eq!(cam.read_ram(0xA000), 0x5A);
+
+    // Bit 4: registradores no lugar da RAM; só A000 é legível
+    cam.write_register(0x4000, 0x10);
+    cam.write_ram(0xA002, 0x12);
+    assert_eq!(cam.read_ram(0xA000), 0x00);
+    assert_eq!(cam.read_ram(0xA002), 0x00);
+    cam.write_register(0x4000, 0x0F);
+    assert_eq!(cam.read_ram(0xA000), 0x5A);
+}
+
+#[test]
+fn test_camera_capture_writes_dithered_tiles() {
+    let mut cam = camera();
+    cam.set_camera_source(Some(CameraSource::Image(half_image())));
+    setup_sensor(&mut cam);
+
+    cam.write_ram(0xA000, 0x01);
+    assert_eq!(cam.read_ram(0xA000) & 0x01, 0x01);
+    cam.tick(1000);
+    assert_eq!(cam.read_ram(0xA000) & 0x01, 0x01);
+    shoot(&mut cam);
+
+    // Tile (0,0) branco (tom 0), tile (15,13) preto (tom 3)
+    assert_eq!(cam.read_ram(0xA100), 0x00);
+    assert_eq!(cam.read_ram(0xA101), 0x00);
+    let last = 0xA100 + (13 * 16 + 15) * 16;
+    assert_eq!(cam.read_ram(last), 0xFF);
+    assert_eq!(cam.read_ram(last + 1), 0xFF);
+}
+
+#[test]
+fn test_camera_exposure_gain_and_invert() {
+    let mut cam = camera();
+    let grey = vec![0x60u8; SENSOR_WIDTH * SENSOR_HEIGHT];
+    cam.set_camera_source(Some(CameraSource::Image(grey)));
+    setup_sensor(&mut cam);
+
+    // 0x60 fica entre 0x40 e 0x80: tom 2
+    shoot(&mut cam);
+    assert_eq!((cam.read_ram(0xA100), cam.read_ram(0xA101)), (0x00, 0xFF));
+
+    // Exposição dobrada: 0xC0, tom 0
+    cam.write_register(0x4000, 0x10);
+    cam.write_ram(0xA002, 0x20);
+    shoot(&mut cam);
+    assert_eq!((cam.read_ram(0xA100), cam.read_ram(0xA101)), (0x00, 0x00));
+
+    // Invertido: 0xFF - 0xC0 = 0x3F, tom 3
+    cam.write_register(0x4000, 0x10);
+    cam.write_ram(0xA004, 0x08);
+    shoot(&mut cam);
+    assert_eq!((cam.read_ram(0xA100), cam.read_ram(0xA101)), (0xFF, 0xFF));
+
+    // Ganho de 7dB (~2.2x) também leva o cinza acima de 0xC0
+    cam.write_register(0x4000, 0x10);
+    cam.write_ram(0xA002, 0x10);
+    cam.write_ram(0xA004, 0x00);
+    cam.write_ram(0xA001, 0x07);
+    shoot(&mut cam);
+    assert_eq!((cam.read_ram(0xA100), cam.read_ram(0xA101)), (0x00, 0x00));
+}
+
+#[test]
+fn test_camera_edge_enhancement() {
+    let mut registers = [0u8; 0x36];
+    registers[2] = 0x10;
+    for cell in 0..16 {
+        registers[6 + cell * 3..9 + cell * 3].copy_from_slice(&[0x40, 0x80, 0xC0]);
+    }
+    // Coluna 64 um pouco mais clara que o fundo cinza
+    let mut raw = vec![0x90u8; SENSOR_WIDTH * SENSOR_HEIGHT];
+    for y in 0..SENSOR_HEIGHT {
+        raw[y * SENSOR_WIDTH + 64] = 0xB0;
+    }
+
+    let flat = Camera::process_image(&registers, &raw);
+    assert_eq!(flat[10 * SENSOR_WIDTH + 63], 1);
+    assert_eq!(flat[10 * SENSOR_WIDTH + 64], 1);
+
+    // Realce horizontal com razão 1: a coluna clareia e as vizinhas escurecem
+    registers[1] = 0x20;
+    registers[4] = 0x20;
+    let edges = Camera::process_image(&registers, &raw);
+    assert_eq!(edges[10 * SENSOR_WIDTH + 64], 0);
+    assert_eq!(edges[10 * SENSOR_WIDTH + 63], 2);
+    assert_eq!(edges[10 * SENSOR_WIDTH + 20], 1);
+}
+
+#[test]
+fn test_camera_callback_and_pgm_sources() {
+    let mut cam = camera();
+    cam.set_camera_source(Some(CameraSource::Callback(Box::new(
+        |out: &mut [u8]| out.fill(0x00),
+    ))));
+    setup_sensor(&mut cam);
+    shoot(&mut cam);
+    assert_eq!(cam.read_ram(0xA100), 0xFF);
+
+    // PGM 2x1 (branco, preto) esticado para o sensor
+    let pgm = CameraSource::from_pgm(b"P2\n# teste\n2 1\n15\n15 0\n").unwrap();
+    let CameraSource::Image(image) = pgm else {
+        panic!("PGM deveria virar imagem fixa");
+    };
+    assert_eq!(image.len(), SENSOR_WIDTH * SENSOR_HEIGHT);
+    assert_eq!(image[0], 0xFF);
+    assert_eq!(image[SENSOR_WIDTH - 1], 0x00);
+
+    let mut binary = b"P5 2 2 255\n".to_vec();
+    binary.extend_from_slice(&[0x10, 0x20, 0x30, 0x40]);
+    let CameraSource::Image(image) = CameraSource::from_pgm(&binary).unwrap() else {
+        panic!("PGM deveria virar imagem fixa");
+    };
+    assert_eq!(
+        image[(SENSOR_HEIGHT - 1) * SENSOR_WIDTH + SENSOR_WIDTH - 1],
+        0x40
+    );
+    assert!(CameraSource::from_pgm(b"P6 1 1 255\n\0\0\0").is_err());
+}
+
+#[test]
+fn test_camera_save_state_mid_capture() {
+    use gb_emu::GB::savestate::{StateReader, StateWriter};
+
+    let mut cam = camera();
+    setup_sensor(&mut cam);
+    cam.write_ram(0xA000, 0x01);
+    cam.tick(10_000);
+    let mut w = StateWriter::new();
+    cam.save_state(&mut w);
+    let data = w.into_inner();
+
+    let mut other = camera();
+    other.load_state(&mut StateReader::new(&data)).unwrap();
+    other.set_camera_source(Some(CameraSource::Image(half_image())));
+    assert_eq!(other.read_ram(0xA000) & 0x01, 0x01);
+    shoot(&mut other);
+    assert_eq!(other.read_ram(0xA100), 0x00);
+}
+
+#[test]
+fn test_camera_photo_persists_through_save_ram() {
+    let mut cam = camera();
+    cam.set_camera_source(Some(CameraSource::Image(half_image())));
+    setup_sensor(&mut cam);
+    shoot(&mut cam);
+
+    // O que o main grava no .sav ao sair (inclusive headless) e lê de volta
+    let sav = cam.save_ram().unwrap();
+    let mut other = camera();
+    other.load_ram(&sav);
+    let last = 0xA100 + (13 * 16 + 15) * 16;
+    assert_eq!(other.read_ram(0xA100), 0x00);
+    assert_eq!(other.read_ram(last), 0xFF);
+    assert_eq!(other.read_ram(last + 1), 0xFF);
+}
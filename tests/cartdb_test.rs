@@ -0,0 +1,147 @@
+// Integration tests para o banco de cartuchos
+// cargo test cartdb_test
+
+#[cfg(test)]
+mod cartdb_tests {
+    use gb_emu::GB::CPU::BootModel;
+    use gb_emu::GB::cartdb::{CartDb, CartKey, MapperOverride, Region};
+    use gb_emu::GB::cartridge::{crc32, global_checksum, header_global_checksum};
+    use gb_emu::GB::mbc::{Unlicensed, create_mbc_for};
+
+    /// ROM de 64KB sem mapper, com o checksum global correto gravado
+    fn rom() -> Vec<u8> {
+        let mut rom = vec![0u8; 64 * 1024];
+        rom[0x0134..0x0138].copy_from_slice(b"TEST");
+        rom[0x4000] = 0x01;
+        let sum = global_checksum(&rom);
+        rom[0x014E..0x0150].copy_from_slice(&sum.to_be_bytes());
+        rom
+    }
+
+    #[test]
+    fn test_global_checksum_skips_its_own_bytes() {
+        let mut rom = rom();
+        assert_eq!(global_checksum(&rom), header_global_checksum(&rom));
+        assert_eq!(header_global_checksum(&rom), 0x0141);
+        rom[0x014E] = 0xAB;
+        assert_eq!(global_checksum(&rom), 0x0141);
+    }
+
+    #[test]
+    fn test_parse_and_lookup() {
+        let rom = rom();
+        let text = format!(
+            "# comentário\n\
+             global:0141 | TEST (global) | JPN | não | - | - | -\n\
+             crc32:{:08X} | TEST | usa | sim | 0x13 | 64 | cgb # dump bom\n",
+            crc32(&rom)
+        );
+        let db = CartDb::parse(&text).unwrap();
+        let info = db.lookup(&rom).unwrap();
+        assert_eq!(info.title, "TEST");
+        assert_eq!(info.region, Region::Usa);
+        assert!(info.verified);
+        assert_eq!(info.mapper, Some(MapperOverride::CartType(0x13)));
+        assert_eq!(info.ram_size, Some(64 * 1024));
+        assert_eq!(info.model, Some(BootModel::Cgb));
+
+        // Um byte ruim muda o CRC, mas o checksum global do header ainda casa
+        let mut bad = rom.clone();
+        bad[0x7000] = 0x55;
+        let info = db.lookup(&bad).unwrap();
+        assert_eq!(info.title, "TEST (global)");
+        assert_eq!(info.region, Region::Japan);
+        assert!(!info.verified);
+
+        let mut other = rom;
+        other[0x014F] ^= 0xFF;
+        other[0x7000] = 0x55;
+        assert!(db.lookup(&other).is_none());
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        let err = CartDb::parse("\n\ncrc32:12 | X | USA | sim | - | -").unwrap_err();
+        assert!(err.starts_with("Linha 3:"), "{}", err);
+        assert!(CartDb::parse("md5:12 | X | USA | sim | - | - | -").is_err());
+        assert!(CartDb::parse("crc32:12 | X | Marte | sim | - | - | -").is_err());
+        assert!(CartDb::parse("crc32:12 | X | USA | sim | 0x1FF | - | -").is_err());
+        assert!(CartDb::parse("crc32:12 | X | USA | sim | - | - | gbc2").is_err());
+        CartDb::builtin();
+    }
+
+    #[test]
+    fn test_builtin_has_verified_entries() {
+        let db = CartDb::builtin();
+        let red = db.get(CartKey::Crc32(0x9F7FDD53)).unwrap();
+        assert_eq!(red.title, "Pokemon Red Version");
+        assert_eq!(red.region, Region::Usa);
+        assert!(red.verified);
+        assert_eq!(red.model, Some(BootModel::Sgb));
+
+        let crystal = db.get(CartKey::Crc32(0x3358E30A)).unwrap();
+        assert_eq!(crystal.model, Some(BootModel::Cgb));
+        assert!(db.get(CartKey::Crc32(0x46DF91AD)).unwrap().verified);
+
+        // ROM fora do banco: o checksum global não casa com nenhuma entrada
+        assert!(db.lookup(&rom()).is_none());
+    }
+
+    #[test]
+    fn test_merge_gives_priority_to_new_entries() {
+        let rom = rom();
+        let mut db = CartDb::parse("global:0141 | ANTIGO | EUR | sim | - | - | -").unwrap();
+        db.merge(CartDb::parse("global:0141 | NOVO | WORLD | sim | - | - | -").unwrap());
+        assert_eq!(db.lookup(&rom).unwrap().title, "NOVO");
+    }
+
+    #[test]
+    fn test_overrides_fix_lying_header() {
+        let rom = rom();
+        let db = CartDb::parse("global:0141 | TEST | USA | sim | 0x13 | 64 | -").unwrap();
+        let mut mbc = create_mbc_for(rom.clone(), db.lookup(&rom));
+
+        // O header diz ROM ONLY; o banco corrige para MBC30 com 64KB de RAM
+        mbc.write_register(0x2000, 0x01);
+        assert_eq!(mbc.read_rom(0x4000), 0x01);
+        mbc.write_register(0x0000, 0x0A);
+        mbc.write_register(0x4000, 0x07);
+        mbc.write_ram(0xA000, 0x42);
+        assert_eq!(mbc.read_ram(0xA000), 0x42);
+        assert_eq!(mbc.save_ram().map(|r| r.len()), Some(64 * 1024 + 5 + 8));
+    }
+
+    #[test]
+    fn test_unlicensed_override() {
+        let rom = rom();
+        let db = CartDb::parse("global:0141 | TEST | USA | não | wisdom-tree | - | -").unwrap();
+        let info = db.lookup(&rom).unwrap();
+        assert_eq!(
+            info.mapper,
+            Some(MapperOverride::Unlicensed(Unlicensed::WisdomTree))
+        );
+        let mut mbc = create_mbc_for(rom, Some(info));
+        assert_eq!(mbc.read_rom(0x4000), 0x01);
+        // Banco de 32KB escolhido pelo endereço
+        mbc.write_register(0x0001, 0x00);
+        assert_eq!(mbc.read_rom(0x4000), 0x00);
+    }
+    #[test]
+    fn test_builtin_overrides_wisdom_tree_header() {
+        // O header de Joshua diz ROM ONLY; o banco embutido troca o mapper
+        let db = CartDb::builtin();
+        let joshua = db.get(CartKey::Crc32(0x2C6F7E1A)).unwrap();
+        assert_eq!(joshua.title, "Joshua & the Battle of Jericho");
+        assert_eq!(
+            joshua.mapper,
+            Some(MapperOverride::Unlicensed(Unlicensed::WisdomTree))
+        );
+        assert_eq!(joshua.ram_size, None);
+
+        let mut mbc = create_mbc_for(rom(), Some(joshua));
+        mbc.write_register(0x0001, 0x00);
+        assert_eq!(mbc.read_rom(0x4000), 0x00);
+        mbc.write_register(0x0000, 0x00);
+        assert_eq!(mbc.read_rom(0x4000), 0x01);
+    }
+}
@@ -1,4 +1,4 @@
-use gb_emu::GB::cartridge::{is_cgb_only_rom, is_cgb_rom};
+use gb_emu::GB::cartridge::{crc32, is_cgb_only_rom, is_cgb_rom};
 
 #[test]
 fn test_cgb_header_flags() {
@@ -16,3 +16,9 @@ fn test_cgb_header_flags() {
     assert!(is_cgb_rom(&rom));
     assert!(is_cgb_only_rom(&rom));
 }
+
+#[test]
+fn test_crc32() {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+}
@@ -0,0 +1,165 @@
+// Integration tests para WRAM em bancos (SVBK) e HDMA/GDMA do CGB
+// cargo test cgb_dma_test
+
+#[cfg(test)]
+mod cgb_dma_tests {
+    use gb_emu::GB::CPU::CPU;
+
+    fn cpu_with_flag(cgb_flag: u8) -> CPU {
+        let mut rom = vec![0x00; 32 * 1024];
+        rom[0x0143] = cgb_flag;
+        for (i, byte) in rom[0x4000..0x4100].iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        let mut cpu = CPU::new(rom);
+        cpu.init_post_boot();
+        cpu
+    }
+
+    fn cgb_cpu() -> CPU {
+        cpu_with_flag(0xC0)
+    }
+
+    fn start_dma(cpu: &mut CPU, src: u16, dst: u16, hdma5: u8) {
+        cpu.bus.write(0xFF51, (src >> 8) as u8);
+        cpu.bus.write(0xFF52, src as u8);
+        cpu.bus.write(0xFF53, (dst >> 8) as u8);
+        cpu.bus.write(0xFF54, dst as u8);
+        cpu.bus.write(0xFF55, hdma5);
+    }
+
+    // Avança até a próxima entrada em HBlank (modo 0)
+    fn run_until_next_hblank(cpu: &mut CPU) {
+        while cpu.bus.ppu.mode == 0 {
+            cpu.bus.tick(4);
+        }
+        while cpu.bus.ppu.mode != 0 {
+            cpu.bus.tick(4);
+        }
+    }
+
+    #[test]
+    fn test_svbk_switches_upper_wram_bank() {
+        let mut cpu = cgb_cpu();
+
+        for bank in 1..8u8 {
+            cpu.bus.write(0xFF70, bank);
+            cpu.bus.write(0xD000, 0x10 + bank);
+        }
+        cpu.bus.write(0xC000, 0xAA);
+
+        for bank in 1..8u8 {
+            cpu.bus.write(0xFF70, bank);
+            assert_eq!(cpu.bus.read(0xD000), 0x10 + bank, "banco {}", bank);
+            assert_eq!(cpu.bus.read(0xC000), 0xAA);
+        }
+
+        // Banco 0 seleciona o banco 1
+        cpu.bus.write(0xFF70, 0x00);
+        assert_eq!(cpu.bus.read(0xD000), 0x11);
+        assert_eq!(cpu.bus.read(0xFF70), 0xF8);
+
+        // Echo RAM segue o banco selecionado
+        cpu.bus.write(0xFF70, 0x05);
+        assert_eq!(cpu.bus.read(0xF000), 0x15);
+        assert_eq!(cpu.bus.read(0xFF70), 0xFD);
+    }
+
+    #[test]
+    fn test_svbk_ignored_in_dmg_mode() {
+        let mut cpu = cpu_with_flag(0x00);
+        cpu.bus.write(0xD000, 0x42);
+        cpu.bus.write(0xFF70, 0x03);
+        assert_eq!(cpu.bus.read(0xD000), 0x42);
+        assert_eq!(cpu.bus.read(0xFF70), 0xFF);
+    }
+
+    #[test]
+    fn test_gdma_copies_immediately_and_stalls_cpu() {
+        let mut cpu = cgb_cpu();
+        cpu.bus.write(0xFF40, 0x00);
+
+        // 3 blocos da ROM (0x4000) para 0x8800, com a origem desalinhada
+        start_dma(&mut cpu, 0x4005, 0x8805, 0x02);
+
+        for i in 0..48 {
+            assert_eq!(cpu.bus.read(0x8800 + i), i as u8);
+        }
+        assert_eq!(cpu.bus.read(0x8800 + 48), cpu.bus.ppu.vram[0x0830]);
+        assert_eq!(cpu.bus.read(0xFF55), 0xFF);
+
+        // CPU fica parada 8 M-cycles por bloco
+        let (cycles, _) = cpu.execute_next();
+        assert_eq!(cycles, 3 * 32);
+    }
+
+    #[test]
+    fn test_gdma_uses_selected_vram_bank_and_wram_bank() {
+        let mut cpu = cgb_cpu();
+        cpu.bus.write(0xFF40, 0x00);
+
+        cpu.bus.write(0xFF70, 0x03);
+        for i in 0..16 {
+            cpu.bus.write(0xD100 + i, 0xB0 + i as u8);
+        }
+        cpu.bus.write(0xFF4F, 0x01);
+        start_dma(&mut cpu, 0xD100, 0x9000, 0x00);
+
+        for i in 0..16 {
+            assert_eq!(cpu.bus.ppu.vram1[0x1000 + i], 0xB0 + i as u8);
+        }
+    }
+
+    #[test]
+    fn test_hblank_dma_copies_one_block_per_hblank() {
+        let mut cpu = cgb_cpu();
+        for i in 0..32 {
+            cpu.bus.write(0xC000 + i, 0x80 + i as u8);
+        }
+        run_until_next_hblank(&mut cpu);
+
+        start_dma(&mut cpu, 0xC000, 0x8000, 0x81);
+        assert_eq!(cpu.bus.read(0xFF55), 0x01);
+
+        run_until_next_hblank(&mut cpu);
+        assert_eq!(cpu.bus.read(0xFF55), 0x00);
+        assert_eq!(cpu.bus.ppu.vram[0], 0x80);
+        assert_eq!(cpu.bus.ppu.vram[15], 0x8F);
+        assert_ne!(cpu.bus.ppu.vram[16], 0x90);
+        assert_eq!(cpu.bus.take_cpu_stall_cycles(), 32);
+
+        run_until_next_hblank(&mut cpu);
+        assert_eq!(cpu.bus.read(0xFF55), 0xFF);
+        assert_eq!(cpu.bus.ppu.vram[16], 0x90);
+        assert_eq!(cpu.bus.ppu.vram[31], 0x9F);
+        assert_eq!(cpu.bus.take_cpu_stall_cycles(), 32);
+    }
+
+    #[test]
+    fn test_hblank_dma_can_be_cancelled() {
+        let mut cpu = cgb_cpu();
+        run_until_next_hblank(&mut cpu);
+
+        start_dma(&mut cpu, 0x4000, 0x8000, 0x83);
+        run_until_next_hblank(&mut cpu);
+        assert_eq!(cpu.bus.read(0xFF55), 0x02);
+
+        cpu.bus.write(0xFF55, 0x00);
+        assert_eq!(cpu.bus.read(0xFF55), 0x82);
+
+        cpu.bus.take_cpu_stall_cycles();
+        run_until_next_hblank(&mut cpu);
+        assert_eq!(cpu.bus.take_cpu_stall_cycles(), 0);
+        assert_eq!(cpu.bus.read(0xFF55), 0x82);
+    }
+
+    #[test]
+    fn test_hdma_registers_ignored_in_dmg_mode() {
+        let mut cpu = cpu_with_flag(0x00);
+        cpu.bus.write(0xFF40, 0x00);
+        let before = cpu.bus.ppu.vram[0];
+        start_dma(&mut cpu, 0x4000, 0x8000, 0x00);
+        assert_eq!(cpu.bus.ppu.vram[0], before);
+        assert_eq!(cpu.bus.read(0xFF55), 0xFF);
+    }
+}
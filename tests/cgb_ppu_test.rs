@@ -0,0 +1,252 @@
+// Integration tests para o PPU em modo CGB
+// cargo test cgb_ppu_test
+
+#[cfg(test)]
+mod cgb_ppu_tests {
+    use gb_emu::GB::CPU::{BootModel, CPU};
+    use gb_emu::GB::PPU::{DMG_SHADES_RGB555, PPU};
+
+    const RED: u16 = 0x001F;
+    const GREEN: u16 = 0x03E0;
+    const BLUE: u16 = 0x7C00;
+
+    fn cgb_cpu(cgb_flag: u8) -> CPU {
+        let mut rom = vec![0x00; 32 * 1024];
+        rom[0x0143] = cgb_flag;
+        let mut cpu = CPU::new(rom);
+        cpu.init_post_boot();
+        // LCD desligado: VRAM e paletas sempre acessíveis
+        cpu.bus.write(0xFF40, 0x00);
+        cpu
+    }
+
+    fn set_color(ram: &mut [u8; 64], palette: usize, color: usize, rgb: u16) {
+        let idx = palette * 8 + color * 2;
+        ram[idx..idx + 2].copy_from_slice(&rgb.to_le_bytes());
+    }
+
+    // Tile sólido com a cor indicada (0-3)
+    fn fill_tile(vram: &mut [u8; 0x2000], tile: usize, color: u8) {
+        let lsb = if color & 1 != 0 { 0xFF } else { 0x00 };
+        let msb = if color & 2 != 0 { 0xFF } else { 0x00 };
+        for row in 0..8 {
+            vram[tile * 16 + row * 2] = lsb;
+            vram[tile * 16 + row * 2 + 1] = msb;
+        }
+    }
+
+    fn cgb_ppu() -> PPU {
+        let mut ppu = PPU::new();
+        ppu.cgb_mode = true;
+        ppu.lcdc = 0x93; // LCD on, BG on, sprites on, tile data 0x8000
+        ppu.vram[0x1800..0x1C00].fill(0);
+        ppu.vram1[0x1800..0x1C00].fill(0);
+        fill_tile(&mut ppu.vram, 0, 0);
+        ppu
+    }
+
+    #[test]
+    fn test_cgb_compatible_rom_runs_in_color() {
+        let cpu = cgb_cpu(0x80);
+        assert!(cpu.bus.cgb_mode);
+        assert!(cpu.bus.ppu.cgb_mode);
+    }
+
+    #[test]
+    fn test_dmg_model_keeps_cgb_compatible_rom_on_dmg_path() {
+        let mut rom = vec![0x00; 32 * 1024];
+        rom[0x0143] = 0x80;
+        let mut cpu = CPU::new(rom);
+        cpu.init_post_boot_model(BootModel::DmgAbc);
+        assert!(!cpu.bus.cgb_mode);
+        assert!(!cpu.bus.ppu.cgb_mode);
+    }
+
+    #[test]
+    fn test_vbk_switches_vram_bank() {
+        let mut cpu = cgb_cpu(0xC0);
+
+        cpu.bus.write(0x8000, 0x11);
+        cpu.bus.write(0xFF4F, 0x01);
+        assert_eq!(cpu.bus.read(0xFF4F), 0xFF);
+        cpu.bus.write(0x8000, 0x22);
+        assert_eq!(cpu.bus.read(0x8000), 0x22);
+
+        cpu.bus.write(0xFF4F, 0x00);
+        assert_eq!(cpu.bus.read(0xFF4F), 0xFE);
+        assert_eq!(cpu.bus.read(0x8000), 0x11);
+        assert_eq!(cpu.bus.ppu.vram[0], 0x11);
+        assert_eq!(cpu.bus.ppu.vram1[0], 0x22);
+    }
+
+    #[test]
+    fn test_palette_data_auto_increment() {
+        let mut cpu = cgb_cpu(0xC0);
+
+        // BCPS com auto-incremento a partir da paleta 1, cor 0
+        cpu.bus.write(0xFF68, 0x88);
+        cpu.bus.write(0xFF69, 0x1F);
+        cpu.bus.write(0xFF69, 0x00);
+        assert_eq!(cpu.bus.read(0xFF68), 0xCA);
+        assert_eq!(cpu.bus.ppu.bg_palette_ram[8], 0x1F);
+        assert_eq!(cpu.bus.ppu.bg_palette_ram[9], 0x00);
+
+        // Sem auto-incremento o índice fica parado
+        cpu.bus.write(0xFF6A, 0x3F);
+        cpu.bus.write(0xFF6B, 0x7C);
+        cpu.bus.write(0xFF6B, 0x7D);
+        assert_eq!(cpu.bus.read(0xFF6A), 0x7F);
+        assert_eq!(cpu.bus.read(0xFF6B), 0x7D);
+
+        // Índice volta para 0 depois de 0x3F
+        cpu.bus.write(0xFF68, 0xBF);
+        cpu.bus.write(0xFF69, 0x55);
+        assert_eq!(cpu.bus.read(0xFF68) & 0x3F, 0x00);
+    }
+
+    #[test]
+    fn test_palette_data_locked_in_dmg_compat_mode() {
+        let mut rom = vec![0x00; 32 * 1024];
+        rom[0x0143] = 0x00;
+        let mut cpu = CPU::new(rom);
+        cpu.init_post_boot_model(BootModel::Cgb);
+        cpu.bus.write(0xFF40, 0x00);
+
+        cpu.bus.write(0xFF68, 0x80);
+        cpu.bus.write(0xFF69, 0x12);
+        assert_eq!(cpu.bus.read(0xFF69), 0xFF);
+    }
+
+    #[test]
+    fn test_bg_attributes_palette_bank_and_flip() {
+        let mut ppu = cgb_ppu();
+
+        // Tile 1 no banco 1: metade esquerda cor 1, metade direita cor 2
+        for row in 0..8 {
+            ppu.vram1[16 + row * 2] = 0xF0;
+            ppu.vram1[16 + row * 2 + 1] = 0x0F;
+        }
+        set_color(&mut ppu.bg_palette_ram, 3, 1, RED);
+        set_color(&mut ppu.bg_palette_ram, 3, 2, GREEN);
+
+        ppu.vram[0x1800] = 1;
+        ppu.vram1[0x1800] = 0x08 | 0x03; // banco 1, paleta 3
+        ppu.vram[0x1801] = 1;
+        ppu.vram1[0x1801] = 0x20 | 0x08 | 0x03; // + flip X
+
+        ppu.ly = 0;
+        ppu.render_bg_scanline();
+
+        assert_eq!(ppu.rgb_framebuffer[0], RED);
+        assert_eq!(ppu.rgb_framebuffer[7], GREEN);
+        assert_eq!(ppu.rgb_framebuffer[8], GREEN);
+        assert_eq!(ppu.rgb_framebuffer[15], RED);
+    }
+
+    #[test]
+    fn test_bg_attribute_vertical_flip() {
+        let mut ppu = cgb_ppu();
+
+        // Tile 1: só a linha 7 tem cor 3
+        fill_tile(&mut ppu.vram, 1, 0);
+        ppu.vram[16 + 14] = 0xFF;
+        ppu.vram[16 + 15] = 0xFF;
+        set_color(&mut ppu.bg_palette_ram, 0, 0, BLUE);
+        set_color(&mut ppu.bg_palette_ram, 0, 3, RED);
+
+        ppu.vram[0x1800] = 1;
+        ppu.vram1[0x1800] = 0x40;
+        ppu.ly = 0;
+        ppu.render_bg_scanline();
+        assert_eq!(ppu.rgb_framebuffer[0], RED);
+
+        ppu.vram1[0x1800] = 0x00;
+        ppu.render_bg_scanline();
+        assert_eq!(ppu.rgb_framebuffer[0], BLUE);
+    }
+
+    #[test]
+    fn test_sprite_uses_obj_palette_and_bank() {
+        let mut ppu = cgb_ppu();
+
+        fill_tile(&mut ppu.vram1, 2, 1);
+        set_color(&mut ppu.obj_palette_ram, 5, 1, GREEN);
+
+        ppu.oam[0] = 16;
+        ppu.oam[1] = 8;
+        ppu.oam[2] = 2;
+        ppu.oam[3] = 0x08 | 0x05; // banco 1, paleta 5
+
+        ppu.ly = 0;
+        ppu.render_bg_scanline();
+        ppu.render_sprites_scanline(0);
+
+        assert_eq!(ppu.rgb_framebuffer[0], GREEN);
+        assert_eq!(ppu.rgb_framebuffer[7], GREEN);
+    }
+
+    #[test]
+    fn test_bg_attribute_priority_over_sprites() {
+        let mut ppu = cgb_ppu();
+
+        fill_tile(&mut ppu.vram, 1, 1);
+        fill_tile(&mut ppu.vram, 2, 3);
+        set_color(&mut ppu.bg_palette_ram, 0, 1, BLUE);
+        set_color(&mut ppu.obj_palette_ram, 0, 3, RED);
+
+        // Tile do BG opaco e com prioridade
+        ppu.vram[0x1800] = 1;
+        ppu.vram1[0x1800] = 0x80;
+        ppu.oam[0] = 16;
+        ppu.oam[1] = 8;
+        ppu.oam[2] = 2;
+        ppu.oam[3] = 0x00;
+
+        ppu.ly = 0;
+        ppu.render_bg_scanline();
+        ppu.render_sprites_scanline(0);
+        assert_eq!(ppu.rgb_framebuffer[0], BLUE);
+
+        // LCDC bit 0 desligado: sprites sempre por cima
+        ppu.lcdc &= !0x01;
+        ppu.render_bg_scanline();
+        ppu.render_sprites_scanline(0);
+        assert_eq!(ppu.rgb_framebuffer[0], RED);
+    }
+
+    #[test]
+    fn test_lower_oam_index_wins_in_cgb_mode() {
+        let mut ppu = cgb_ppu();
+
+        fill_tile(&mut ppu.vram, 2, 3);
+        set_color(&mut ppu.obj_palette_ram, 0, 3, RED);
+        set_color(&mut ppu.obj_palette_ram, 1, 3, GREEN);
+
+        // Sprite 0 mais à direita, sprite 1 mais à esquerda sobrepondo
+        ppu.oam[0..4].copy_from_slice(&[16, 12, 2, 0x00]);
+        ppu.oam[4..8].copy_from_slice(&[16, 8, 2, 0x01]);
+
+        ppu.ly = 0;
+        ppu.render_bg_scanline();
+        ppu.render_sprites_scanline(0);
+
+        assert_eq!(ppu.rgb_framebuffer[3], GREEN);
+        assert_eq!(ppu.rgb_framebuffer[4], RED);
+    }
+
+    #[test]
+    fn test_dmg_mode_fills_rgb_framebuffer_with_greys() {
+        let mut ppu = PPU::new();
+        fill_tile(&mut ppu.vram, 0, 3);
+        ppu.vram[0x1800..0x1C00].fill(0);
+        ppu.lcdc = 0x91;
+        ppu.bgp = 0xE4;
+        ppu.ly = 0;
+
+        let mut iflags = 0;
+        ppu.change_mode(0, &mut iflags);
+
+        assert_eq!(ppu.framebuffer[0], 3);
+        assert_eq!(ppu.rgb_framebuffer[0], DMG_SHADES_RGB555[3]);
+    }
+}
@@ -0,0 +1,98 @@
+// Integration tests para as paletas de compatibilidade DMG-no-CGB
+// cargo test compat_palettes_test
+
+#[cfg(test)]
+mod compat_palettes_tests {
+    use gb_emu::GB::CPU::{BootModel, CPU};
+    use gb_emu::GB::compat_palettes::{palettes_for_buttons, palettes_for_rom, title_checksum};
+
+    fn dmg_rom(title: &str, licensee: u8) -> Vec<u8> {
+        let mut rom = vec![0x00; 32 * 1024];
+        rom[0x0134..0x0134 + title.len()].copy_from_slice(title.as_bytes());
+        rom[0x014B] = licensee;
+        rom
+    }
+
+    #[test]
+    fn test_title_checksum_only_for_nintendo() {
+        assert_eq!(title_checksum(&dmg_rom("POKEMON RED", 0x01)), Some(0x14));
+        assert_eq!(title_checksum(&dmg_rom("POKEMON RED", 0x08)), None);
+
+        let mut rom = dmg_rom("TETRIS", 0x33);
+        rom[0x0144..0x0146].copy_from_slice(b"01");
+        assert_eq!(title_checksum(&rom), Some(0xDB));
+    }
+
+    #[test]
+    fn test_known_title_palettes() {
+        // Pokémon Red: BG e OBJ1 vermelhos, OBJ0 verde
+        let red = palettes_for_rom(&dmg_rom("POKEMON RED", 0x01));
+        assert_eq!(red.bg, [0x7FFF, 0x421F, 0x1CF2, 0x0000]);
+        assert_eq!(red.obj0, [0x7FFF, 0x1BEF, 0x0200, 0x0000]);
+        assert_eq!(red.obj1, red.bg);
+    }
+
+    #[test]
+    fn test_fourth_letter_disambiguates_duplicates() {
+        // 0x46 é a soma de SUPER MARIOLAND (4ª letra 'E')
+        let sml = palettes_for_rom(&dmg_rom("SUPER MARIOLAND", 0x01));
+        assert_eq!(sml.bg, [0x7ED6, 0x4BFF, 0x2175, 0x0000]);
+        assert_eq!(sml.obj0, [0x0000, 0x7FFF, 0x421F, 0x1CF2]);
+
+        // Mesma soma com outra 4ª letra: paleta padrão
+        let rom = dmg_rom("SUEPR MARIOLAND", 0x01);
+        assert_eq!(title_checksum(&rom), Some(0x46));
+        let other = palettes_for_rom(&rom);
+        assert_eq!(other, palettes_for_buttons("right+a").unwrap());
+    }
+
+    #[test]
+    fn test_unknown_game_uses_default_palette() {
+        let default = palettes_for_rom(&dmg_rom("HOMEBREW", 0x00));
+        assert_eq!(default.bg, [0x7FFF, 0x1BEF, 0x6180, 0x0000]);
+        assert_eq!(default.obj0, [0x7FFF, 0x421F, 0x1CF2, 0x0000]);
+    }
+
+    #[test]
+    fn test_button_combos() {
+        let gray = palettes_for_buttons("Left+B").unwrap();
+        assert_eq!(gray.bg, [0x7FFF, 0x5294, 0x294A, 0x0000]);
+        assert_eq!(gray.obj0, gray.bg);
+
+        let inverted = palettes_for_buttons("right+b").unwrap();
+        assert_eq!(inverted.bg, [0x0000, 0x4200, 0x037F, 0x7FFF]);
+        assert!(palettes_for_buttons("select").is_none());
+    }
+
+    #[test]
+    fn test_cgb_model_renders_dmg_game_in_color() {
+        let mut rom = dmg_rom("POKEMON RED", 0x01);
+        rom[0x0147] = 0x00;
+        let mut cpu = CPU::with_model(rom, BootModel::Cgb);
+        assert!(!cpu.bus.cgb_mode);
+        assert_eq!(&cpu.bus.ppu.bg_palette_ram[0..4], &[0xFF, 0x7F, 0x1F, 0x42]);
+
+        // Tile 0 com cor 1 e BGP identidade: shade 1 vira a cor 1 da paleta BG 0
+        cpu.bus.write(0xFF40, 0x00);
+        for row in 0..8 {
+            cpu.bus.write(0x8000 + row * 2, 0xFF);
+            cpu.bus.write(0x8000 + row * 2 + 1, 0x00);
+        }
+        for addr in 0x9800..0x9C00 {
+            cpu.bus.write(addr, 0x00);
+        }
+        cpu.bus.write(0xFF47, 0xE4);
+        cpu.bus.write(0xFF40, 0x91);
+        for _ in 0..200 {
+            cpu.bus.tick(4);
+        }
+        assert_eq!(cpu.bus.ppu.framebuffer[0], 1);
+        assert_eq!(cpu.bus.ppu.rgb_framebuffer[0], 0x421F);
+    }
+
+    #[test]
+    fn test_dmg_model_keeps_grey_shades() {
+        let cpu = CPU::with_model(dmg_rom("POKEMON RED", 0x01), BootModel::DmgAbc);
+        assert!(!cpu.bus.ppu.cgb_hardware);
+    }
+}
@@ -0,0 +1,108 @@
+// Integration tests para o modo de velocidade dupla do CGB
+// cargo test double_speed_test
+
+#[cfg(test)]
+mod double_speed_tests {
+    use gb_emu::GB::CPU::CPU;
+
+    // ROM CGB com STOP em 0x0100
+    fn cgb_cpu() -> CPU {
+        let mut rom = vec![0x00; 32 * 1024];
+        rom[0x0143] = 0xC0;
+        rom[0x0100] = 0x10; // STOP
+        rom[0x0101] = 0x00;
+        let mut cpu = CPU::new(rom);
+        cpu.init_post_boot();
+        cpu
+    }
+
+    fn switch_to_double_speed(cpu: &mut CPU) {
+        cpu.bus.write(0xFF4D, 0x01);
+        cpu.execute_next();
+        // Consome a pausa da troca de velocidade
+        cpu.execute_next();
+    }
+
+    #[test]
+    fn test_stop_with_key1_switches_speed() {
+        let mut cpu = cgb_cpu();
+        cpu.bus.write(0xFF4D, 0x01);
+        assert_eq!(cpu.bus.read(0xFF4D), 0x7F);
+
+        cpu.execute_next();
+        assert!(cpu.bus.cgb_speed);
+        assert_eq!(cpu.bus.read(0xFF4D), 0xFE);
+    }
+
+    #[test]
+    fn test_speed_switch_stalls_cpu_and_resets_div() {
+        let mut cpu = cgb_cpu();
+        cpu.bus.tick(4096);
+        assert_ne!(cpu.bus.read(0xFF04), 0);
+
+        cpu.bus.write(0xFF4D, 0x01);
+        cpu.execute_next();
+        assert_eq!(cpu.bus.read(0xFF04), 0);
+        let pc = cpu.registers.get_pc();
+
+        // 2050 M-cycles parada, sem executar instruções
+        let (cycles, _) = cpu.execute_next();
+        assert_eq!(cycles, 2050 * 4);
+        assert_eq!(cpu.registers.get_pc(), pc);
+    }
+
+    #[test]
+    fn test_stop_without_key1_keeps_speed() {
+        let mut cpu = cgb_cpu();
+        cpu.execute_next();
+        assert!(!cpu.bus.cgb_speed);
+    }
+
+    #[test]
+    fn test_ppu_runs_at_half_rate_in_double_speed() {
+        let mut cpu = cgb_cpu();
+        switch_to_double_speed(&mut cpu);
+
+        // Uma linha tem 456 dots: em velocidade dupla são 912 ciclos de CPU
+        cpu.bus.write(0xFF40, 0x00);
+        cpu.bus.write(0xFF40, 0x91);
+        assert_eq!(cpu.bus.ppu.ly, 0);
+        cpu.bus.tick(456);
+        assert_eq!(cpu.bus.ppu.ly, 0);
+        cpu.bus.tick(456);
+        assert_eq!(cpu.bus.ppu.ly, 1);
+    }
+
+    #[test]
+    fn test_timer_follows_cpu_clock_in_double_speed() {
+        let mut cpu = cgb_cpu();
+        switch_to_double_speed(&mut cpu);
+
+        // DIV incrementa a cada 256 ciclos de CPU em qualquer velocidade
+        cpu.bus.write(0xFF04, 0);
+        cpu.bus.tick(256);
+        assert_eq!(cpu.bus.read(0xFF04), 1);
+    }
+
+    #[test]
+    fn test_serial_fast_clock_in_cgb_mode() {
+        let mut cpu = cgb_cpu();
+        cpu.bus.write(0xFF01, 0x42);
+        cpu.bus.write(0xFF02, 0x83);
+        assert_eq!(cpu.bus.read(0xFF02), 0xFF);
+
+        // 8 bits a 16 ciclos cada
+        cpu.bus.tick(128);
+        assert_eq!(cpu.bus.read(0xFF02) & 0x80, 0);
+        assert_ne!(cpu.bus.read(0xFF0F) & 0x08, 0);
+    }
+
+    #[test]
+    fn test_serial_normal_clock_is_slow() {
+        let mut cpu = cgb_cpu();
+        cpu.bus.write(0xFF02, 0x81);
+        assert_eq!(cpu.bus.read(0xFF02), 0xFD);
+        cpu.bus.tick(128);
+        assert_ne!(cpu.bus.read(0xFF02) & 0x80, 0);
+    }
+}
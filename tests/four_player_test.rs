@@ -0,0 +1,198 @@
+// Integration tests para o Four Player Adapter (DMG-07)
+// cargo test four_player_test
+
+#[cfg(test)]
+mod four_player_tests {
+    use gb_emu::GB::CPU::{BootModel, CPU};
+    use gb_emu::GB::four_player::{FourPlayerAdapter, Phase};
+    use gb_emu::GB::serial::SerialPort;
+
+    type Responder = Box<dyn Fn(&[u8]) -> u8>;
+
+    /// Só a porta serial de um Game Boy em external clock: rearma depois de
+    /// cada byte com a resposta escolhida pelo que já chegou
+    struct FakeGb {
+        port: SerialPort,
+        got: Vec<u8>,
+        respond: Responder,
+    }
+
+    impl FakeGb {
+        fn new(adapter: &mut FourPlayerAdapter, player: usize, respond: Responder) -> Self {
+            let mut port = SerialPort::new();
+            port.set_link(Some(Box::new(adapter.attach(player))));
+            let mut gb = FakeGb {
+                port,
+                got: Vec::new(),
+                respond,
+            };
+            gb.arm();
+            gb
+        }
+
+        fn arm(&mut self) {
+            let byte = (self.respond)(&self.got);
+            self.port.write_sb(byte);
+            self.port.write_sc(0x80, false, 0);
+        }
+
+        fn step(&mut self, cycles: u32) {
+            if self.port.step(cycles, false) {
+                self.got.push(self.port.read_sb());
+                self.arm();
+            }
+        }
+    }
+
+    fn run(adapter: &mut FourPlayerAdapter, gbs: &mut [FakeGb], cycles: u32) {
+        for _ in 0..cycles / 16 {
+            adapter.step(16);
+            for gb in gbs.iter_mut() {
+                gb.step(16);
+            }
+        }
+    }
+
+    /// Resposta do ping para o próximo byte do pacote: ACK1, ACK2, RATE, SIZE
+    fn ping_reply(got: &[u8]) -> u8 {
+        let next = match got.iter().rposition(|&b| b == 0xFE) {
+            Some(header) => got.len() - header,
+            None => 0,
+        };
+        [0x88, 0x88, 0x00, 0x04][next % 4]
+    }
+
+    /// Início da transmissão: logo depois dos quatro 0xCC
+    fn transmission_start(got: &[u8]) -> Option<usize> {
+        got.windows(4).position(|w| w == [0xCC; 4]).map(|i| i + 4)
+    }
+
+    /// Ping até o jogador 1 pedir o início; na transmissão cada jogador manda
+    /// `data(player, posição)` nos 4 primeiros bytes do ciclo
+    fn player(index: usize, data: fn(usize, usize) -> u8) -> Responder {
+        Box::new(move |got: &[u8]| match transmission_start(got) {
+            Some(start) => match (got.len() - start) % 16 {
+                pos if pos < 4 => data(index, pos),
+                _ => 0x00,
+            },
+            None if index == 0 && got.len() >= 8 => 0xAA,
+            None => ping_reply(got),
+        })
+    }
+
+    #[test]
+    fn test_ping_reports_connected_players_and_ids() {
+        let mut adapter = FourPlayerAdapter::new();
+        let mut gbs: Vec<FakeGb> = (0..3)
+            .map(|p| FakeGb::new(&mut adapter, p, Box::new(ping_reply)))
+            .collect();
+        run(&mut adapter, &mut gbs, 8192 * 10);
+
+        assert_eq!(adapter.phase(), Phase::Ping);
+        assert_eq!(adapter.connected(), 0x07);
+        // STAT ganha o nibble dos conectados assim que os dois ACKs chegam
+        assert_eq!(
+            gbs[0].got[..8],
+            [0xFE, 0x01, 0x71, 0x71, 0xFE, 0x71, 0x71, 0x71]
+        );
+        assert_eq!(
+            gbs[2].got[..8],
+            [0xFE, 0x03, 0x73, 0x73, 0xFE, 0x73, 0x73, 0x73]
+        );
+    }
+
+    #[test]
+    fn test_transmission_broadcasts_previous_packets() {
+        let mut adapter = FourPlayerAdapter::new();
+        let data = |player: usize, pos: usize| ((player as u8 + 1) << 4) | pos as u8;
+        let mut gbs: Vec<FakeGb> = (0..4)
+            .map(|p| FakeGb::new(&mut adapter, p, player(p, data)))
+            .collect();
+        run(&mut adapter, &mut gbs, 8192 * 60);
+
+        assert_eq!(adapter.phase(), Phase::Transmission);
+        let expected: Vec<u8> = (0..4)
+            .flat_map(|p| (0..4).map(move |pos| data(p, pos)))
+            .collect();
+        for gb in &gbs {
+            let start = transmission_start(&gb.got).unwrap();
+            // O primeiro ciclo ainda não tem dados de ninguém
+            assert_eq!(gb.got[start..start + 16], [0u8; 16]);
+            assert_eq!(gb.got[start + 16..start + 32], expected[..]);
+        }
+    }
+
+    #[test]
+    fn test_missing_player_slot_is_zero_and_restart_returns_to_ping() {
+        let mut adapter = FourPlayerAdapter::new();
+        let data = |player: usize, _pos: usize| if player == 0 { 0xFF } else { 0x42 };
+        let mut gbs: Vec<FakeGb> = (0..2)
+            .map(|p| FakeGb::new(&mut adapter, p, player(p, data)))
+            .collect();
+        run(&mut adapter, &mut gbs, 8192 * 24);
+        assert_eq!(adapter.phase(), Phase::Transmission);
+
+        // Quatro 0xFF do jogador 1 no primeiro ciclo: volta ao ping
+        run(&mut adapter, &mut gbs, 8192 * 16);
+        assert_eq!(adapter.phase(), Phase::Ping);
+        let got = &gbs[1].got;
+        let start = transmission_start(got).unwrap();
+        assert_eq!(got.get(start + 16), Some(&0xFE));
+    }
+
+    /// Arma com 0x88 oito vezes e guarda o que chega em C000
+    fn ping_rom() -> Vec<u8> {
+        let mut rom = vec![0u8; 32 * 1024];
+        rom[0x0100..0x0104].copy_from_slice(&[0x00, 0xC3, 0x50, 0x01]);
+        #[rustfmt::skip]
+        let program = [
+            0x21, 0x00, 0xC0,       // LD HL,C000
+            // loop:
+            0x3E, 0x88,             // LD A,88
+            0xE0, 0x01,             // LDH (SB),A
+            0x3E, 0x80,             // LD A,80
+            0xE0, 0x02,             // LDH (SC),A
+            // wait:
+            0xF0, 0x02,             // LDH A,(SC)
+            0xCB, 0x7F,             // BIT 7,A
+            0x20, 0xFA,             // JR NZ,wait
+            0xF0, 0x01,             // LDH A,(SB)
+            0x22,                   // LD (HL+),A
+            0x7D,                   // LD A,L
+            0xFE, 0x08,             // CP 8
+            0x20, 0xEA,             // JR NZ,loop
+            // done:
+            0x18, 0xFE,             // JR done
+        ];
+        rom[0x0150..0x0150 + program.len()].copy_from_slice(&program);
+        rom
+    }
+
+    #[test]
+    fn test_cpus_answer_ping_through_adapter() {
+        let mut adapter = FourPlayerAdapter::new();
+        let mut cpus: Vec<CPU> = (0..2)
+            .map(|_| CPU::with_model(ping_rom(), BootModel::DmgAbc))
+            .collect();
+        for (player, cpu) in cpus.iter_mut().enumerate() {
+            adapter.connect(player, cpu);
+        }
+        adapter.run(&mut cpus, 150_000);
+
+        let received = |cpu: &mut CPU| {
+            (0xC000..0xC008)
+                .map(|a| cpu.bus.read(a))
+                .collect::<Vec<u8>>()
+        };
+        assert_eq!(
+            received(&mut cpus[0]),
+            [0xFE, 0x01, 0x31, 0x31, 0xFE, 0x31, 0x31, 0x31]
+        );
+        assert_eq!(
+            received(&mut cpus[1]),
+            [0xFE, 0x02, 0x32, 0x32, 0xFE, 0x32, 0x32, 0x32]
+        );
+        // Depois dos 8 bytes as CPUs param de responder e saem do ping
+        assert_eq!(adapter.connected(), 0x00);
+    }
+}
@@ -0,0 +1,97 @@
+use gb_emu::GB::CPU::CPU;
+use gb_emu::GB::infrared::LocalIrPeer;
+use gb_emu::GB::mbc::{MBC, create_mbc, huc1::HuC1};
+
+fn huc1_rom() -> Vec<u8> {
+    let mut rom = vec![0u8; 1024 * 1024];
+    rom[0x0147] = 0xFF; // HuC1+RAM+BATTERY
+    rom[0x0149] = 0x03;
+    rom
+}
+
+#[test]
+fn test_huc1_selected_for_cart_type_0xff() {
+    let mut rom = huc1_rom();
+    rom[0x3F * 0x4000] = 0x3F;
+    let mut mbc = create_mbc(rom);
+    mbc.write_register(0x2000, 0x3F);
+    assert_eq!(mbc.read_rom(0x4000), 0x3F);
+    assert_eq!(mbc.save_ram().map(|r| r.len()), Some(32 * 1024));
+}
+
+#[test]
+fn test_huc1_rom_and_ram_banking() {
+    let mut rom = huc1_rom();
+    rom[0x4000] = 0x01;
+    rom[5 * 0x4000] = 0x05;
+    let mut mbc = HuC1::new(rom, 32 * 1024);
+
+    // Banco 0 vira banco 1
+    mbc.write_register(0x2000, 0x00);
+    assert_eq!(mbc.read_rom(0x4000), 0x01);
+    mbc.write_register(0x2000, 0x05);
+    assert_eq!(mbc.read_rom(0x4000), 0x05);
+
+    mbc.write_register(0x4000, 0x03);
+    mbc.write_ram(0xA123, 0x99);
+    mbc.write_register(0x4000, 0x00);
+    assert_eq!(mbc.read_ram(0xA123), 0x00);
+    mbc.write_register(0x4000, 0x03);
+    assert_eq!(mbc.read_ram(0xA123), 0x99);
+}
+
+#[test]
+fn test_huc1_ir_mode_hides_ram() {
+    let mut mbc = HuC1::new(huc1_rom(), 32 * 1024);
+    mbc.write_ram(0xA000, 0x42);
+
+    mbc.write_register(0x0000, 0x0E);
+    assert_eq!(mbc.read_ram(0xA000), 0xC0);
+    mbc.write_ram(0xA000, 0x01); // LED, não RAM
+
+    mbc.write_register(0x0000, 0x0A);
+    assert_eq!(mbc.read_ram(0xA000), 0x42);
+
+    // Só 0x0E exato liga o IR
+    mbc.write_register(0x0000, 0x1E);
+    assert_eq!(mbc.read_ram(0xA000), 0x42);
+}
+
+#[test]
+fn test_huc1_ir_link_between_two_instances() {
+    let mut a = CPU::new(huc1_rom());
+    let mut b = CPU::new(huc1_rom());
+    let (peer_a, peer_b) = LocalIrPeer::pair();
+    a.bus.set_cart_ir_peer(Some(Box::new(peer_a)));
+    b.bus.set_cart_ir_peer(Some(Box::new(peer_b)));
+
+    a.bus.write(0x0000, 0x0E);
+    b.bus.write(0x0000, 0x0E);
+    assert_eq!(b.bus.read(0xA000), 0xC0);
+
+    // LED de A aceso: B enxerga luz, A não
+    a.bus.write(0xA000, 0x01);
+    assert_eq!(b.bus.read(0xA000), 0xC1);
+    assert_eq!(a.bus.read(0xA000), 0xC0);
+
+    a.bus.write(0xA000, 0x00);
+    assert_eq!(b.bus.read(0xA000), 0xC0);
+}
+
+#[test]
+fn test_huc1_save_state_restores_led() {
+    let mut a = CPU::new(huc1_rom());
+    a.bus.write(0x0000, 0x0E);
+    a.bus.write(0xA000, 0x01);
+    let state = a.save_state();
+
+    let mut other = CPU::new(huc1_rom());
+    let mut watcher = CPU::new(huc1_rom());
+    let (peer_a, peer_b) = LocalIrPeer::pair();
+    other.bus.set_cart_ir_peer(Some(Box::new(peer_a)));
+    watcher.bus.set_cart_ir_peer(Some(Box::new(peer_b)));
+    other.load_state(&state).unwrap();
+
+    watcher.bus.write(0x0000, 0x0E);
+    assert_eq!(watcher.bus.read(0xA000), 0xC1);
+}
@@ -0,0 +1,150 @@
+use gb_emu::GB::mbc::{MBC, create_mbc, huc3::HuC3};
+
+fn huc3() -> HuC3 {
+    let mut rom = vec![0u8; 512 * 1024];
+    rom[0x0147] = 0xFE;
+    HuC3::new(rom, 32 * 1024)
+}
+
+fn command(mbc: &mut HuC3, value: u8) {
+    mbc.write_register(0x0000, 0x0B);
+    mbc.write_ram(0xA000, value);
+}
+
+fn response(mbc: &mut HuC3) -> u8 {
+    mbc.write_register(0x0000, 0x0C);
+    mbc.read_ram(0xA000)
+}
+
+/// Grava minutos e dias pelo protocolo: endereço 0, 7 nibbles e comando 0x61
+fn set_clock(mbc: &mut HuC3, minutes: u16, days: u16) {
+    command(mbc, 0x40);
+    command(mbc, 0x50);
+    for i in 0..3 {
+        command(mbc, 0x30 | ((minutes >> (i * 4)) & 0x0F) as u8);
+    }
+    for i in 0..4 {
+        command(mbc, 0x30 | ((days >> (i * 4)) & 0x0F) as u8);
+    }
+    command(mbc, 0x61);
+}
+
+/// Comando 0x60 e leitura dos 7 nibbles com 0x1x
+fn read_clock(mbc: &mut HuC3) -> (u16, u16) {
+    command(mbc, 0x60);
+    command(mbc, 0x40);
+    command(mbc, 0x50);
+    let mut nibbles = [0u16; 7];
+    for n in nibbles.iter_mut() {
+        command(mbc, 0x10);
+        let value = response(mbc);
+        assert_eq!(value & 0xF0, 0x10);
+        *n = (value & 0x0F) as u16;
+    }
+    let minutes = nibbles[0] | (nibbles[1] << 4) | (nibbles[2] << 8);
+    let days = nibbles[3] | (nibbles[4] << 4) | (nibbles[5] << 8) | (nibbles[6] << 12);
+    (minutes, days)
+}
+
+#[test]
+fn test_huc3_selected_for_cart_type_0xfe() {
+    let mut rom = vec![0u8; 128 * 1024];
+    rom[0x0147] = 0xFE;
+    rom[0x0149] = 0x03;
+    rom[5 * 0x4000] = 0x55;
+    let mut mbc = create_mbc(rom);
+    mbc.write_register(0x2000, 0x05);
+    assert_eq!(mbc.read_rom(0x4000), 0x55);
+    // 32KB de RAM + 4 bytes de relógio + timestamp
+    assert_eq!(mbc.save_ram().map(|r| r.len()), Some(32 * 1024 + 12));
+}
+
+#[test]
+fn test_huc3_ram_modes_and_banking() {
+    let mut mbc = huc3();
+    mbc.write_register(0x0000, 0x0A);
+    mbc.write_register(0x4000, 0x02);
+    mbc.write_ram(0xA010, 0x77);
+    mbc.write_register(0x4000, 0x00);
+    assert_eq!(mbc.read_ram(0xA010), 0x00);
+    mbc.write_register(0x4000, 0x02);
+    assert_eq!(mbc.read_ram(0xA010), 0x77);
+
+    // Modo 0: RAM só leitura
+    mbc.write_register(0x0000, 0x00);
+    mbc.write_ram(0xA010, 0x11);
+    assert_eq!(mbc.read_ram(0xA010), 0x77);
+}
+
+#[test]
+fn test_huc3_rtc_command_protocol() {
+    let mut mbc = huc3();
+    set_clock(&mut mbc, 1439, 300);
+    assert_eq!(read_clock(&mut mbc), (1439, 300));
+
+    // Nibbles soltos: 0x2x escreve sem avançar
+    command(&mut mbc, 0x45);
+    command(&mut mbc, 0x5A);
+    command(&mut mbc, 0x29);
+    command(&mut mbc, 0x10);
+    assert_eq!(response(&mut mbc), 0x19);
+}
+
+#[test]
+fn test_huc3_status_semaphore_and_ir_stub() {
+    let mut mbc = huc3();
+    command(&mut mbc, 0x62);
+    assert_eq!(response(&mut mbc), 0x61);
+
+    mbc.write_register(0x0000, 0x0D);
+    assert_eq!(mbc.read_ram(0xA000) & 0x01, 0x01);
+
+    // IR: LED aceita escrita, nenhum sinal recebido
+    mbc.write_register(0x0000, 0x0E);
+    mbc.write_ram(0xA000, 0x01);
+    assert_eq!(mbc.read_ram(0xA000), 0xC0);
+}
+
+#[test]
+fn test_huc3_clock_persists_in_sav_and_advances() {
+    let mut mbc = huc3();
+    mbc.write_register(0x0000, 0x0A);
+    mbc.write_ram(0xA000, 0x42);
+    set_clock(&mut mbc, 1430, 7);
+
+    let mut sav = mbc.save_ram().unwrap();
+    let ram_len = 32 * 1024;
+    assert_eq!(sav[0], 0x42);
+    assert_eq!(&sav[ram_len..ram_len + 4], &[0x96, 0x05, 0x07, 0x00]);
+
+    // Timestamp de 15 minutos atrás: passa da meia-noite
+    let ts_pos = ram_len + 4;
+    let mut ts = [0u8; 8];
+    ts.copy_from_slice(&sav[ts_pos..ts_pos + 8]);
+    let saved = i64::from_le_bytes(ts) - 15 * 60;
+    sav[ts_pos..ts_pos + 8].copy_from_slice(&saved.to_le_bytes());
+
+    let mut other = huc3();
+    other.load_ram(&sav);
+    let (minutes, days) = read_clock(&mut other);
+    assert_eq!(days, 8);
+    assert!((5..=6).contains(&minutes));
+    other.write_register(0x0000, 0x0A);
+    assert_eq!(other.read_ram(0xA000), 0x42);
+}
+
+#[test]
+fn test_huc3_save_state() {
+    use gb_emu::GB::savestate::{StateReader, StateWriter};
+
+    let mut mbc = huc3();
+    set_clock(&mut mbc, 100, 2);
+    mbc.write_register(0x2000, 0x03);
+    let mut w = StateWriter::new();
+    mbc.save_state(&mut w);
+    let data = w.into_inner();
+
+    let mut other = huc3();
+    other.load_state(&mut StateReader::new(&data)).unwrap();
+    assert_eq!(read_clock(&mut other), (100, 2));
+}
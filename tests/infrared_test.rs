@@ -0,0 +1,169 @@
+// Integration tests para a porta infravermelha do CGB (FF56)
+// cargo test infrared_test
+
+#[cfg(test)]
+mod infrared_tests {
+    use gb_emu::GB::CPU::{BootModel, CPU};
+    use gb_emu::GB::infrared::{IrPeer, IrStream, LocalIrPeer};
+
+    /// LED aceso, leitura habilitada
+    const LED_ON: u8 = 0xC1;
+    const LED_OFF: u8 = 0xC0;
+
+    fn cgb() -> CPU {
+        let mut rom = vec![0u8; 32 * 1024];
+        rom[0x0143] = 0xC0;
+        let cpu = CPU::with_model(rom, BootModel::Cgb);
+        assert!(cpu.bus.cgb_mode);
+        cpu
+    }
+
+    fn linked() -> (CPU, CPU) {
+        let mut a = cgb();
+        let mut b = cgb();
+        let (peer_a, peer_b) = LocalIrPeer::pair();
+        a.bus.set_ir_peer(Some(Box::new(peer_a)));
+        b.bus.set_ir_peer(Some(Box::new(peer_b)));
+        (a, b)
+    }
+
+    fn receiving(cpu: &CPU) -> bool {
+        cpu.bus.read(0xFF56) & 0x02 == 0
+    }
+
+    #[test]
+    fn test_two_instances_see_each_other_led() {
+        let (mut a, mut b) = linked();
+        b.bus.write(0xFF56, LED_OFF);
+        b.bus.tick(16);
+        assert!(!receiving(&b));
+        assert_eq!(b.bus.read(0xFF56), 0xFE);
+
+        a.bus.write(0xFF56, LED_ON);
+        b.bus.tick(16);
+        assert!(receiving(&b));
+        assert_eq!(b.bus.read(0xFF56), 0xFC);
+        assert_eq!(a.bus.read(0xFF56), 0xFF);
+
+        a.bus.write(0xFF56, LED_OFF);
+        b.bus.tick(16);
+        assert!(!receiving(&b));
+    }
+
+    #[test]
+    fn test_read_disabled_reports_no_light() {
+        let (mut a, mut b) = linked();
+        a.bus.write(0xFF56, LED_ON);
+        b.bus.write(0xFF56, 0x00);
+        b.bus.tick(16);
+        assert_eq!(b.bus.read(0xFF56), 0x3E);
+
+        // Habilitar depois enxerga a luz que já estava lá
+        b.bus.write(0xFF56, LED_OFF);
+        assert!(receiving(&b));
+    }
+
+    #[test]
+    fn test_constant_light_fades() {
+        let (mut a, mut b) = linked();
+        a.bus.write(0xFF56, LED_ON);
+        b.bus.write(0xFF56, LED_OFF);
+        b.bus.tick(4_000);
+        assert!(receiving(&b));
+        b.bus.tick(20_000);
+        assert!(!receiving(&b));
+
+        // Piscar de novo volta a ser detectado
+        a.bus.write(0xFF56, LED_OFF);
+        b.bus.tick(16);
+        a.bus.write(0xFF56, LED_ON);
+        b.bus.tick(16);
+        assert!(receiving(&b));
+    }
+
+    #[test]
+    fn test_dmg_mode_has_no_ir_port() {
+        let mut cpu = CPU::with_model(vec![0u8; 32 * 1024], BootModel::DmgAbc);
+        let (peer, mut other) = LocalIrPeer::pair();
+        cpu.bus.set_ir_peer(Some(Box::new(peer)));
+        other.set_led(true);
+        cpu.bus.write(0xFF56, LED_ON);
+        cpu.bus.tick(16);
+        assert_eq!(cpu.bus.read(0xFF56), 0xFF);
+        assert!(!other.light_detected());
+    }
+
+    #[test]
+    fn test_stream_replays_light_and_records_led() {
+        let stream = IrStream::parse("# luz de teste\n100 1\n\n5000 0 # apaga\n").unwrap();
+        let mut cpu = cgb();
+        cpu.bus.set_ir_peer(Some(Box::new(stream)));
+        cpu.bus.write(0xFF56, LED_OFF);
+
+        cpu.bus.tick(96);
+        assert!(!receiving(&cpu));
+        cpu.bus.tick(8);
+        assert!(receiving(&cpu));
+        cpu.bus.write(0xFF56, LED_ON);
+        cpu.bus.tick(4_896);
+        assert!(!receiving(&cpu));
+
+        assert!(IrStream::parse("100 2").is_err());
+        assert!(IrStream::parse("abc 1").is_err());
+    }
+
+    #[test]
+    fn test_stream_writes_recording_on_drop() {
+        let path = std::env::temp_dir().join(format!("gb_emu_ir_{}.txt", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let mut stream = IrStream::new(Vec::new()).record_to(&path);
+        stream.set_led(true);
+        stream.tick(300);
+        stream.set_led(true);
+        stream.set_led(false);
+        assert_eq!(stream.recorded(), [(0, true), (300, false)]);
+        drop(stream);
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text, "0 1\n300 0\n");
+        // A gravação serve de entrada para outra sessão
+        let mut replay = IrStream::load(&path).unwrap();
+        assert!(replay.light_detected());
+        replay.tick(300);
+        assert!(!replay.light_detected());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_flush_writes_recording_without_drop() {
+        let path = std::env::temp_dir().join(format!("gb_emu_ir_flush_{}.txt", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let mut cpu = cgb();
+        cpu.bus
+            .set_ir_peer(Some(Box::new(IrStream::new(Vec::new()).record_to(&path))));
+        cpu.bus.tick(100);
+        cpu.bus.write(0xFF56, LED_ON);
+
+        // Como no modo headless, que sai por process::exit sem Drop
+        cpu.bus.flush_ir();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "100 1\n");
+        std::mem::forget(cpu);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_save_state_restores_rp() {
+        let (mut a, mut b) = linked();
+        a.bus.write(0xFF56, LED_ON);
+        let state = a.save_state();
+        a.bus.write(0xFF56, LED_OFF);
+        b.bus.write(0xFF56, LED_OFF);
+        b.bus.tick(16);
+        assert!(!receiving(&b));
+
+        a.load_state(&state).unwrap();
+        assert_eq!(a.bus.read(0xFF56), 0xFF);
+        b.bus.tick(16);
+        assert!(receiving(&b));
+    }
+}
@@ -0,0 +1,171 @@
+// Integration tests para o cabo link entre duas instâncias
+// cargo test link_cable_test
+
+#[cfg(test)]
+mod link_cable_tests {
+    use gb_emu::GB::CPU::{BootModel, CPU};
+    use gb_emu::GB::netlink::{LinkListener, NetLink};
+    use gb_emu::GB::serial::{self, LinkEndpoint, LocalLink};
+    use std::thread;
+
+    const MASTER: u8 = 0x81;
+    const SLAVE: u8 = 0x80;
+
+    /// Programa de troca: envia 4 bytes de 0x0200 e guarda os recebidos em
+    /// C000. O papel (SC) fica em 0x0300 e a espera antes de cada byte em
+    /// 0x0301, para o slave armar antes do master mandar o clock
+    fn trade_rom(role: u8, delay: u8, items: [u8; 4]) -> Vec<u8> {
+        let mut rom = vec![0u8; 32 * 1024];
+        rom[0x0100..0x0104].copy_from_slice(&[0x00, 0xC3, 0x50, 0x01]);
+        #[rustfmt::skip]
+        let program = [
+            0x21, 0x00, 0xC0,       // LD HL,C000
+            0x11, 0x00, 0x02,       // LD DE,0200
+            // loop:
+            0xFA, 0x01, 0x03,       // LD A,(0301)
+            0x47,                   // LD B,A
+            // delay:
+            0x05,                   // DEC B
+            0x20, 0xFD,             // JR NZ,delay
+            0x1A,                   // LD A,(DE)
+            0xE0, 0x01,             // LDH (SB),A
+            0xFA, 0x00, 0x03,       // LD A,(0300)
+            0xE0, 0x02,             // LDH (SC),A
+            // wait:
+            0xF0, 0x02,             // LDH A,(SC)
+            0xCB, 0x7F,             // BIT 7,A
+            0x20, 0xFA,             // JR NZ,wait
+            0xF0, 0x01,             // LDH A,(SB)
+            0x22,                   // LD (HL+),A
+            0x13,                   // INC DE
+            0x7B,                   // LD A,E
+            0xFE, 0x04,             // CP 4
+            0x20, 0xE2,             // JR NZ,loop
+            // done:
+            0x18, 0xFE,             // JR done
+        ];
+        rom[0x0150..0x0150 + program.len()].copy_from_slice(&program);
+        rom[0x0200..0x0204].copy_from_slice(&items);
+        rom[0x0300] = role;
+        rom[0x0301] = delay;
+        rom
+    }
+
+    fn received(cpu: &mut CPU) -> [u8; 4] {
+        [0xC000, 0xC001, 0xC002, 0xC003].map(|addr| cpu.bus.read(addr))
+    }
+
+    #[test]
+    fn test_two_instances_trade_bytes() {
+        let mut master = CPU::with_model(
+            trade_rom(MASTER, 0x40, [0x11, 0x22, 0x33, 0x44]),
+            BootModel::DmgAbc,
+        );
+        let mut slave = CPU::with_model(
+            trade_rom(SLAVE, 0x01, [0xA1, 0xB2, 0xC3, 0xD4]),
+            BootModel::DmgAbc,
+        );
+        serial::connect(&mut master, &mut slave);
+
+        serial::run_linked(&mut master, &mut slave, 100_000);
+
+        assert_eq!(received(&mut master), [0xA1, 0xB2, 0xC3, 0xD4]);
+        assert_eq!(received(&mut slave), [0x11, 0x22, 0x33, 0x44]);
+        assert_eq!(master.bus.serial.output_buffer, [0x11, 0x22, 0x33, 0x44]);
+        assert_eq!(slave.bus.serial.output_buffer, [0xA1, 0xB2, 0xC3, 0xD4]);
+    }
+
+    /// Roda o programa de troca num processo simulado (thread própria) e
+    /// devolve o que chegou pelo cabo
+    fn run_over_net(rom: Vec<u8>, link: NetLink) -> [u8; 4] {
+        let mut cpu = CPU::with_model(rom, BootModel::DmgAbc);
+        cpu.bus.set_link_cable(Some(Box::new(link)));
+        let mut cycles = 0;
+        while cycles < 100_000 {
+            cycles += cpu.execute_next().0;
+        }
+        // Desconecta para o outro lado não esperar mais por este
+        cpu.bus.set_link_cable(None);
+        received(&mut cpu)
+    }
+
+    fn trade_over(listener: LinkListener) {
+        let addr = listener.local_addr();
+        let master = thread::spawn(move || {
+            let link = listener.accept().unwrap();
+            run_over_net(trade_rom(MASTER, 0x40, [0x11, 0x22, 0x33, 0x44]), link)
+        });
+        let link = NetLink::connect(&addr).unwrap();
+        let slave = run_over_net(trade_rom(SLAVE, 0x01, [0xA1, 0xB2, 0xC3, 0xD4]), link);
+
+        assert_eq!(master.join().unwrap(), [0xA1, 0xB2, 0xC3, 0xD4]);
+        assert_eq!(slave, [0x11, 0x22, 0x33, 0x44]);
+    }
+
+    #[test]
+    fn test_trade_over_tcp() {
+        trade_over(LinkListener::bind("127.0.0.1:0").unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_trade_over_unix_socket() {
+        let path = std::env::temp_dir().join(format!("gb_emu_link_{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        trade_over(LinkListener::bind(&format!("unix:{}", path.display())).unwrap());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_connect_to_missing_peer_fails() {
+        assert!(NetLink::connect("unix:/nonexistent/gb_emu.sock").is_err());
+    }
+
+    #[test]
+    fn test_master_without_cable_receives_ff() {
+        let mut cpu = CPU::with_model(vec![0u8; 32 * 1024], BootModel::DmgAbc);
+        cpu.bus.write(0xFF01, 0x5A);
+        cpu.bus.write(0xFF02, 0x81);
+        cpu.bus.tick(4096);
+        assert_eq!(cpu.bus.read(0xFF02) & 0x80, 0);
+        assert_eq!(cpu.bus.read(0xFF01), 0xFF);
+        assert_ne!(cpu.bus.read(0xFF0F) & 0x08, 0);
+    }
+
+    #[test]
+    fn test_slave_waits_for_external_clock() {
+        let mut cpu = CPU::with_model(vec![0u8; 32 * 1024], BootModel::DmgAbc);
+        let (link, mut peer) = LocalLink::pair();
+        cpu.bus.set_link_cable(Some(Box::new(link)));
+        cpu.bus.write(0xFF01, 0x80);
+        cpu.bus.write(0xFF02, 0x80);
+
+        // Sem clock do outro lado nada acontece, mas o bit 7 fica ligado
+        cpu.bus.tick(100_000);
+        assert_eq!(cpu.bus.read(0xFF02), 0xFE);
+
+        // O peer faz papel de master: 8 pulsos levando 0x3C
+        let mut got = 0u8;
+        for i in 0..8 {
+            let bit = (0x3C >> (7 - i)) & 1 != 0;
+            got = (got << 1) | peer.clock_out(bit, 0) as u8;
+            cpu.bus.tick(4);
+        }
+        assert_eq!(got, 0x80);
+        assert_eq!(cpu.bus.read(0xFF01), 0x3C);
+        assert_eq!(cpu.bus.read(0xFF02) & 0x80, 0);
+        assert_ne!(cpu.bus.read(0xFF0F) & 0x08, 0);
+    }
+
+    #[test]
+    fn test_clearing_start_flag_aborts_transfer() {
+        let mut cpu = CPU::with_model(vec![0u8; 32 * 1024], BootModel::DmgAbc);
+        cpu.bus.write(0xFF01, 0x12);
+        cpu.bus.write(0xFF02, 0x81);
+        cpu.bus.tick(1024);
+        cpu.bus.write(0xFF02, 0x01);
+        cpu.bus.tick(8192);
+        assert_eq!(cpu.bus.read(0xFF0F) & 0x08, 0);
+        assert!(cpu.bus.serial.output_buffer.is_empty());
+    }
+}
@@ -174,3 +174,73 @@ fn test_mbc3_save_and_load_ram_with_rtc() {
     assert_eq!(m, 10, "RTC minutes should be restored from save");
     assert_eq!(h, 1, "RTC hours should be restored from save");
 }
+
+#[test]
+fn test_mbc30_ram_banks_above_3() {
+    // MBC30: 64KB de RAM (8 bancos)
+    let rom = vec![0; 32 * 1024];
+    let mut mbc = MBC3::new(rom, 64 * 1024);
+    mbc.write_register(0x0000, 0x0A);
+
+    for bank in 0..8u8 {
+        mbc.write_register(0x4000, bank);
+        mbc.write_ram(0xA123, 0x40 + bank);
+    }
+    for bank in 0..8u8 {
+        mbc.write_register(0x4000, bank);
+        assert_eq!(mbc.read_ram(0xA123), 0x40 + bank);
+    }
+
+    let sav = mbc.save_ram().unwrap();
+    assert_eq!(sav[7 * 0x2000 + 0x123], 0x47);
+
+    // RTC continua em 0x08-0x0C
+    mbc.write_register(0x4000, 0x08);
+    mbc.write_ram(0xA000, 30);
+    mbc.write_register(0x6000, 0x00);
+    mbc.write_register(0x6000, 0x01);
+    assert_eq!(mbc.read_ram(0xA000), 30);
+}
+
+#[test]
+fn test_mbc3_regular_ignores_ram_banks_above_3() {
+    let rom = vec![0; 32 * 1024];
+    let mut mbc = MBC3::new(rom, 32 * 1024);
+    mbc.write_register(0x0000, 0x0A);
+
+    mbc.write_register(0x4000, 0x00);
+    mbc.write_ram(0xA000, 0x11);
+    mbc.write_register(0x4000, 0x04);
+    mbc.write_ram(0xA000, 0x99);
+    assert_eq!(mbc.read_ram(0xA000), 0xFF);
+
+    mbc.write_register(0x4000, 0x00);
+    assert_eq!(mbc.read_ram(0xA000), 0x11);
+}
+
+#[test]
+fn test_mbc30_rom_banks_above_127() {
+    // 4MB = 256 bancos
+    let mut rom = vec![0u8; 4 * 1024 * 1024];
+    for bank in [1usize, 0x80, 0xC3, 0xFF] {
+        rom[bank * 0x4000] = bank as u8;
+    }
+    let mut mbc = MBC3::new(rom, 32 * 1024);
+
+    for bank in [0x80u8, 0xC3, 0xFF] {
+        mbc.write_register(0x2000, bank);
+        assert_eq!(mbc.read_rom(0x4000), bank);
+    }
+    mbc.write_register(0x2000, 0x00);
+    assert_eq!(mbc.read_rom(0x4000), 1);
+}
+
+#[test]
+fn test_mbc3_regular_masks_rom_bank_to_7_bits() {
+    let mut rom = vec![0u8; 2 * 1024 * 1024];
+    rom[0x03 * 0x4000] = 0x03;
+    let mut mbc = MBC3::new(rom, 8 * 1024);
+
+    mbc.write_register(0x2000, 0x83);
+    assert_eq!(mbc.read_rom(0x4000), 0x03);
+}
@@ -24,3 +24,72 @@ fn test_mbc5_ram_enable_disable() {
     mbc.write_ram(0xA000, 0x99);
     assert_eq!(mbc.read_ram(0xA000), 0x99);
 }
+
+fn rumble_cart() -> MBC5 {
+    let mut rom = vec![0u8; 512 * 1024];
+    rom[0x0147] = 0x1E;
+    MBC5::new(rom, 32 * 1024)
+}
+
+#[test]
+fn test_mbc5_rumble_bit_does_not_select_ram_bank() {
+    let mut mbc = rumble_cart();
+    mbc.write_register(0x0000, 0x0A);
+
+    mbc.write_register(0x4000, 0x01);
+    mbc.write_ram(0xA000, 0x11);
+    // Bit 3 liga o motor mas continua no banco 1
+    mbc.write_register(0x4000, 0x09);
+    assert_eq!(mbc.read_ram(0xA000), 0x11);
+    mbc.write_ram(0xA001, 0x22);
+    mbc.write_register(0x4000, 0x01);
+    assert_eq!(mbc.read_ram(0xA001), 0x22);
+}
+
+#[test]
+fn test_mbc5_rumble_duty_per_frame() {
+    let mut mbc = rumble_cart();
+    assert_eq!(mbc.take_rumble_duty(), Some(0.0));
+
+    mbc.write_register(0x4000, 0x08);
+    mbc.tick(1000);
+    mbc.write_register(0x4000, 0x00);
+    mbc.tick(3000);
+    assert_eq!(mbc.take_rumble_duty(), Some(0.25));
+
+    // Acumuladores zeram a cada leitura
+    mbc.write_register(0x4000, 0x08);
+    mbc.tick(500);
+    assert_eq!(mbc.take_rumble_duty(), Some(1.0));
+}
+
+#[test]
+fn test_mbc5_without_rumble_keeps_16_ram_banks() {
+    let rom = vec![0u8; 512 * 1024];
+    let mut mbc = MBC5::new(rom, 128 * 1024);
+    assert_eq!(mbc.take_rumble_duty(), None);
+    mbc.write_register(0x0000, 0x0A);
+
+    mbc.write_register(0x4000, 0x09);
+    mbc.write_ram(0xA000, 0x99);
+    mbc.write_register(0x4000, 0x01);
+    assert_eq!(mbc.read_ram(0xA000), 0x00);
+    mbc.write_register(0x4000, 0x09);
+    assert_eq!(mbc.read_ram(0xA000), 0x99);
+}
+
+#[test]
+fn test_mbc5_rumble_motor_in_save_state() {
+    use gb_emu::GB::savestate::{StateReader, StateWriter};
+
+    let mut mbc = rumble_cart();
+    mbc.write_register(0x4000, 0x08);
+    let mut w = StateWriter::new();
+    mbc.save_state(&mut w);
+    let data = w.into_inner();
+
+    let mut other = rumble_cart();
+    other.load_state(&mut StateReader::new(&data)).unwrap();
+    other.tick(100);
+    assert_eq!(other.take_rumble_duty(), Some(1.0));
+}
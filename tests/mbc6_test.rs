@@ -0,0 +1,146 @@
+use gb_emu::GB::mbc::{MBC, create_mbc, mbc6::MBC6};
+
+/// ROM de 1MB em bancos de 8KB, cada um começando com o próprio número
+fn mbc6() -> MBC6 {
+    let mut rom = vec![0u8; 1024 * 1024];
+    for bank in 0..128 {
+        rom[bank * 0x2000] = bank as u8;
+    }
+    rom[0x0147] = 0x20;
+    MBC6::new(rom)
+}
+
+/// Mapeia a flash nas duas janelas (A = banco 2, B = banco 1), liberando escrita
+fn map_flash(mbc: &mut MBC6) {
+    mbc.write_register(0x0C00, 0x01);
+    mbc.write_register(0x1000, 0x01);
+    mbc.write_register(0x2000, 0x02);
+    mbc.write_register(0x2800, 0x08);
+    mbc.write_register(0x3000, 0x01);
+    mbc.write_register(0x3800, 0x08);
+}
+
+/// AA em 0x5555 (banco 2 + 0x1555) e 55 em 0x2AAA (banco 1 + 0x0AAA)
+fn unlock(mbc: &mut MBC6) {
+    mbc.write_register(0x5555, 0xAA);
+    mbc.write_register(0x6AAA, 0x55);
+}
+
+#[test]
+fn test_mbc6_selected_for_cart_type_0x20() {
+    let mut rom = vec![0u8; 1024 * 1024];
+    rom[0x0147] = 0x20;
+    let mbc = create_mbc(rom);
+    assert_eq!(
+        mbc.save_ram().map(|r| r.len()),
+        Some(32 * 1024 + 1024 * 1024)
+    );
+}
+
+#[test]
+fn test_mbc6_independent_rom_windows() {
+    let mut mbc = mbc6();
+    mbc.write_register(0x2000, 0x05);
+    mbc.write_register(0x3000, 0x7F);
+    assert_eq!(mbc.read_rom(0x4000), 5);
+    assert_eq!(mbc.read_rom(0x6000), 0x7F);
+    // 0000-3FFF fixo nos bancos 0-1
+    assert_eq!(mbc.read_rom(0x2000), 1);
+}
+
+#[test]
+fn test_mbc6_split_ram_banks() {
+    let mut mbc = mbc6();
+    mbc.write_register(0x0000, 0x0A);
+    mbc.write_register(0x0400, 0x03);
+    mbc.write_register(0x0800, 0x05);
+    mbc.write_ram(0xA010, 0x33);
+    mbc.write_ram(0xB010, 0x55);
+
+    // A mesma página vista pela outra janela
+    mbc.write_register(0x0800, 0x03);
+    assert_eq!(mbc.read_ram(0xB010), 0x33);
+    mbc.write_register(0x0400, 0x05);
+    assert_eq!(mbc.read_ram(0xA010), 0x55);
+}
+
+#[test]
+fn test_mbc6_flash_program_erase_and_id() {
+    let mut mbc = mbc6();
+    map_flash(&mut mbc);
+    assert_eq!(mbc.read_rom(0x4000), 0xFF);
+
+    // Programa 0x42 no início do banco 2
+    unlock(&mut mbc);
+    mbc.write_register(0x5555, 0xA0);
+    mbc.write_register(0x4000, 0x42);
+    assert_eq!(mbc.read_rom(0x4000), 0x42);
+
+    // Escrita sem a sequência não altera a flash
+    mbc.write_register(0x4000, 0x00);
+    assert_eq!(mbc.read_rom(0x4000), 0x42);
+
+    // Modo ID e saída com F0
+    unlock(&mut mbc);
+    mbc.write_register(0x5555, 0x90);
+    assert_eq!(mbc.read_rom(0x4000), 0xC2);
+    assert_eq!(mbc.read_rom(0x4001), 0x81);
+    mbc.write_register(0x4000, 0xF0);
+    assert_eq!(mbc.read_rom(0x4000), 0x42);
+
+    // Apagar o setor volta a 0xFF
+    unlock(&mut mbc);
+    mbc.write_register(0x5555, 0x80);
+    unlock(&mut mbc);
+    mbc.write_register(0x4000, 0x30);
+    assert_eq!(mbc.read_rom(0x4000), 0xFF);
+}
+
+#[test]
+fn test_mbc6_flash_write_protect() {
+    let mut mbc = mbc6();
+    map_flash(&mut mbc);
+    mbc.write_register(0x1000, 0x00);
+    unlock(&mut mbc);
+    mbc.write_register(0x5555, 0xA0);
+    mbc.write_register(0x4000, 0x42);
+    assert_eq!(mbc.read_rom(0x4000), 0xFF);
+}
+
+#[test]
+fn test_mbc6_persists_ram_and_flash() {
+    let mut mbc = mbc6();
+    mbc.write_register(0x0000, 0x0A);
+    mbc.write_ram(0xA000, 0x11);
+    map_flash(&mut mbc);
+    unlock(&mut mbc);
+    mbc.write_register(0x5555, 0xA0);
+    mbc.write_register(0x4100, 0x22);
+    let sav = mbc.save_ram().unwrap();
+
+    let mut other = mbc6();
+    other.load_ram(&sav);
+    other.write_register(0x0000, 0x0A);
+    assert_eq!(other.read_ram(0xA000), 0x11);
+    map_flash(&mut other);
+    assert_eq!(other.read_rom(0x4100), 0x22);
+}
+
+#[test]
+fn test_mbc6_save_state() {
+    use gb_emu::GB::savestate::{StateReader, StateWriter};
+
+    let mut mbc = mbc6();
+    map_flash(&mut mbc);
+    unlock(&mut mbc);
+    mbc.write_register(0x5555, 0xA0);
+    let mut w = StateWriter::new();
+    mbc.save_state(&mut w);
+    let data = w.into_inner();
+
+    // O comando de programação pendente sobrevive ao save state
+    let mut other = mbc6();
+    other.load_state(&mut StateReader::new(&data)).unwrap();
+    other.write_register(0x4000, 0x5A);
+    assert_eq!(other.read_rom(0x4000), 0x5A);
+}
@@ -0,0 +1,185 @@
+use gb_emu::GB::mbc::{MBC, create_mbc, mbc7::MBC7};
+
+fn mbc7() -> MBC7 {
+    let mut rom = vec![0u8; 1024 * 1024];
+    rom[0x0147] = 0x22; // MBC7+SENSOR+RUMBLE+RAM+BATTERY
+    let mut mbc = MBC7::new(rom);
+    mbc.write_register(0x0000, 0x0A);
+    mbc.write_register(0x4000, 0x40);
+    mbc
+}
+
+/// Pinos do registrador Ax8x: CS = bit 7, CLK = bit 6, DI = bit 1
+fn eeprom_bit(mbc: &mut MBC7, bit: bool) {
+    let di = if bit { 0x02 } else { 0x00 };
+    mbc.write_ram(0xA080, 0x80 | di);
+    mbc.write_ram(0xA080, 0xC0 | di);
+}
+
+/// Sobe CS e envia bit de início + opcode (2 bits) + endereço (8 bits)
+fn eeprom_command(mbc: &mut MBC7, opcode: u8, address: u8) {
+    mbc.write_ram(0xA080, 0x00);
+    mbc.write_ram(0xA080, 0x80);
+    eeprom_bit(mbc, true);
+    for i in (0..2).rev() {
+        eeprom_bit(mbc, (opcode >> i) & 1 != 0);
+    }
+    for i in (0..8).rev() {
+        eeprom_bit(mbc, (address >> i) & 1 != 0);
+    }
+}
+
+fn eeprom_write_word(mbc: &mut MBC7, address: u8, value: u16) {
+    eeprom_command(mbc, 0b01, address);
+    for i in (0..16).rev() {
+        eeprom_bit(mbc, (value >> i) & 1 != 0);
+    }
+    mbc.write_ram(0xA080, 0x00);
+}
+
+fn eeprom_read_word(mbc: &mut MBC7, address: u8) -> u16 {
+    eeprom_command(mbc, 0b10, address);
+    // Bit 0 de preâmbulo antes dos dados
+    assert_eq!(mbc.read_ram(0xA080) & 0x01, 0);
+    let mut value = 0u16;
+    for _ in 0..16 {
+        eeprom_bit(mbc, false);
+        value = (value << 1) | (mbc.read_ram(0xA080) & 0x01) as u16;
+    }
+    mbc.write_ram(0xA080, 0x00);
+    value
+}
+
+fn read_accel(mbc: &MBC7) -> (u16, u16) {
+    let x = u16::from_le_bytes([mbc.read_ram(0xA020), mbc.read_ram(0xA030)]);
+    let y = u16::from_le_bytes([mbc.read_ram(0xA040), mbc.read_ram(0xA050)]);
+    (x, y)
+}
+
+#[test]
+fn test_mbc7_selected_for_cart_type_0x22() {
+    let mut rom = vec![0u8; 64 * 1024];
+    rom[0x0147] = 0x22;
+    rom[3 * 0x4000] = 0x33;
+    let mut mbc = create_mbc(rom);
+    mbc.write_register(0x2000, 0x03);
+    assert_eq!(mbc.read_rom(0x4000), 0x33);
+    assert_eq!(mbc.save_ram().map(|r| r.len()), Some(256));
+}
+
+#[test]
+fn test_mbc7_registers_need_both_enables() {
+    let mut rom = vec![0u8; 64 * 1024];
+    rom[0x0147] = 0x22;
+    let mut mbc = MBC7::new(rom);
+    assert_eq!(mbc.read_ram(0xA060), 0xFF);
+    mbc.write_register(0x0000, 0x0A);
+    assert_eq!(mbc.read_ram(0xA060), 0xFF);
+    mbc.write_register(0x4000, 0x40);
+    assert_eq!(mbc.read_ram(0xA060), 0x00);
+    // B000-BFFF não tem registradores
+    assert_eq!(mbc.read_ram(0xB060), 0xFF);
+}
+
+#[test]
+fn test_mbc7_accelerometer_latch() {
+    let mut mbc = mbc7();
+    mbc.set_tilt(0.5, -1.0);
+
+    // Sem apagar antes, 0xAA não captura nada
+    mbc.write_ram(0xA010, 0xAA);
+    assert_eq!(read_accel(&mbc), (0x8000, 0x8000));
+
+    mbc.write_ram(0xA000, 0x55);
+    assert_eq!(read_accel(&mbc), (0x8000, 0x8000));
+    mbc.write_ram(0xA010, 0xAA);
+    assert_eq!(read_accel(&mbc), (0x81D0 - 0x38, 0x81D0 + 0x70));
+
+    // O valor fica travado até o próximo apagar + latch
+    mbc.set_tilt(0.0, 0.0);
+    mbc.write_ram(0xA010, 0xAA);
+    assert_eq!(read_accel(&mbc), (0x81D0 - 0x38, 0x81D0 + 0x70));
+    mbc.write_ram(0xA000, 0x55);
+    mbc.write_ram(0xA010, 0xAA);
+    assert_eq!(read_accel(&mbc), (0x81D0, 0x81D0));
+}
+
+#[test]
+fn test_mbc7_eeprom_write_needs_ewen() {
+    let mut mbc = mbc7();
+    eeprom_write_word(&mut mbc, 5, 0x1234);
+    assert_eq!(eeprom_read_word(&mut mbc, 5), 0xFFFF);
+
+    // EWEN: 00 11xxxxxx
+    eeprom_command(&mut mbc, 0b00, 0xC0);
+    mbc.write_ram(0xA080, 0x00);
+    eeprom_write_word(&mut mbc, 5, 0x1234);
+    assert_eq!(mbc.read_ram(0xA080) & 0x01, 1);
+    assert_eq!(eeprom_read_word(&mut mbc, 5), 0x1234);
+    assert_eq!(eeprom_read_word(&mut mbc, 6), 0xFFFF);
+
+    // ERASE volta a palavra para 0xFFFF
+    eeprom_command(&mut mbc, 0b11, 5);
+    mbc.write_ram(0xA080, 0x00);
+    assert_eq!(eeprom_read_word(&mut mbc, 5), 0xFFFF);
+}
+
+#[test]
+fn test_mbc7_eeprom_wral_and_eral() {
+    let mut mbc = mbc7();
+    eeprom_command(&mut mbc, 0b00, 0xC0);
+    mbc.write_ram(0xA080, 0x00);
+
+    // WRAL: 00 01xxxxxx + dados
+    eeprom_command(&mut mbc, 0b00, 0x40);
+    for i in (0..16).rev() {
+        eeprom_bit(&mut mbc, (0xBEEFu16 >> i) & 1 != 0);
+    }
+    mbc.write_ram(0xA080, 0x00);
+    assert_eq!(eeprom_read_word(&mut mbc, 0), 0xBEEF);
+    assert_eq!(eeprom_read_word(&mut mbc, 127), 0xBEEF);
+
+    // ERAL: 00 10xxxxxx
+    eeprom_command(&mut mbc, 0b00, 0x80);
+    mbc.write_ram(0xA080, 0x00);
+    assert!(mbc.save_ram().unwrap().iter().all(|&b| b == 0xFF));
+}
+
+#[test]
+fn test_mbc7_eeprom_persists_through_save_ram() {
+    let mut mbc = mbc7();
+    eeprom_command(&mut mbc, 0b00, 0xC0);
+    mbc.write_ram(0xA080, 0x00);
+    eeprom_write_word(&mut mbc, 0x10, 0xCAFE);
+    let sav = mbc.save_ram().unwrap();
+    assert_eq!(sav.len(), 256);
+    assert_eq!(&sav[0x20..0x22], &0xCAFEu16.to_le_bytes());
+
+    let mut other = mbc7();
+    other.load_ram(&sav);
+    assert_eq!(eeprom_read_word(&mut other, 0x10), 0xCAFE);
+}
+
+#[test]
+fn test_mbc7_save_state() {
+    use gb_emu::GB::savestate::{StateReader, StateWriter};
+
+    let mut mbc = mbc7();
+    mbc.set_tilt(-1.0, 0.0);
+    mbc.write_ram(0xA000, 0x55);
+    mbc.write_ram(0xA010, 0xAA);
+    eeprom_command(&mut mbc, 0b00, 0xC0);
+    mbc.write_ram(0xA080, 0x00);
+    eeprom_write_word(&mut mbc, 1, 0x0042);
+
+    let mut w = StateWriter::new();
+    mbc.save_state(&mut w);
+    let data = w.into_inner();
+
+    let mut rom = vec![0u8; 1024 * 1024];
+    rom[0x0147] = 0x22;
+    let mut other = MBC7::new(rom);
+    other.load_state(&mut StateReader::new(&data)).unwrap();
+    assert_eq!(read_accel(&other), (0x81D0 + 0x70, 0x81D0));
+    assert_eq!(eeprom_read_word(&mut other, 1), 0x0042);
+}
@@ -0,0 +1,135 @@
+use gb_emu::GB::cartridge::NINTENDO_LOGO;
+use gb_emu::GB::mbc::{MBC, create_mbc, mmm01::MMM01};
+
+/// Logo e checksum de header válidos no cabeçalho em `offset`
+fn write_header(rom: &mut [u8], offset: usize, cart_type: u8, ram_code: u8) {
+    let header = &mut rom[offset..];
+    header[0x0104..0x0134].copy_from_slice(&NINTENDO_LOGO);
+    header[0x0147] = cart_type;
+    header[0x0149] = ram_code;
+    header[0x014D] = header[0x0134..0x014D]
+        .iter()
+        .fold(0u8, |x, &b| x.wrapping_sub(b).wrapping_sub(1));
+}
+
+/// Offset do cabeçalho do menu em `multicart_rom`
+const MENU: usize = 6 * 0x4000;
+
+/// ROM de 8 bancos: cada banco começa com o próprio número e o menu
+/// (com o cabeçalho MMM01) ocupa os bancos 6-7
+fn multicart_rom() -> Vec<u8> {
+    let mut rom = vec![0u8; 8 * 0x4000];
+    for bank in 0..8 {
+        rom[bank * 0x4000] = bank as u8;
+    }
+    // Banco 0 é o começo do primeiro jogo, com o header dele (MBC1)
+    write_header(&mut rom, 0, 0x01, 0x00);
+    write_header(&mut rom, MENU, 0x0D, 0x03); // MMM01+RAM+BATTERY, 32KB
+    rom
+}
+
+/// Sequência do menu: jogo de 32KB nos bancos 2-3 (só o bit 0 livre) e trava
+fn select_game(mbc: &mut dyn MBC) {
+    mbc.write_register(0x2000, 0x02);
+    mbc.write_register(0x6000, 0x0F << 2);
+    mbc.write_register(0x0000, 0x40);
+}
+
+#[test]
+fn test_mmm01_detects_menu_header_at_end() {
+    let rom = multicart_rom();
+    assert_eq!(MMM01::detect(&rom), Some(MENU));
+    let mbc = create_mbc(rom);
+    // Liga no menu: os dois últimos bancos
+    assert_eq!(mbc.read_rom(0x0000), 6);
+    assert_eq!(mbc.read_rom(0x4000), 7);
+    assert_eq!(mbc.save_ram().map(|r| r.len()), Some(32 * 1024));
+}
+
+#[test]
+fn test_mmm01_not_detected_in_normal_mbc5_rom() {
+    // 256KB MBC5 em que o byte na posição do "cabeçalho do menu" é 0x0B
+    let mut rom = vec![0u8; 16 * 0x4000];
+    for bank in 0..16 {
+        rom[bank * 0x4000] = bank as u8;
+    }
+    write_header(&mut rom, 0, 0x19, 0x00);
+    rom[14 * 0x4000 + 0x0147] = 0x0B;
+    assert_eq!(MMM01::detect(&rom), None);
+
+    let mut mbc = create_mbc(rom.clone());
+    assert_eq!(mbc.read_rom(0x0000), 0x00);
+    mbc.write_register(0x2000, 0x03);
+    assert_eq!(mbc.read_rom(0x4000), 0x03);
+
+    // Sem logo e checksum no fim também não vale, com qualquer tipo no banco 0
+    rom[0x0147] = 0xAA;
+    assert_eq!(MMM01::detect(&rom), None);
+}
+
+#[test]
+fn test_mmm01_menu_ignores_bank_writes_until_locked() {
+    let mut mbc = MMM01::new(multicart_rom(), 0, MENU);
+    mbc.write_register(0x2000, 0x02);
+    assert_eq!(mbc.read_rom(0x0000), 6);
+    assert_eq!(mbc.read_rom(0x4000), 7);
+}
+
+#[test]
+fn test_mmm01_locked_game_sees_mbc1() {
+    let mut mbc = MMM01::new(multicart_rom(), 0, MENU);
+    select_game(&mut mbc);
+    assert_eq!(mbc.read_rom(0x0000), 2);
+    assert_eq!(mbc.read_rom(0x4000), 3);
+
+    // O jogo só troca o bit livre; banco 0 do jogo vira 1, como no MBC1
+    mbc.write_register(0x2000, 0x00);
+    assert_eq!(mbc.read_rom(0x4000), 3);
+    mbc.write_register(0x2000, 0x05);
+    assert_eq!(mbc.read_rom(0x4000), 3);
+    assert_eq!(mbc.read_rom(0x0000), 2);
+
+    // Máscara e trava não mudam mais depois de travar
+    mbc.write_register(0x6000, 0x00);
+    mbc.write_register(0x0000, 0x00);
+    mbc.write_register(0x2000, 0x04);
+    assert_eq!(mbc.read_rom(0x0000), 2);
+    assert_eq!(mbc.read_rom(0x4000), 3);
+}
+
+#[test]
+fn test_mmm01_ram_and_save_state() {
+    use gb_emu::GB::savestate::{StateReader, StateWriter};
+
+    let mut mbc = MMM01::new(multicart_rom(), 32 * 1024, MENU);
+    select_game(&mut mbc);
+    mbc.write_register(0x0000, 0x0A);
+    mbc.write_register(0x6000, 0x01);
+    mbc.write_register(0x4000, 0x02);
+    mbc.write_ram(0xA000, 0x77);
+    mbc.write_register(0x4000, 0x00);
+    assert_eq!(mbc.read_ram(0xA000), 0x00);
+
+    let mut w = StateWriter::new();
+    mbc.save_state(&mut w);
+    let data = w.into_inner();
+    let mut other = MMM01::new(multicart_rom(), 32 * 1024, MENU);
+    other.load_state(&mut StateReader::new(&data)).unwrap();
+    assert_eq!(other.read_rom(0x0000), 2);
+    other.write_register(0x4000, 0x02);
+    assert_eq!(other.read_ram(0xA000), 0x77);
+}
+
+#[test]
+fn test_mmm01_header_in_bank0_boots_bank0() {
+    // Dump com o menu no começo: o cabeçalho MMM01 está no banco 0
+    let mut rom = vec![0u8; 8 * 0x4000];
+    for bank in 0..8 {
+        rom[bank * 0x4000] = bank as u8;
+    }
+    write_header(&mut rom, 0, 0x0B, 0x00);
+    assert_eq!(MMM01::detect(&rom), Some(0));
+    let mbc = create_mbc(rom);
+    assert_eq!(mbc.read_rom(0x0000), 0);
+    assert_eq!(mbc.read_rom(0x4000), 1);
+}
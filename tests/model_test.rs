@@ -0,0 +1,154 @@
+// Integration tests para a seleção de modelo de hardware
+// cargo test model_test
+
+#[cfg(test)]
+mod model_tests {
+    use gb_emu::GB::CPU::{BootModel, CPU};
+    use gb_emu::GB::test_runner::infer_model_from_path;
+
+    const ALL_MODELS: [BootModel; 8] = [
+        BootModel::Dmg0,
+        BootModel::DmgAbc,
+        BootModel::Mgb,
+        BootModel::Sgb,
+        BootModel::Sgb2,
+        BootModel::Cgb0,
+        BootModel::Cgb,
+        BootModel::Agb,
+    ];
+
+    fn rom_with_flag(cgb_flag: u8) -> Vec<u8> {
+        let mut rom = vec![0x00; 32 * 1024];
+        rom[0x0143] = cgb_flag;
+        // Primeiro byte do logo: 0xCE
+        rom[0x0104] = 0xCE;
+        rom
+    }
+
+    #[test]
+    fn test_model_names_round_trip() {
+        for model in ALL_MODELS {
+            assert_eq!(BootModel::from_name(model.name()), Some(model));
+        }
+        assert_eq!(BootModel::from_name("CGB"), Some(BootModel::Cgb));
+        assert_eq!(BootModel::from_name("gba"), None);
+    }
+
+    #[test]
+    fn test_default_model_from_header() {
+        assert_eq!(BootModel::for_rom(&rom_with_flag(0x00)), BootModel::DmgAbc);
+        assert_eq!(BootModel::for_rom(&rom_with_flag(0x80)), BootModel::Cgb);
+        assert_eq!(BootModel::for_rom(&rom_with_flag(0xC0)), BootModel::Cgb);
+    }
+
+    #[test]
+    fn test_mooneye_filename_fallback() {
+        let dmg = rom_with_flag(0x00);
+        let compat = rom_with_flag(0x80);
+        assert_eq!(
+            infer_model_from_path("acceptance/boot_regs-dmg0.gb", &dmg),
+            BootModel::Dmg0
+        );
+        assert_eq!(
+            infer_model_from_path("acceptance/boot_div2-S.gb", &dmg),
+            BootModel::Sgb2
+        );
+        assert_eq!(
+            infer_model_from_path("misc/boot_regs-A.gb", &dmg),
+            BootModel::Agb
+        );
+        assert_eq!(
+            infer_model_from_path("misc/boot_hwio-C.gb", &dmg),
+            BootModel::Cgb
+        );
+        // Sem sufixo: CGB-compatible fica no DMG, CGB-only vai para CGB
+        assert_eq!(
+            infer_model_from_path("cpu_instrs.gb", &compat),
+            BootModel::DmgAbc
+        );
+        assert_eq!(
+            infer_model_from_path("game.gbc", &rom_with_flag(0xC0)),
+            BootModel::Cgb
+        );
+    }
+
+    #[test]
+    fn test_dmg_model_forces_dmg_mode() {
+        let cpu = CPU::with_model(rom_with_flag(0x80), BootModel::Mgb);
+        assert!(!cpu.bus.cgb_mode);
+        assert_eq!(cpu.registers.get_af(), 0xFFB0);
+        assert_eq!(cpu.bus.read(0xFF4F), 0xFF);
+    }
+
+    #[test]
+    fn test_cgb_model_with_dmg_cart_enables_compat_registers() {
+        let cpu = CPU::with_model(rom_with_flag(0x00), BootModel::Cgb);
+        assert!(!cpu.bus.cgb_mode);
+        assert_eq!(cpu.registers.get_af(), 0x1180);
+        assert_eq!(cpu.bus.read(0xFF4F), 0xFE);
+        assert_eq!(cpu.bus.read(0xFF68), 0xC8);
+    }
+
+    #[test]
+    fn test_post_boot_vram_has_logo_on_dmg_only() {
+        // 0xCE: nibble 0xC -> 0b11110000, nibble 0xE -> 0b11111100
+        let cpu = CPU::with_model(rom_with_flag(0x00), BootModel::DmgAbc);
+        assert_eq!(cpu.bus.ppu.vram[0x0010], 0xF0);
+        assert_eq!(cpu.bus.ppu.vram[0x0012], 0xF0);
+        assert_eq!(cpu.bus.ppu.vram[0x0014], 0xFC);
+        assert_eq!(cpu.bus.ppu.vram[0x0011], 0x00);
+        assert_eq!(cpu.bus.ppu.vram[0x0190], 0x3C);
+        assert_eq!(cpu.bus.ppu.vram[0x1904], 0x01);
+        assert_eq!(cpu.bus.ppu.vram[0x1910], 0x19);
+        assert_eq!(cpu.bus.ppu.vram[0x1924], 0x0D);
+
+        for model in [BootModel::Sgb, BootModel::Cgb] {
+            let cpu = CPU::with_model(rom_with_flag(0xC0), model);
+            assert!(cpu.bus.ppu.vram.iter().all(|&b| b == 0), "{:?}", model);
+            assert!(cpu.bus.ppu.vram1.iter().all(|&b| b == 0), "{:?}", model);
+        }
+    }
+
+    #[test]
+    fn test_power_on_ram_is_reproducible_per_model() {
+        let wram = |cpu: &CPU| {
+            (0xC000..0xE000)
+                .map(|a| cpu.bus.read(a))
+                .collect::<Vec<u8>>()
+        };
+        let hram = |cpu: &CPU| {
+            (0xFF80..0xFFFF)
+                .map(|a| cpu.bus.read(a))
+                .collect::<Vec<u8>>()
+        };
+
+        let a = CPU::with_model(rom_with_flag(0x00), BootModel::DmgAbc);
+        let b = CPU::with_model(rom_with_flag(0x00), BootModel::DmgAbc);
+        assert_eq!(wram(&a), wram(&b));
+        assert_eq!(hram(&a), hram(&b));
+        assert_eq!(a.bus.ppu.oam, b.bus.ppu.oam);
+        // Lixo, não zeros
+        assert!(wram(&a).iter().any(|&v| v != 0));
+
+        let mgb = CPU::with_model(rom_with_flag(0x00), BootModel::Mgb);
+        assert_ne!(wram(&a), wram(&mgb));
+
+        // Boot ROM real: o mesmo conteúdo do modelo
+        let mut booted = CPU::new(rom_with_flag(0x00));
+        booted
+            .load_boot_rom_as(vec![0; 0x100], BootModel::DmgAbc)
+            .unwrap();
+        assert_eq!(wram(&booted), wram(&a));
+    }
+
+    #[test]
+    fn test_boot_rom_size_must_match_model() {
+        let mut cpu = CPU::new(rom_with_flag(0x00));
+        assert!(
+            cpu.load_boot_rom_as(vec![0; 0x100], BootModel::Cgb)
+                .is_err()
+        );
+        assert!(cpu.load_boot_rom_as(vec![0; 0x900], BootModel::Agb).is_ok());
+        assert!(cpu.bus.cgb_mode);
+    }
+}
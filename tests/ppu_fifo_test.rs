@@ -0,0 +1,221 @@
+// Integration tests para o renderizador com fetcher + FIFO de pixels
+// cargo test ppu_fifo_test
+
+#[cfg(test)]
+mod ppu_fifo_tests {
+    use gb_emu::GB::CPU::{BootModel, CPU};
+    use gb_emu::GB::PPU::PPU;
+    use gb_emu::GB::cartridge::NINTENDO_LOGO;
+    use gb_emu::GB::test_runner::{self, TestResult};
+
+    /// PPU no início da linha 0 com o FIFO ligado, tile 0 liso (cor 0) e
+    /// tile 1 com a linha 0 em cor 1 e a linha 1 em cor 2
+    fn fifo_ppu() -> PPU {
+        let mut ppu = PPU::new();
+        ppu.fifo_renderer = true;
+        ppu.vram.fill(0);
+        ppu.oam.fill(0);
+        for row in 0..8 {
+            let (lo, hi) = if row % 2 == 0 {
+                (0xFF, 0x00)
+            } else {
+                (0x00, 0xFF)
+            };
+            ppu.vram[16 + row * 2] = lo;
+            ppu.vram[16 + row * 2 + 1] = hi;
+        }
+        ppu.bgp = 0xE4;
+        ppu.lcdc = 0x91;
+        ppu
+    }
+
+    fn step_dots(ppu: &mut PPU, dots: u32) {
+        let mut iflags = 0u8;
+        for _ in 0..dots {
+            ppu.step(1, &mut iflags);
+        }
+    }
+
+    /// mode_clock em que o STAT passa a mostrar o modo 0 na linha atual
+    fn mode0_clock(ppu: &mut PPU) -> u32 {
+        let mut iflags = 0u8;
+        while ppu.mode_clock < 100 || ppu.mode != 0 {
+            ppu.step(1, &mut iflags);
+        }
+        ppu.mode_clock
+    }
+
+    fn line(ppu: &PPU, ly: usize) -> &[u8] {
+        &ppu.framebuffer[ly * 160..ly * 160 + 160]
+    }
+
+    #[test]
+    fn test_mode3_length_from_fetcher() {
+        let mut ppu = fifo_ppu();
+        assert_eq!(mode0_clock(&mut ppu), 256);
+
+        // SCX & 7 pixels descartados no início da linha
+        let mut ppu = fifo_ppu();
+        ppu.scx = 3;
+        assert_eq!(mode0_clock(&mut ppu), 259);
+    }
+
+    #[test]
+    fn test_sprite_penalty_depends_on_tile_alignment() {
+        // Sprite alinhado ao tile: 6 dots de busca + 5 esperando o fetcher
+        let mut ppu = fifo_ppu();
+        ppu.lcdc |= 0x02;
+        ppu.obp0 = 0xE4;
+        ppu.oam[0..4].copy_from_slice(&[16, 8, 1, 0]);
+        assert_eq!(mode0_clock(&mut ppu), 256 + 11);
+        assert!(line(&ppu, 0)[..8].iter().all(|&s| s == 1));
+        assert_eq!(line(&ppu, 0)[8], 0);
+
+        // 5 pixels dentro do tile: o fetcher já está pronto
+        let mut ppu = fifo_ppu();
+        ppu.lcdc |= 0x02;
+        ppu.oam[0..4].copy_from_slice(&[16, 13, 0, 0]);
+        assert_eq!(mode0_clock(&mut ppu), 256 + 6);
+
+        // Sprites desligados não pausam o fetcher
+        let mut ppu = fifo_ppu();
+        ppu.oam[0..4].copy_from_slice(&[16, 8, 0, 0]);
+        assert_eq!(mode0_clock(&mut ppu), 256);
+    }
+
+    #[test]
+    fn test_mid_scanline_bgp_write() {
+        let mut ppu = fifo_ppu();
+        ppu.vram[0x1800..0x1C00].fill(1);
+
+        // Pixel n sai no dot 80 + 12 + n: troca a BGP depois do pixel 79
+        step_dots(&mut ppu, 80 + 12 + 80);
+        ppu.bgp = 0xE0; // cor 1 -> branco
+        step_dots(&mut ppu, 456 - (80 + 12 + 80));
+
+        assert!(line(&ppu, 0)[..80].iter().all(|&s| s == 1));
+        assert!(line(&ppu, 0)[80..].iter().all(|&s| s == 0));
+    }
+
+    #[test]
+    fn test_mid_scanline_scx_write() {
+        let mut ppu = fifo_ppu();
+        // Colunas 0-15 com tile 0, colunas 16-31 com tile 1
+        for row in 0..32 {
+            ppu.vram[0x1800 + row * 32 + 16..0x1800 + row * 32 + 32].fill(1);
+        }
+
+        // SCX só vale para os tiles buscados depois da escrita
+        step_dots(&mut ppu, 80 + 12 + 64);
+        ppu.scx = 64;
+        step_dots(&mut ppu, 456 - (80 + 12 + 64));
+
+        let pixels = line(&ppu, 0);
+        assert!(pixels[..64].iter().all(|&s| s == 0));
+        assert!(pixels[100..].iter().all(|&s| s == 1));
+    }
+
+    #[test]
+    fn test_window_start_and_line_counter() {
+        let mut ppu = fifo_ppu();
+        ppu.vram[0x1C00..0x2000].fill(1);
+        ppu.lcdc = 0x91 | 0x20 | 0x40;
+        ppu.wy = 0;
+        ppu.wx = 87;
+
+        // Linha 0: window a partir do pixel 80, com 6 dots de reinício do fetcher
+        assert_eq!(mode0_clock(&mut ppu), 256 + 6);
+        step_dots(&mut ppu, 456 - 256 - 6);
+        assert!(line(&ppu, 0)[..80].iter().all(|&s| s == 0));
+        assert!(line(&ppu, 0)[80..].iter().all(|&s| s == 1));
+
+        // Linha 1 sem window: o contador interno não avança
+        ppu.lcdc &= !0x20;
+        step_dots(&mut ppu, 456);
+        assert!(line(&ppu, 1).iter().all(|&s| s == 0));
+
+        // Linha 2 mostra a linha 1 da window (cor 2), não a linha 2
+        ppu.lcdc |= 0x20;
+        step_dots(&mut ppu, 456);
+        assert!(line(&ppu, 2)[80..].iter().all(|&s| s == 2));
+    }
+
+    #[test]
+    fn test_fifo_state_survives_save_state() {
+        use gb_emu::GB::savestate::{StateReader, StateWriter};
+
+        let mut ppu = fifo_ppu();
+        ppu.vram[0x1800..0x1C00].fill(1);
+        step_dots(&mut ppu, 150);
+        let mut w = StateWriter::new();
+        ppu.save_state(&mut w);
+        let data = w.into_inner();
+
+        let mut other = PPU::new();
+        other.load_state(&mut StateReader::new(&data)).unwrap();
+        assert!(other.fifo_renderer);
+        step_dots(&mut ppu, 300);
+        step_dots(&mut other, 300);
+        assert_eq!(line(&ppu, 0), line(&other, 0));
+        assert!(line(&other, 0).iter().all(|&s| s == 1));
+    }
+
+    /// ROM com o logo no header: espera uns 3 quadros com a tela pós-boot
+    /// (logo e ®) e para no LD B,B
+    fn logo_rom() -> Vec<u8> {
+        let mut rom = vec![0u8; 32 * 1024];
+        rom[0x0100..0x0104].copy_from_slice(&[0x00, 0xC3, 0x50, 0x01]);
+        rom[0x0104..0x0134].copy_from_slice(&NINTENDO_LOGO);
+        #[rustfmt::skip]
+        let program = [
+            0x01, 0x00, 0x20,       // LD BC,2000
+            // loop:
+            0x0B,                   // DEC BC
+            0x78,                   // LD A,B
+            0xB1,                   // OR C
+            0x20, 0xFB,             // JR NZ,loop
+            0x40,                   // LD B,B
+            0x18, 0xFE,             // JR -2
+        ];
+        rom[0x0150..0x0150 + program.len()].copy_from_slice(&program);
+        rom
+    }
+
+    fn to_pgm(framebuffer: &[u8]) -> Vec<u8> {
+        let mut pgm = b"P5\n160 144\n255\n".to_vec();
+        pgm.extend(
+            framebuffer
+                .iter()
+                .map(|&s| [0xFF, 0xAA, 0x55, 0x00][s as usize & 3]),
+        );
+        pgm
+    }
+
+    #[test]
+    fn test_screenshot_harness_matches_scanline_renderer() {
+        // Referência: a mesma tela pelo renderizador por linha
+        let mut reference = CPU::with_model(logo_rom(), BootModel::DmgAbc);
+        while reference.bus.read(reference.registers.get_pc()) != 0x40 {
+            reference.execute_next();
+        }
+        let mut pgm = to_pgm(&reference.bus.ppu.framebuffer);
+        assert!(reference.bus.ppu.framebuffer.contains(&3));
+
+        let mut cpu = CPU::with_model(logo_rom(), BootModel::DmgAbc);
+        cpu.bus.ppu.fifo_renderer = true;
+        assert!(matches!(
+            test_runner::run_screenshot(&mut cpu, &pgm),
+            TestResult::Passed
+        ));
+
+        // Um pixel diferente reprova
+        let last = pgm.len() - 1;
+        pgm[last] ^= 0xFF;
+        let mut cpu = CPU::with_model(logo_rom(), BootModel::DmgAbc);
+        cpu.bus.ppu.fifo_renderer = true;
+        assert!(matches!(
+            test_runner::run_screenshot(&mut cpu, &pgm),
+            TestResult::Failed(_)
+        ));
+    }
+}
@@ -0,0 +1,203 @@
+// Integration tests para a Game Boy Printer
+// cargo test printer_test
+
+#[cfg(test)]
+mod printer_tests {
+    use gb_emu::GB::CPU::{BootModel, CPU};
+    use gb_emu::GB::printer::{self, PrintedImage, Printer};
+    use gb_emu::GB::serial::LinkEndpoint;
+    use std::sync::{Arc, Mutex};
+
+    type Prints = Arc<Mutex<Vec<PrintedImage>>>;
+
+    fn printer() -> (Printer, Prints) {
+        let prints: Prints = Arc::new(Mutex::new(Vec::new()));
+        let sink = prints.clone();
+        let printer = Printer::new(Box::new(move |image: &PrintedImage| {
+            sink.lock().unwrap().push(image.clone())
+        }));
+        (printer, prints)
+    }
+
+    /// Troca um byte com a impressora, MSB primeiro
+    fn exchange(printer: &mut Printer, byte: u8, now: u64) -> u8 {
+        let mut got = 0u8;
+        for i in 0..8 {
+            let bit = (byte >> (7 - i)) & 1 != 0;
+            got = (got << 1) | printer.clock_out(bit, now) as u8;
+        }
+        got
+    }
+
+    /// Manda um pacote e devolve (vivo, status)
+    fn packet(
+        printer: &mut Printer,
+        command: u8,
+        compressed: bool,
+        data: &[u8],
+        now: u64,
+    ) -> (u8, u8) {
+        let mut bytes = vec![command, compressed as u8];
+        bytes.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(data);
+        let checksum = bytes
+            .iter()
+            .fold(0u16, |sum, &b| sum.wrapping_add(b as u16));
+
+        for b in [0x88, 0x33] {
+            assert_eq!(exchange(printer, b, now), 0x00);
+        }
+        for &b in bytes.iter().chain(&checksum.to_le_bytes()) {
+            assert_eq!(exchange(printer, b, now), 0x00);
+        }
+        let alive = exchange(printer, 0x00, now);
+        let status = exchange(printer, 0x00, now);
+        (alive, status)
+    }
+
+    /// Uma faixa de 160x16 com o tile `tile` repetido
+    fn band(tile: [u8; 16]) -> Vec<u8> {
+        tile.repeat(40)
+    }
+
+    #[test]
+    fn test_init_and_status_handshake() {
+        let (mut p, _) = printer();
+        assert_eq!(packet(&mut p, 0x01, false, &[], 0), (0x81, 0x00));
+        assert_eq!(packet(&mut p, 0x0F, false, &[], 0), (0x81, 0x00));
+    }
+
+    #[test]
+    fn test_data_and_print_render_with_palette_and_margins() {
+        let (mut p, prints) = printer();
+        packet(&mut p, 0x01, false, &[], 0);
+
+        // Cor 1 na metade de cima de cada tile, cor 2 na de baixo
+        let mut tile = [0u8; 16];
+        for y in 0..4 {
+            tile[y * 2] = 0xFF;
+        }
+        for y in 4..8 {
+            tile[y * 2 + 1] = 0xFF;
+        }
+        let (_, status) = packet(&mut p, 0x04, false, &band(tile), 0);
+        assert_eq!(status, 0x08);
+        assert_eq!(packet(&mut p, 0x04, false, &[], 0).1, 0x08);
+
+        // 1 folha, margens 1 antes e 2 depois, paleta 0xE4 (identidade)
+        let (_, status) = packet(&mut p, 0x02, false, &[1, 0x12, 0xE4, 0x40], 0);
+        assert_eq!(status & 0x02, 0x02);
+        assert_eq!(status & 0x08, 0x00);
+
+        let prints = prints.lock().unwrap();
+        assert_eq!(prints.len(), 1);
+        let image = &prints[0];
+        assert_eq!((image.width, image.height), (160, 16 + 16 + 32));
+        assert_eq!(image.pixels[0], 0);
+        assert_eq!(image.pixels[16 * 160], 1);
+        assert_eq!(image.pixels[(16 + 4) * 160 + 100], 2);
+        assert_eq!(image.pixels[63 * 160], 0);
+    }
+
+    #[test]
+    fn test_palette_remaps_colors() {
+        let (mut p, prints) = printer();
+        packet(&mut p, 0x04, false, &band([0xFF; 16]), 0);
+        // Cor 3 vira tom 1
+        packet(&mut p, 0x02, false, &[1, 0x01, 0x40, 0x40], 0);
+        let prints = prints.lock().unwrap();
+        assert!(prints[0].pixels[..160 * 16].iter().all(|&s| s == 1));
+    }
+
+    #[test]
+    fn test_prints_without_bottom_margin_join_one_page() {
+        let (mut p, prints) = printer();
+        for margins in [0x10, 0x00, 0x03] {
+            packet(&mut p, 0x04, false, &band([0xFF; 16]), 0);
+            packet(&mut p, 0x02, false, &[1, margins, 0xE4, 0x40], 0);
+        }
+        let prints = prints.lock().unwrap();
+        assert_eq!(prints.len(), 1);
+        assert_eq!(prints[0].height, 16 + 3 * 16 + 3 * 16);
+    }
+
+    #[test]
+    fn test_rle_decompression() {
+        // 3 literais, depois 0xAA repetido 5 vezes
+        let data = [0x02, 1, 2, 3, 0x83, 0xAA];
+        assert_eq!(
+            printer::decompress(&data),
+            [1, 2, 3, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA]
+        );
+
+        let (mut p, prints) = printer();
+        let mut compressed = Vec::new();
+        for _ in 0..4 {
+            compressed.extend_from_slice(&[0xFF, 0xFF]); // 129 x 0xFF
+        }
+        compressed.extend_from_slice(&[0xFA, 0xFF]); // +124 = 640
+        packet(&mut p, 0x04, true, &compressed, 0);
+        packet(&mut p, 0x02, false, &[1, 0x01, 0xE4, 0x40], 0);
+        let prints = prints.lock().unwrap();
+        assert!(prints[0].pixels[..160 * 16].iter().all(|&s| s == 3));
+    }
+
+    #[test]
+    fn test_checksum_error_and_busy_status() {
+        let (mut p, prints) = printer();
+        for b in [0x88, 0x33, 0x0F, 0x00, 0x00, 0x00, 0x00, 0x00] {
+            exchange(&mut p, b, 0);
+        }
+        assert_eq!(exchange(&mut p, 0x00, 0), 0x81);
+        assert_eq!(exchange(&mut p, 0x00, 0), 0x01);
+        assert_eq!(packet(&mut p, 0x0F, false, &[], 0).1, 0x00);
+
+        packet(&mut p, 0x04, false, &band([0; 16]), 0);
+        packet(&mut p, 0x02, false, &[1, 0x01, 0xE4, 0x40], 1000);
+        assert_eq!(packet(&mut p, 0x0F, false, &[], 2000).1, 0x02);
+        assert_eq!(packet(&mut p, 0x0F, false, &[], 4_194_304).1, 0x00);
+        assert_eq!(prints.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_png_and_pgm_output() {
+        let image = PrintedImage {
+            width: 2,
+            height: 2,
+            pixels: vec![0, 1, 2, 3],
+        };
+        let pgm = image.to_pgm();
+        assert!(pgm.starts_with(b"P5\n2 2\n255\n"));
+        assert_eq!(&pgm[pgm.len() - 4..], &[255, 170, 85, 0]);
+
+        let png = image.to_png();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 2, 0, 0, 0, 2]);
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]));
+
+        let dir = std::env::temp_dir().join(format!("gb_emu_printer_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut save = printer::save_to_directory(dir.to_str().unwrap());
+        save(&image);
+        let saved = std::fs::read(dir.join("print_001.png")).unwrap();
+        assert_eq!(saved, png);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_game_boy_talks_to_printer_over_serial() {
+        let mut cpu = CPU::with_model(vec![0u8; 32 * 1024], BootModel::DmgAbc);
+        let (p, _) = printer();
+        cpu.bus.set_link_cable(Some(Box::new(p)));
+
+        let mut responses = Vec::new();
+        for b in [0x88, 0x33, 0x0F, 0x00, 0x00, 0x00, 0x0F, 0x00, 0x00, 0x00] {
+            cpu.bus.write(0xFF01, b);
+            cpu.bus.write(0xFF02, 0x81);
+            cpu.bus.tick(4096);
+            responses.push(cpu.bus.read(0xFF01));
+        }
+        assert_eq!(responses[8..], [0x81, 0x00]);
+    }
+}
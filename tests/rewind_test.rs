@@ -0,0 +1,97 @@
+// Integration tests para o buffer de rewind
+// cargo test rewind_test
+
+use gb_emu::GB::CPU::CPU;
+use gb_emu::GB::rewind::{RewindBuffer, RewindConfig};
+
+fn cpu_with_loop() -> CPU {
+    let mut rom = vec![0x00; 32 * 1024];
+    rom[0x0147] = 0x00;
+    // INC (HL); INC L; JR -4 com HL = 0xC000
+    rom[0x0100..0x0108].copy_from_slice(&[0x21, 0x00, 0xC0, 0x34, 0x2C, 0x18, 0xFC, 0x00]);
+    let mut cpu = CPU::new(rom);
+    cpu.init_post_boot();
+    cpu.bus.ppu.headless = true;
+    cpu
+}
+
+fn run_cycles(cpu: &mut CPU, cycles: u64) {
+    let target = cpu.cycles + cycles;
+    while cpu.cycles < target {
+        cpu.execute_next();
+    }
+}
+
+#[test]
+fn test_rewind_restores_snapshots_in_reverse_order() {
+    let mut cpu = cpu_with_loop();
+    let mut rewind = RewindBuffer::new(RewindConfig::default());
+    let mut history = Vec::new();
+
+    for _ in 0..20 {
+        run_cycles(&mut cpu, 70_224);
+        let state = cpu.save_state();
+        history.push(state.clone());
+        rewind.push(state);
+    }
+    assert_eq!(rewind.len(), 20);
+
+    for expected in history.iter().rev() {
+        let snapshot = rewind.pop().expect("snapshot deveria existir");
+        assert_eq!(&snapshot, expected);
+        cpu.load_state(&snapshot).unwrap();
+    }
+    assert!(rewind.is_empty());
+    assert!(rewind.pop().is_none());
+}
+
+#[test]
+fn test_rewind_deltas_are_smaller_than_full_states() {
+    let mut cpu = cpu_with_loop();
+    let mut rewind = RewindBuffer::new(RewindConfig::default());
+
+    run_cycles(&mut cpu, 70_224);
+    let full_size = cpu.save_state().len();
+    for _ in 0..10 {
+        rewind.push(cpu.save_state());
+        run_cycles(&mut cpu, 70_224);
+    }
+
+    // 1 snapshot inteiro + 9 deltas deve ocupar bem menos que 10 snapshots
+    assert!(rewind.memory_usage() < full_size * 4);
+}
+
+#[test]
+fn test_rewind_respects_memory_limit() {
+    let config = RewindConfig {
+        interval_frames: 1,
+        max_bytes: 4096,
+    };
+    let mut rewind = RewindBuffer::new(config);
+
+    for i in 0..200u32 {
+        let mut state = vec![0u8; 1024];
+        for (j, byte) in state.iter_mut().enumerate() {
+            *byte = (i as usize * 7 + j) as u8;
+        }
+        rewind.push(state);
+        assert!(rewind.memory_usage() <= 4096);
+    }
+
+    // Os mais antigos foram descartados, mas os recentes continuam corretos
+    let newest = rewind.pop().unwrap();
+    assert_eq!(newest[0], (199 * 7) as u8);
+    let previous = rewind.pop().unwrap();
+    assert_eq!(previous[0], (198 * 7) as u8);
+    assert_eq!(previous[1023], (198usize * 7 + 1023) as u8);
+}
+
+#[test]
+fn test_rewind_frame_tick_interval() {
+    let mut rewind = RewindBuffer::new(RewindConfig {
+        interval_frames: 3,
+        max_bytes: 1024,
+    });
+    let ticks: Vec<bool> = (0..6).map(|_| rewind.frame_tick()).collect();
+    assert_eq!(ticks, vec![false, false, true, false, false, true]);
+}
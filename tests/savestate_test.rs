@@ -0,0 +1,100 @@
+// Integration tests para save states
+// cargo test savestate_test
+
+use gb_emu::GB::CPU::CPU;
+
+/// ROM MBC1+RAM que liga o APU, o timer, um OAM DMA e fica em loop
+/// misturando DIV, WRAM e RAM externa com interrupções habilitadas.
+fn build_rom() -> Vec<u8> {
+    let mut rom = vec![0x00; 64 * 1024];
+    rom[0x0147] = 0x03; // MBC1+RAM+BATTERY
+    rom[0x0148] = 0x01; // 64 KB
+    rom[0x0149] = 0x02; // 8 KB RAM
+
+    rom[0x0040] = 0xD9; // VBlank: RETI
+    rom[0x0050] = 0xD9; // Timer: RETI
+    rom[0x0100..0x0104].copy_from_slice(&[0x00, 0xC3, 0x50, 0x01]);
+
+    let program: [u8; 44] = [
+        0x3E, 0x80, 0xE0, 0x26, // NR52 = 0x80
+        0x3E, 0xF3, 0xE0, 0x12, // NR12 = 0xF3
+        0x3E, 0x87, 0xE0, 0x14, // NR14 = trigger
+        0x3E, 0x05, 0xE0, 0x07, // TAC = 0x05
+        0x3E, 0x05, 0xE0, 0xFF, // IE = VBlank | Timer
+        0x3E, 0x0A, 0xEA, 0x00, 0x00, // habilita RAM externa
+        0x3E, 0xC0, 0xE0, 0x46, // OAM DMA de 0xC000
+        0xFB, // EI
+        0x21, 0x00, 0xC0, // LD HL,0xC000
+        // loop:
+        0x34, // INC (HL)
+        0xF0, 0x04, // LDH A,(DIV)
+        0x86, // ADD A,(HL)
+        0x47, // LD B,A
+        0xEA, 0x00, 0xA0, // LD (0xA000),A
+        0x2C, // INC L
+        0x18, 0xF5, // JR loop
+    ];
+    rom[0x0150..0x0150 + program.len()].copy_from_slice(&program);
+    rom
+}
+
+fn new_cpu() -> CPU {
+    let mut cpu = CPU::new(build_rom());
+    cpu.init_post_boot();
+    cpu.bus.ppu.headless = true;
+    cpu
+}
+
+fn run_trace(cpu: &mut CPU, instructions: usize) -> Vec<(u16, u16, u16, u16, u64, u8, u8)> {
+    (0..instructions)
+        .map(|_| {
+            cpu.execute_next();
+            (
+                cpu.registers.get_pc(),
+                cpu.registers.get_af(),
+                cpu.registers.get_bc(),
+                cpu.registers.get_hl(),
+                cpu.cycles,
+                cpu.bus.read(0xFF44),
+                cpu.bus.read(0xFF04),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_restored_machine_runs_cycle_identical() {
+    let mut original = new_cpu();
+    run_trace(&mut original, 50_000);
+
+    let state = original.save_state();
+    let expected = run_trace(&mut original, 100_000);
+
+    let mut restored = new_cpu();
+    restored
+        .load_state(&state)
+        .expect("save state deveria carregar");
+    let actual = run_trace(&mut restored, 100_000);
+
+    assert_eq!(expected, actual, "máquina restaurada divergiu da original");
+    assert_eq!(original.bus.read(0xA000), restored.bus.read(0xA000));
+    assert_eq!(original.bus.read(0xFF26), restored.bus.read(0xFF26));
+    assert_eq!(original.bus.ppu.oam, restored.bus.ppu.oam);
+    assert_eq!(original.save_state(), restored.save_state());
+}
+
+#[test]
+fn test_load_state_rejects_invalid_data() {
+    let mut cpu = new_cpu();
+    run_trace(&mut cpu, 1_000);
+    let before = cpu.save_state();
+
+    assert!(cpu.load_state(b"XXXX\x01\x00").is_err());
+
+    let mut truncated = before.clone();
+    truncated.truncate(before.len() / 2);
+    assert!(cpu.load_state(&truncated).is_err());
+
+    // Falha no meio da leitura não pode deixar a máquina corrompida
+    assert_eq!(cpu.save_state(), before);
+}
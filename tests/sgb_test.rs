@@ -0,0 +1,288 @@
+// Integration tests para o Super Game Boy
+// cargo test sgb_test
+
+#[cfg(test)]
+mod sgb_tests {
+    use gb_emu::GB::CPU::{BootModel, CPU};
+    use gb_emu::GB::sgb::{SGB_WIDTH, ScreenMask};
+
+    fn sgb_cpu() -> CPU {
+        let mut rom = vec![0x00; 32 * 1024];
+        rom[0x0146] = 0x03;
+        CPU::with_model(rom, BootModel::Sgb)
+    }
+
+    /// Envia um comando pulsando P14/P15 em FF00 (um pacote de 16 bytes por vez)
+    fn send(cpu: &mut CPU, data: &[u8]) {
+        for packet in data.chunks(16) {
+            cpu.bus.write(0xFF00, 0x30);
+            cpu.bus.write(0xFF00, 0x00);
+            cpu.bus.write(0xFF00, 0x30);
+            for i in 0..128 {
+                let byte = packet.get(i / 8).copied().unwrap_or(0);
+                let bit = (byte >> (i % 8)) & 1;
+                cpu.bus.write(0xFF00, if bit == 1 { 0x10 } else { 0x20 });
+                cpu.bus.write(0xFF00, 0x30);
+            }
+            // Bit de parada
+            cpu.bus.write(0xFF00, 0x20);
+            cpu.bus.write(0xFF00, 0x30);
+        }
+    }
+
+    /// Deixa 4KB visíveis para uma transferência pela VRAM: tiles 0-255
+    /// em 0x8000 e o mapa do BG com os tiles em ordem, 20 por linha
+    fn show_transfer_data(cpu: &mut CPU, data: &[u8]) {
+        cpu.bus.write(0xFF40, 0x00);
+        for (i, &byte) in data.iter().enumerate() {
+            cpu.bus.write(0x8000 + i as u16, byte);
+        }
+        for i in 0..256u16 {
+            cpu.bus.write(0x9800 + (i / 20) * 32 + i % 20, i as u8);
+        }
+        cpu.bus.write(0xFF40, 0x10);
+    }
+
+    fn sgb(cpu: &CPU) -> &gb_emu::GB::sgb::Sgb {
+        cpu.bus.sgb.as_deref().expect("SGB ligado")
+    }
+
+    #[test]
+    fn test_only_sgb_models_enable_sgb() {
+        assert!(sgb_cpu().bus.sgb.is_some());
+        let cpu = CPU::with_model(vec![0x00; 32 * 1024], BootModel::DmgAbc);
+        assert!(cpu.bus.sgb.is_none());
+    }
+
+    #[test]
+    fn test_pal01_sets_palettes_and_shared_color() {
+        let mut cpu = sgb_cpu();
+        let mut packet = [0u8; 16];
+        packet[0] = 0x01; // PAL01, 1 pacote
+        let colors: [u16; 7] = [0x7FFF, 0x001F, 0x03E0, 0x7C00, 0x1111, 0x2222, 0x3333];
+        for (i, c) in colors.iter().enumerate() {
+            packet[1 + i * 2..3 + i * 2].copy_from_slice(&c.to_le_bytes());
+        }
+        send(&mut cpu, &packet);
+
+        let sgb = sgb(&cpu);
+        assert_eq!(sgb.palettes[0], [0x7FFF, 0x001F, 0x03E0, 0x7C00]);
+        assert_eq!(sgb.palettes[1], [0x7FFF, 0x1111, 0x2222, 0x3333]);
+        assert_eq!(sgb.palettes[3][0], 0x7FFF);
+    }
+
+    #[test]
+    fn test_corrupt_stop_bit_discards_packet() {
+        let mut cpu = sgb_cpu();
+        let before = sgb(&cpu).palettes;
+        cpu.bus.write(0xFF00, 0x30);
+        cpu.bus.write(0xFF00, 0x00);
+        cpu.bus.write(0xFF00, 0x30);
+        for i in 0..128 {
+            let bit = if i < 8 { 0x20 } else { 0x10 };
+            cpu.bus.write(0xFF00, bit);
+            cpu.bus.write(0xFF00, 0x30);
+        }
+        cpu.bus.write(0xFF00, 0x10);
+        cpu.bus.write(0xFF00, 0x30);
+        assert_eq!(sgb(&cpu).palettes, before);
+    }
+
+    #[test]
+    fn test_attr_blk_inside_border_outside() {
+        let mut cpu = sgb_cpu();
+        let mut packet = [0u8; 16];
+        packet[0] = (0x04 << 3) | 1; // ATTR_BLK
+        packet[1] = 1;
+        packet[2] = 0x07; // dentro, borda e fora
+        packet[3] = 0x01 | (0x02 << 2) | (0x03 << 4);
+        packet[4..8].copy_from_slice(&[2, 2, 5, 5]);
+        send(&mut cpu, &packet);
+
+        let map = sgb(&cpu).attr_map;
+        assert_eq!(map[3 * 20 + 3], 1);
+        assert_eq!(map[2 * 20 + 4], 2);
+        assert_eq!(map[5 * 20 + 5], 2);
+        assert_eq!(map[0], 3);
+        assert_eq!(map[17 * 20 + 19], 3);
+    }
+
+    #[test]
+    fn test_attr_blk_outside_only_includes_border() {
+        let mut cpu = sgb_cpu();
+        let mut packet = [0u8; 16];
+        packet[0] = (0x04 << 3) | 1; // ATTR_BLK
+        packet[1] = 1;
+        packet[2] = 0x04; // só fora
+        packet[3] = 0x01 | (0x02 << 2) | (0x03 << 4);
+        packet[4..8].copy_from_slice(&[2, 2, 5, 5]);
+        send(&mut cpu, &packet);
+
+        let map = sgb(&cpu).attr_map;
+        assert_eq!(map[3 * 20 + 3], 0);
+        assert_eq!(map[2 * 20 + 4], 3);
+        assert_eq!(map[5 * 20 + 5], 3);
+        assert_eq!(map[0], 3);
+    }
+
+    #[test]
+    fn test_attr_blk_inside_and_outside_keeps_border() {
+        let mut cpu = sgb_cpu();
+        let mut packet = [0u8; 16];
+        packet[0] = (0x04 << 3) | 1; // ATTR_BLK
+        packet[1] = 1;
+        packet[2] = 0x05; // dentro e fora, sem borda
+        packet[3] = 0x01 | (0x02 << 2) | (0x03 << 4);
+        packet[4..8].copy_from_slice(&[2, 2, 5, 5]);
+        send(&mut cpu, &packet);
+
+        let map = sgb(&cpu).attr_map;
+        assert_eq!(map[3 * 20 + 3], 1);
+        assert_eq!(map[2 * 20 + 4], 0);
+        assert_eq!(map[5 * 20 + 5], 0);
+        assert_eq!(map[0], 3);
+    }
+
+    #[test]
+    fn test_attr_lin_div_chr() {
+        let mut cpu = sgb_cpu();
+
+        // ATTR_DIV: divisão horizontal na linha 9
+        let mut packet = [0u8; 16];
+        packet[0] = (0x06 << 3) | 1;
+        packet[1] = 0x40 | (0x02 << 4) | (0x01 << 2) | 0x03;
+        packet[2] = 9;
+        send(&mut cpu, &packet);
+        let map = sgb(&cpu).attr_map;
+        assert_eq!(map[8 * 20], 1);
+        assert_eq!(map[9 * 20], 2);
+        assert_eq!(map[10 * 20], 3);
+
+        // ATTR_LIN: coluna 4 com paleta 0
+        let mut packet = [0u8; 16];
+        packet[0] = (0x05 << 3) | 1;
+        packet[1] = 1;
+        packet[2] = 0x04;
+        send(&mut cpu, &packet);
+        assert_eq!(sgb(&cpu).attr_map[17 * 20 + 4], 0);
+
+        // ATTR_CHR: 5 células a partir de (18, 0), quebrando para a linha 1
+        let mut packet = [0u8; 16];
+        packet[0] = (0x07 << 3) | 1;
+        packet[1] = 18;
+        packet[2] = 0;
+        packet[3] = 5;
+        packet[6] = 0b01_10_11_01;
+        packet[7] = 0b10_00_00_00;
+        send(&mut cpu, &packet);
+        let map = sgb(&cpu).attr_map;
+        assert_eq!(&map[18..20], &[1, 2]);
+        assert_eq!(&map[20..23], &[3, 1, 2]);
+    }
+
+    #[test]
+    fn test_pal_trn_and_pal_set() {
+        let mut cpu = sgb_cpu();
+        let mut data = vec![0u8; 0x1000];
+        // Paleta de sistema 300: cores 300*4 + c
+        for c in 0..4 {
+            let color = (300 * 4 + c) as u16;
+            data[300 * 8 + c * 2..300 * 8 + c * 2 + 2].copy_from_slice(&color.to_le_bytes());
+        }
+        show_transfer_data(&mut cpu, &data);
+
+        let mut packet = [0u8; 16];
+        packet[0] = (0x0B << 3) | 1; // PAL_TRN
+        send(&mut cpu, &packet);
+
+        let mut packet = [0u8; 16];
+        packet[0] = (0x0A << 3) | 1; // PAL_SET
+        packet[3..5].copy_from_slice(&300u16.to_le_bytes());
+        packet[9] = 0x40;
+        send(&mut cpu, &packet);
+
+        let sgb = sgb(&cpu);
+        // A cor 0 vem da paleta 0 (sistema 0)
+        assert_eq!(sgb.palettes[1], [0, 1201, 1202, 1203]);
+        assert_eq!(sgb.mask, ScreenMask::Cancel);
+    }
+
+    #[test]
+    fn test_mlt_req_cycles_joypad_ids() {
+        let mut cpu = sgb_cpu();
+        cpu.bus.write(0xFF00, 0x30);
+        assert_eq!(cpu.bus.read(0xFF00) & 0x0F, 0x0F);
+
+        let mut packet = [0u8; 16];
+        packet[0] = (0x11 << 3) | 1;
+        packet[1] = 0x01; // 2 jogadores
+        send(&mut cpu, &packet);
+        assert_eq!(cpu.bus.read(0xFF00) & 0x0F, 0x0F);
+
+        // P15 subindo avança para o jogador 2
+        cpu.bus.write(0xFF00, 0x10);
+        cpu.bus.write(0xFF00, 0x30);
+        assert_eq!(cpu.bus.read(0xFF00) & 0x0F, 0x0E);
+
+        // O jogador 2 não tem botões
+        cpu.bus.joypad.press("A");
+        cpu.bus.write(0xFF00, 0x10);
+        assert_eq!(cpu.bus.read(0xFF00) & 0x0F, 0x0F);
+
+        // Volta para o jogador 1, que vê o A pressionado
+        cpu.bus.write(0xFF00, 0x30);
+        assert_eq!(cpu.bus.read(0xFF00) & 0x0F, 0x0F);
+        cpu.bus.write(0xFF00, 0x10);
+        assert_eq!(cpu.bus.read(0xFF00) & 0x0F, 0x0E);
+    }
+
+    #[test]
+    fn test_border_and_colorized_game_output() {
+        let mut cpu = sgb_cpu();
+
+        // Tile de borda 1: plano 0 cheio -> cor 1
+        let mut tiles = vec![0u8; 0x1000];
+        for row in 0..8 {
+            tiles[32 + row * 2] = 0xFF;
+        }
+        show_transfer_data(&mut cpu, &tiles);
+        let mut packet = [0u8; 16];
+        packet[0] = (0x13 << 3) | 1; // CHR_TRN
+        send(&mut cpu, &packet);
+
+        // Mapa: célula (0,0) usa o tile 1 com a paleta 4
+        let mut pct = vec![0u8; 0x1000];
+        pct[0..2].copy_from_slice(&(1u16 | (4 << 10)).to_le_bytes());
+        pct[0x802..0x804].copy_from_slice(&0x001Fu16.to_le_bytes());
+        show_transfer_data(&mut cpu, &pct);
+        let mut packet = [0u8; 16];
+        packet[0] = (0x14 << 3) | 1; // PCT_TRN
+        send(&mut cpu, &packet);
+
+        let mut game = [0u8; 160 * 144];
+        game[0] = 3;
+        let sgb = cpu.bus.sgb.as_mut().unwrap();
+        sgb.render(&game);
+        assert_eq!(sgb.framebuffer[0], 0x001F);
+        assert_eq!(sgb.framebuffer[8], sgb.palettes[0][0]);
+        assert_eq!(sgb.framebuffer[40 * SGB_WIDTH + 48], sgb.palettes[0][3]);
+        assert_eq!(sgb.framebuffer[40 * SGB_WIDTH + 49], sgb.palettes[0][0]);
+    }
+
+    #[test]
+    fn test_mask_en_and_save_state() {
+        let mut cpu = sgb_cpu();
+        let mut packet = [0u8; 16];
+        packet[0] = (0x17 << 3) | 1;
+        packet[1] = 2; // tela preta
+        send(&mut cpu, &packet);
+
+        let state = cpu.save_state();
+        let mut other = sgb_cpu();
+        other.load_state(&state).unwrap();
+        let sgb = other.bus.sgb.as_mut().unwrap();
+        assert_eq!(sgb.mask, ScreenMask::Black);
+        sgb.render(&[3u8; 160 * 144]);
+        assert_eq!(sgb.framebuffer[100 * SGB_WIDTH + 100], 0x0000);
+    }
+}
@@ -0,0 +1,183 @@
+use gb_emu::GB::mbc::{MBC, create_mbc, tama5::TAMA5};
+
+fn tama5() -> TAMA5 {
+    let mut rom = vec![0u8; 512 * 1024];
+    rom[0x0147] = 0xFD;
+    for bank in 0..32 {
+        rom[bank * 0x4000 + 1] = bank as u8;
+    }
+    TAMA5::new(rom)
+}
+
+fn write_reg(mbc: &mut TAMA5, reg: u8, value: u8) {
+    mbc.write_ram(0xA001, reg);
+    mbc.write_ram(0xA000, value);
+}
+
+fn read_reg(mbc: &mut TAMA5, reg: u8) -> u8 {
+    mbc.write_ram(0xA001, reg);
+    mbc.read_ram(0xA000)
+}
+
+fn eeprom_write(mbc: &mut TAMA5, address: u8, value: u8) {
+    write_reg(mbc, 0x4, value & 0x0F);
+    write_reg(mbc, 0x5, value >> 4);
+    write_reg(mbc, 0x6, address >> 4);
+    write_reg(mbc, 0x7, address & 0x0F);
+}
+
+fn eeprom_read(mbc: &mut TAMA5, address: u8) -> u8 {
+    write_reg(mbc, 0x6, (1 << 1) | (address >> 4));
+    write_reg(mbc, 0x7, address & 0x0F);
+    let lo = read_reg(mbc, 0xC);
+    let hi = read_reg(mbc, 0xD);
+    assert_eq!(lo & 0xF0, 0xF0);
+    ((hi & 0x0F) << 4) | (lo & 0x0F)
+}
+
+fn rtc_write(mbc: &mut TAMA5, reg: u8, value: u8) {
+    write_reg(mbc, 0x4, value);
+    write_reg(mbc, 0x6, 2 << 1);
+    write_reg(mbc, 0x7, reg);
+}
+
+fn rtc_read(mbc: &mut TAMA5, reg: u8) -> u8 {
+    write_reg(mbc, 0x6, 3 << 1);
+    write_reg(mbc, 0x7, reg);
+    read_reg(mbc, 0xC) & 0x0F
+}
+
+/// (minuto, hora, dia, mês) em decimal a partir dos nibbles BCD
+fn rtc_date(mbc: &mut TAMA5) -> (u8, u8, u8, u8) {
+    let pair = |mbc: &mut TAMA5, reg: u8| rtc_read(mbc, reg + 1) * 10 + rtc_read(mbc, reg);
+    (pair(mbc, 2), pair(mbc, 4), pair(mbc, 7), pair(mbc, 9))
+}
+
+#[test]
+fn test_tama5_selected_for_cart_type_0xfd() {
+    let mut rom = vec![0u8; 512 * 1024];
+    rom[0x0147] = 0xFD;
+    let mut mbc = create_mbc(rom);
+    // Registrador 0xA: chip pronto
+    mbc.write_ram(0xA001, 0x0A);
+    assert_eq!(mbc.read_ram(0xA000), 0xF1);
+    assert_eq!(mbc.save_ram().map(|r| r.len()), Some(32 + 7 + 8));
+}
+
+#[test]
+fn test_tama5_rom_bank_from_two_nibbles() {
+    let mut mbc = tama5();
+    write_reg(&mut mbc, 0x0, 0x3);
+    write_reg(&mut mbc, 0x1, 0x1);
+    assert_eq!(mbc.read_rom(0x4001), 0x13);
+}
+
+#[test]
+fn test_tama5_eeprom_protocol() {
+    let mut mbc = tama5();
+    eeprom_write(&mut mbc, 0x00, 0xA5);
+    eeprom_write(&mut mbc, 0x1F, 0x3C);
+    assert_eq!(eeprom_read(&mut mbc, 0x00), 0xA5);
+    assert_eq!(eeprom_read(&mut mbc, 0x1F), 0x3C);
+    assert_eq!(eeprom_read(&mut mbc, 0x10), 0x00);
+}
+
+#[test]
+fn test_tama5_rtc_nibbles() {
+    let mut mbc = tama5();
+    // 23:59 de 31/12
+    rtc_write(&mut mbc, 2, 9);
+    rtc_write(&mut mbc, 3, 5);
+    rtc_write(&mut mbc, 4, 3);
+    rtc_write(&mut mbc, 5, 2);
+    rtc_write(&mut mbc, 7, 1);
+    rtc_write(&mut mbc, 8, 3);
+    rtc_write(&mut mbc, 9, 2);
+    rtc_write(&mut mbc, 10, 1);
+    assert_eq!(rtc_date(&mut mbc), (59, 23, 31, 12));
+}
+
+#[test]
+fn test_tama5_clock_persists_in_sav_and_advances() {
+    let mut mbc = tama5();
+    eeprom_write(&mut mbc, 0x05, 0x77);
+    // 23:59 de 28/02 do ano 01 (não bissexto)
+    for (reg, value) in [
+        (2, 9),
+        (3, 5),
+        (4, 3),
+        (5, 2),
+        (7, 8),
+        (8, 2),
+        (9, 2),
+        (11, 1),
+    ] {
+        rtc_write(&mut mbc, reg, value);
+    }
+    let mut sav = mbc.save_ram().unwrap();
+
+    // Timestamp de 2 minutos atrás: vira 1º de março
+    let mut ts = [0u8; 8];
+    ts.copy_from_slice(&sav[39..47]);
+    let saved = i64::from_le_bytes(ts) - 120;
+    sav[39..47].copy_from_slice(&saved.to_le_bytes());
+
+    let mut other = tama5();
+    other.load_ram(&sav);
+    assert_eq!(eeprom_read(&mut other, 0x05), 0x77);
+    let (minute, hour, day, month) = rtc_date(&mut other);
+    assert_eq!((hour, day, month), (0, 1, 3));
+    assert!((1..=2).contains(&minute));
+}
+
+#[test]
+fn test_tama5_save_state() {
+    use gb_emu::GB::savestate::{StateReader, StateWriter};
+
+    let mut mbc = tama5();
+    eeprom_write(&mut mbc, 0x02, 0x99);
+    write_reg(&mut mbc, 0x0, 0x4);
+    let mut w = StateWriter::new();
+    mbc.save_state(&mut w);
+    let data = w.into_inner();
+
+    let mut other = tama5();
+    other.load_state(&mut StateReader::new(&data)).unwrap();
+    assert_eq!(other.read_rom(0x4001), 0x04);
+    assert_eq!(eeprom_read(&mut other, 0x02), 0x99);
+}
+
+#[test]
+fn test_tama5_corrupt_clock_is_clamped_on_load() {
+    use gb_emu::GB::savestate::{StateReader, StateWriter};
+
+    // .sav com os 7 bytes do relógio lixo e timestamp de um dia atrás
+    let mut sav = tama5().save_ram().unwrap();
+    sav[32..39].fill(0xFF);
+    let mut ts = [0u8; 8];
+    ts.copy_from_slice(&sav[39..47]);
+    let saved = i64::from_le_bytes(ts) - 86_400;
+    sav[39..47].copy_from_slice(&saved.to_le_bytes());
+
+    let mut mbc = tama5();
+    mbc.load_ram(&sav);
+    let (minute, hour, day, month) = rtc_date(&mut mbc);
+    assert!(minute < 60 && hour < 24);
+    assert!((1..=31).contains(&day) && (1..=12).contains(&month));
+
+    // Save state: eeprom (32) + registradores (8) + seleção (1) + relógio
+    let mut w = StateWriter::new();
+    tama5().save_state(&mut w);
+    let mut data = w.into_inner();
+    data[41..48].fill(0xFF);
+    let mut ts = [0u8; 8];
+    ts.copy_from_slice(&data[48..56]);
+    let saved = i64::from_le_bytes(ts) - 86_400;
+    data[48..56].copy_from_slice(&saved.to_le_bytes());
+
+    let mut other = tama5();
+    other.load_state(&mut StateReader::new(&data)).unwrap();
+    let (minute, hour, day, month) = rtc_date(&mut other);
+    assert!(minute < 60 && hour < 24);
+    assert!((1..=31).contains(&day) && (1..=12).contains(&month));
+}
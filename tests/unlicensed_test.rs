@@ -0,0 +1,199 @@
+use gb_emu::GB::mbc::bbd::{Bbd, BbdVariant};
+use gb_emu::GB::mbc::m161::M161;
+use gb_emu::GB::mbc::sachen::{Sachen, SachenModel, unscramble};
+use gb_emu::GB::mbc::wisdom_tree::WisdomTree;
+use gb_emu::GB::mbc::{MBC, Unlicensed, create_mbc};
+
+/// ROM em que o primeiro byte de cada bloco de `size` bytes é o número do bloco
+fn tagged_rom(len: usize, size: usize) -> Vec<u8> {
+    let mut rom = vec![0u8; len];
+    for block in 0..len / size {
+        rom[block * size] = block as u8;
+    }
+    rom
+}
+
+fn wisdom_tree_rom() -> Vec<u8> {
+    let mut rom = tagged_rom(256 * 1024, 0x8000);
+    rom[0x014D] = 0xE7;
+    rom[0x1000..0x100B].copy_from_slice(b"WISDOM TREE");
+    rom
+}
+
+#[test]
+fn test_wisdom_tree_detected_and_switches_32kb_by_address() {
+    assert_eq!(
+        Unlicensed::detect(&wisdom_tree_rom()),
+        Some(Unlicensed::WisdomTree)
+    );
+
+    let mut mbc = create_mbc(wisdom_tree_rom());
+    assert_eq!(mbc.read_rom(0x0000), 0);
+    // Valor ignorado: o banco vem do endereço
+    mbc.write_register(0x0005, 0x00);
+    assert_eq!(mbc.read_rom(0x0000), 5);
+    assert_eq!(mbc.read_rom(0x4000), 0);
+    mbc.write_register(0x3F03, 0xFF);
+    assert_eq!(mbc.read_rom(0x0000), 3);
+    // 4000-7FFF não é registrador
+    mbc.write_register(0x4001, 0x00);
+    assert_eq!(mbc.read_rom(0x0000), 3);
+}
+
+#[test]
+fn test_wisdom_tree_needs_blank_header() {
+    let mut rom = wisdom_tree_rom();
+    rom[0x0134] = b'X';
+    assert_eq!(Unlicensed::detect(&rom), None);
+    let mut small = wisdom_tree_rom();
+    small.truncate(0x8000);
+    assert_eq!(Unlicensed::detect(&small), None);
+}
+
+#[test]
+fn test_m161_locks_after_first_bank_write() {
+    let mut rom = tagged_rom(256 * 1024, 0x8000);
+    rom[0x0147] = 0x10;
+    rom[0x0134..0x013E].copy_from_slice(b"TETRIS SET");
+    assert_eq!(Unlicensed::detect(&rom), Some(Unlicensed::M161));
+
+    let mut mbc = M161::new(rom);
+    mbc.write_register(0x4000, 0x03);
+    assert_eq!(mbc.read_rom(0x0000), 3);
+    mbc.write_register(0x4000, 0x05);
+    assert_eq!(mbc.read_rom(0x0000), 3);
+}
+
+/// Início do logo da Nintendo (o que a detecção olha) seguido de marcadores
+fn logo_byte(i: u16) -> u8 {
+    match i {
+        0 => 0xCE,
+        1 => 0xED,
+        2 => 0x66,
+        _ => 0xA0 + i as u8,
+    }
+}
+
+/// Logo da Nintendo embaralhado em 0x0104 e o da Sachen na metade alta
+fn sachen_rom() -> Vec<u8> {
+    let mut rom = tagged_rom(128 * 1024, 0x4000);
+    for i in 0..0x30u16 {
+        rom[unscramble(0x0104 + i) as usize] = logo_byte(i);
+        rom[unscramble(0x0184 + i) as usize] = 0x50 + i as u8;
+    }
+    rom
+}
+
+#[test]
+fn test_sachen_unscramble_is_involution() {
+    assert_eq!(unscramble(0x0105), 0x0144);
+    assert_eq!(unscramble(0x0102), 0x0110);
+    for address in 0x0100..0x0200 {
+        assert_eq!(unscramble(unscramble(address)), address);
+    }
+}
+
+#[test]
+fn test_sachen_logo_lock_opens_after_boot_reads() {
+    let rom = sachen_rom();
+    assert_eq!(Unlicensed::detect(&rom), Some(Unlicensed::SachenMmc1));
+    let mbc = Sachen::new(rom, SachenModel::Mmc1);
+
+    // Travado: a boot ROM vê a metade alta (logo da Sachen)
+    for i in 0..0x30u16 {
+        assert_eq!(mbc.read_rom(0x0104 + i), 0x50 + i as u8);
+    }
+    // Depois da trava: logo da Nintendo, ainda desembaralhado
+    assert_eq!(mbc.read_rom(0x0105), 0xED);
+    assert_eq!(mbc.read_rom(0x0110), logo_byte(0x0C));
+}
+
+#[test]
+fn test_sachen_mmc2_needs_two_passes() {
+    let mbc = Sachen::new(sachen_rom(), SachenModel::Mmc2);
+    for _ in 0..0x60 {
+        mbc.read_rom(0x0104);
+    }
+    assert_eq!(mbc.read_rom(0x0104), 0x50);
+    mbc.read_rom(0x0104);
+    assert_eq!(mbc.read_rom(0x0104), 0xCE);
+}
+
+#[test]
+fn test_sachen_skip_boot_and_banking() {
+    let mut mbc = Sachen::new(sachen_rom(), SachenModel::Mmc1);
+    mbc.skip_boot();
+    assert_eq!(mbc.read_rom(0x0106), 0x66);
+
+    mbc.write_register(0x2000, 0x05);
+    assert_eq!(mbc.read_rom(0x4000), 5);
+    // Base e máscara só com os bits 4-5 do banco em 1
+    mbc.write_register(0x0000, 0x04);
+    assert_eq!(mbc.read_rom(0x0000), 0);
+
+    mbc.write_register(0x2000, 0x31);
+    mbc.write_register(0x0000, 0x04);
+    mbc.write_register(0x4000, 0x04);
+    // Banco 0 = base & máscara; 4000 = banco com o bit 2 vindo da base
+    assert_eq!(mbc.read_rom(0x0000), 4);
+    mbc.write_register(0x2000, 0x03);
+    assert_eq!(mbc.read_rom(0x4000), 7);
+}
+
+#[test]
+fn test_bbd_reorders_data_and_bank_bits() {
+    let mut rom = vec![0u8; 512 * 1024];
+    for bank in 0..32 {
+        rom[bank * 0x4000 + 1] = 0x24;
+        rom[bank * 0x4000] = bank as u8;
+    }
+    let mut mbc = Bbd::new(rom, 0, BbdVariant::Bbd);
+
+    // Modo 3 do banco: bit 0 vem do bit 3, bit 3 do bit 0
+    mbc.write_register(0x2080, 0x03);
+    mbc.write_register(0x2000, 0x01);
+    assert_eq!(mbc.read_rom(0x4000), 0x08);
+
+    // Modo 7 dos dados (Digimon): troca os bits 2 e 5
+    mbc.write_register(0x2001, 0x07);
+    mbc.write_register(0x2000, 0x01);
+    assert_eq!(mbc.read_rom(0x4001), 0x24);
+    mbc.write_register(0x2001, 0x04);
+    mbc.write_register(0x2000, 0x01);
+    // Modo 4 (Garou): bit 1 <- 5, bit 2 <- 1, bit 5 <- 2
+    assert_eq!(mbc.read_rom(0x4001), 0x22);
+    // Banco 0 não é embaralhado
+    assert_eq!(mbc.read_rom(0x0001), 0x24);
+}
+
+#[test]
+fn test_hitek_tables() {
+    let mut rom = vec![0u8; 256 * 1024];
+    rom[0x02 * 0x4000] = 0x02;
+    rom[0x08 * 0x4000] = 0x08;
+    let mut mbc = Bbd::new(rom, 0, BbdVariant::Hitek);
+    // Modo 1 do banco inverte os bits 0-3
+    mbc.write_register(0x2080, 0x01);
+    mbc.write_register(0x2000, 0x01);
+    assert_eq!(mbc.read_rom(0x4000), 0x08);
+}
+
+#[test]
+fn test_unlicensed_names_and_save_state() {
+    use gb_emu::GB::savestate::{StateReader, StateWriter};
+
+    for mapper in Unlicensed::ALL {
+        assert_eq!(Unlicensed::from_name(mapper.name()), Some(mapper));
+    }
+    assert_eq!(Unlicensed::from_name("BBD"), Some(Unlicensed::Bbd));
+    assert_eq!(Unlicensed::from_name("mbc9"), None);
+
+    let mut mbc = WisdomTree::new(wisdom_tree_rom());
+    mbc.write_register(0x0002, 0x00);
+    let mut w = StateWriter::new();
+    mbc.save_state(&mut w);
+    let data = w.into_inner();
+    let mut other = WisdomTree::new(wisdom_tree_rom());
+    other.load_state(&mut StateReader::new(&data)).unwrap();
+    assert_eq!(other.read_rom(0x0000), 2);
+}